@@ -10,6 +10,13 @@
 //! 特别适用于数据库操作和动态类型处理。
 
 use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use field_access::FieldAccess;
+
+use super::helper::get_table_name;
 
 /// Trait for converting values to a specific type from a dynamic `Any` reference.
 /// 
@@ -109,6 +116,20 @@ pub trait ValueConvert
     /// assert_eq!(SomeType::is_default_value(&42), false);
     /// ```
     fn is_default_value(value: &Self) -> bool;
+
+    /// Returns SQL to place around the value's placeholder when it is written from an
+    /// entity, as `(prefix, suffix)`, e.g. `("ST_GeomFromText(", ", 4326)")`.
+    /// 
+    /// Most values are bound as a plain placeholder, which is the default (`None`).
+    /// 
+    /// 返回从实体写入该值时包裹在占位符两侧的 SQL，形式为 `(前缀, 后缀)`，
+    /// 例如 `("ST_GeomFromText(", ", 4326)")`。
+    /// 
+    /// 大多数值以普通占位符绑定，即默认值（`None`）。
+    fn placeholder_wrap(value: &Self) -> Option<(String, String)> {
+        let _ = value;
+        None
+    }
 }
 
 /// Helper function to recursively unwrap any number of Option layers
@@ -368,6 +389,346 @@ pub fn is_empty_or_none(value: &dyn Any) -> bool {
     false
 }
 
+type TextNormalizer = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+static TEXT_NORMALIZER: RwLock<Option<TextNormalizer>> = RwLock::new(None);
+
+thread_local! {
+    /// 由 `with_text_normalizer` 设置的当前线程范围内的文本规范化函数，优先于进程级设置
+    static SCOPED_TEXT_NORMALIZER: RefCell<Option<TextNormalizer>> = const { RefCell::new(None) };
+}
+
+/// Set a process-wide normalizer applied to text field values on insert/update.
+/// 
+/// Once set, `String` and `&str` fields (including inside `Option`) extracted from
+/// entities for INSERT, UPDATE and UPSERT statements pass through the normalizer before
+/// binding. Binary and non-text values are never touched, and values bound manually with
+/// `push_bind`, query-by-example filters and primary key values are not affected. To normalize only the statements
+/// built in one place, use `with_text_normalizer` instead.
+/// 
+/// # Arguments
+/// * `normalizer` - Function producing the value to bind, e.g. `|s| s.trim().to_string()`
+/// 
+/// 设置进程级的文本规范化函数，应用于插入/更新时的文本字段值。
+/// 
+/// 设置后，为 INSERT、UPDATE 和 UPSERT 语句从实体中提取的 `String` 和 `&str` 字段
+/// （包括 `Option` 内的值）在绑定前都会经过该函数处理。二进制及非文本值不受影响，
+/// 通过 `push_bind` 手动绑定的值、按示例查询的条件值以及主键值也不受影响。只需规范化某处构建的语句时，
+/// 应改用 `with_text_normalizer`。
+/// 
+/// # 参数
+/// * `normalizer` - 生成绑定值的函数，例如 `|s| s.trim().to_string()`
+pub fn set_text_normalizer(normalizer: impl Fn(&str) -> String + Send + Sync + 'static) {
+    let mut guard = TEXT_NORMALIZER.write().unwrap_or_else(|e| e.into_inner());
+    *guard = Some(Arc::new(normalizer));
+}
+
+/// Enable trimming of leading and trailing whitespace on text field values.
+/// 
+/// 启用文本字段值首尾空白的自动去除。
+pub fn enable_text_trim() {
+    set_text_normalizer(|s| s.trim().to_string());
+}
+
+/// Remove the text normalizer, binding text values unchanged.
+/// 
+/// 移除文本规范化函数，文本值按原样绑定。
+pub fn clear_text_normalizer() {
+    let mut guard = TEXT_NORMALIZER.write().unwrap_or_else(|e| e.into_inner());
+    *guard = None;
+}
+
+/// Build statements with a text normalizer that applies only within `build`.
+/// 
+/// Unlike `set_text_normalizer`, nothing process-wide changes: the normalizer is used for
+/// the entity values extracted by builders called inside `build` on the current thread, and
+/// takes precedence over the process-wide one. Builders extract values when they are
+/// created, so the returned statements keep the normalized values.
+/// 
+/// # Arguments
+/// * `normalizer` - Function producing the value to bind, e.g. `|s| s.trim().to_string()`
+/// * `build` - Closure creating the statements, e.g. `|| Insert::one(&entity, &PRIMARY_KEY)`
+/// 
+/// # Returns
+/// The value returned by `build`
+/// 
+/// 使用仅在 `build` 内生效的文本规范化函数构建语句。
+/// 
+/// 与 `set_text_normalizer` 不同，不会改变任何进程级设置：该函数只用于当前线程上 `build`
+/// 内调用的构建器所提取的实体值，并优先于进程级规范化函数。构建器在创建时提取值，
+/// 因此返回的语句保留规范化后的值。
+/// 
+/// # 参数
+/// * `normalizer` - 生成绑定值的函数，例如 `|s| s.trim().to_string()`
+/// * `build` - 创建语句的闭包，例如 `|| Insert::one(&entity, &PRIMARY_KEY)`
+/// 
+/// # 返回值
+/// `build` 的返回值
+pub fn with_text_normalizer<R>(
+    normalizer: impl Fn(&str) -> String + Send + Sync + 'static,
+    build: impl FnOnce() -> R,
+) -> R {
+    /// 离开作用域（包括 panic）时恢复之前的线程范围规范化函数
+    struct Restore(Option<TextNormalizer>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SCOPED_TEXT_NORMALIZER.with(|scoped| *scoped.borrow_mut() = previous);
+        }
+    }
+
+    let previous = SCOPED_TEXT_NORMALIZER.with(|scoped| scoped.replace(Some(Arc::new(normalizer))));
+    let _restore = Restore(previous);
+    build()
+}
+
+/// Convert an entity field value, applying the text normalizer to text values if
+/// `normalize` is set (values being written), then the column transformer registered
+/// for `(table, column)`. Filter and key values pass `false` and stay raw.
+/// 
+/// 转换实体字段值，`normalize` 为真（待写入的值）且设置了文本规范化函数时对文本值应用该函数，
+/// 随后应用为 `(table, column)` 注册的列转换器。过滤条件和键值传入 `false`，保持原值。
+pub(crate) fn convert_field<VAL: ValueConvert>(table: &str, column: &str, value: &dyn Any, normalize: bool) -> VAL {
+    let normalizer = if normalize {
+        SCOPED_TEXT_NORMALIZER.with(|scoped| scoped.borrow().clone())
+            .or_else(|| TEXT_NORMALIZER.read().unwrap_or_else(|e| e.into_inner()).clone())
+    } else {
+        None
+    };
+    let transformer = column_transformer(table, column);
+    if normalizer.is_none() && transformer.is_none() {
+        return VAL::convert(value);
+    }
+
+    let text = unwrap_option::<String>(value)
+        .map(String::as_str)
+        .or_else(|| unwrap_option::<&str>(value).copied());
+    let Some(text) = text else {
+        return VAL::convert(value);
+    };
+    let mut text = match normalizer {
+        Some(normalize) => normalize(text),
+        None => text.to_string(),
+    };
+    if let Some(transformer) = transformer {
+        text = transformer.encode(&text);
+    }
+    VAL::convert(&text)
+}
+
+/// Transformer applied to a single column's text values, e.g. field-level encryption
+/// or JSON normalization.
+/// 
+/// `encode` runs on values extracted from entities for INSERT, UPDATE and UPSERT
+/// statements. `decode` is the optional read side used by `decode_columns`; it returns
+/// `None` by default, which leaves fetched values untouched, so a transformer is only
+/// reversible if it implements `decode`.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use kitx::common::conversion::{register_column_transformer, ColumnTransformer};
+/// 
+/// struct Reverse;
+/// 
+/// impl ColumnTransformer for Reverse {
+///     fn encode(&self, value: &str) -> String { value.chars().rev().collect() }
+///     fn decode(&self, value: &str) -> Option<String> { Some(value.chars().rev().collect()) }
+/// }
+/// 
+/// register_column_transformer("user", "secret", Reverse);
+/// ```
+/// 
+/// 应用于单列文本值的转换器，例如字段级加密或 JSON 规范化。
+/// 
+/// `encode` 作用于为 INSERT、UPDATE 和 UPSERT 语句从实体中提取的值。`decode` 是可选的读取端，
+/// 由 `decode_columns` 使用；默认返回 `None`，即读取的值保持不变，因此只有实现了 `decode`
+/// 的转换器才是可逆的。
+pub trait ColumnTransformer: Send + Sync {
+    /// Transforms a value before it is bound.
+    /// 
+    /// 在绑定前转换值。
+    fn encode(&self, value: &str) -> String;
+
+    /// Restores a stored value, or `None` to leave it unchanged.
+    /// 
+    /// 还原存储的值，返回 `None` 时保持不变。
+    fn decode(&self, value: &str) -> Option<String> {
+        let _ = value;
+        None
+    }
+}
+
+type ColumnTransformers = HashMap<(String, String), Arc<dyn ColumnTransformer>>;
+
+static COLUMN_TRANSFORMERS: RwLock<Option<ColumnTransformers>> = RwLock::new(None);
+
+/// Register a transformer for the text values of one column.
+/// 
+/// Columns are matched by the entity's table name (see `get_table_name`) and field name.
+/// Registering again for the same column replaces the previous transformer. Values bound
+/// manually with `push_bind` are not affected.
+/// 
+/// # Arguments
+/// * `table` - Table name
+/// * `column` - Column name
+/// * `transformer` - Transformer to apply
+/// 
+/// 为某一列的文本值注册转换器。
+/// 
+/// 列按实体的表名（参见 `get_table_name`）和字段名匹配。对同一列再次注册会替换之前的转换器。
+/// 通过 `push_bind` 手动绑定的值不受影响。
+/// 
+/// # 参数
+/// * `table` - 表名
+/// * `column` - 列名
+/// * `transformer` - 要应用的转换器
+pub fn register_column_transformer(table: &str, column: &str, transformer: impl ColumnTransformer + 'static) {
+    let mut guard = COLUMN_TRANSFORMERS.write().unwrap_or_else(|e| e.into_inner());
+    guard.get_or_insert_with(Default::default)
+        .insert((table.to_string(), column.to_string()), Arc::new(transformer));
+}
+
+/// Remove all registered column transformers.
+/// 
+/// 移除所有已注册的列转换器。
+pub fn clear_column_transformers() {
+    let mut guard = COLUMN_TRANSFORMERS.write().unwrap_or_else(|e| e.into_inner());
+    *guard = None;
+}
+
+fn column_transformer(table: &str, column: &str) -> Option<Arc<dyn ColumnTransformer>> {
+    let guard = COLUMN_TRANSFORMERS.read().unwrap_or_else(|e| e.into_inner());
+    guard.as_ref()?
+        .get(&(table.to_string(), column.to_string()))
+        .cloned()
+}
+
+/// Apply the read side of registered column transformers to a fetched entity.
+/// 
+/// Only `String` and `Option<String>` fields with a registered transformer are changed,
+/// and only when its `decode` returns a value.
+/// 
+/// # Arguments
+/// * `entity` - Entity fetched from the database
+/// 
+/// 对读取到的实体应用已注册列转换器的读取端。
+/// 
+/// 只有注册了转换器的 `String` 和 `Option<String>` 字段会被修改，且仅在其 `decode` 返回值时修改。
+/// 
+/// # 参数
+/// * `entity` - 从数据库读取的实体
+pub fn decode_columns<ET: FieldAccess>(entity: &mut ET) {
+    let table = get_table_name::<ET>();
+    for column in entity.field_names() {
+        let Some(transformer) = column_transformer(&table, column) else {
+            continue;
+        };
+        let Some(value) = entity.field_as_any_mut(column) else {
+            continue;
+        };
+        let text = match value.downcast_mut::<String>() {
+            Some(text) => Some(text),
+            None => value.downcast_mut::<Option<String>>().and_then(Option::as_mut),
+        };
+        let Some(text) = text else {
+            continue;
+        };
+        if let Some(decoded) = transformer.decode(text) {
+            *text = decoded;
+        }
+    }
+}
+
+#[cfg(feature = "json-fallback")]
+type JsonSerializer = fn(&dyn Any) -> Option<serde_json::Value>;
+
+#[cfg(feature = "json-fallback")]
+static JSON_TYPES: RwLock<Option<std::collections::HashMap<std::any::TypeId, JsonSerializer>>> = RwLock::new(None);
+
+/// Register a `Serialize` type to be stored as JSON when it has no `DataKind` mapping.
+/// 
+/// `ValueConvert::convert` only recognises a fixed set of types and turns anything else
+/// into `Null`. Field types registered here (and `Option`s of them) are serialized with
+/// serde and bound as `DataKind::Json` instead, so they can be written to JSON columns.
+/// Requires the `json-fallback` feature.
+/// 
+/// # Type Parameters
+/// * `T` - The field type to store as JSON
+/// 
+/// 注册在没有 `DataKind` 映射时以 JSON 存储的 `Serialize` 类型。
+/// 
+/// `ValueConvert::convert` 只识别固定的一组类型，其余类型会被转换为 `Null`。
+/// 在此注册的字段类型（及其 `Option`）会通过 serde 序列化并以 `DataKind::Json` 绑定，
+/// 从而可以写入 JSON 列。需要启用 `json-fallback` 特性。
+/// 
+/// # 类型参数
+/// * `T` - 以 JSON 存储的字段类型
+#[cfg(feature = "json-fallback")]
+pub fn register_json_type<T: serde::Serialize + 'static>() {
+    let mut guard = JSON_TYPES.write().unwrap_or_else(|e| e.into_inner());
+    let types = guard.get_or_insert_with(Default::default);
+    types.insert(std::any::TypeId::of::<T>(), |value| {
+        value.downcast_ref::<T>().and_then(|v| serde_json::to_value(v).ok())
+    });
+    types.insert(std::any::TypeId::of::<Option<T>>(), |value| {
+        value.downcast_ref::<Option<T>>()
+            .and_then(|v| v.as_ref())
+            .and_then(|v| serde_json::to_value(v).ok())
+    });
+}
+
+/// Serialize a value of a registered type to JSON, if its type was registered.
+/// 
+/// 如果值的类型已注册，则将其序列化为 JSON。
+#[cfg(feature = "json-fallback")]
+pub(crate) fn json_fallback(value: &dyn Any) -> Option<serde_json::Value> {
+    let guard = JSON_TYPES.read().unwrap_or_else(|e| e.into_inner());
+    let serialize = guard.as_ref()?.get(&value.type_id())?;
+    serialize(value)
+}
+
+/// Trait for enums stored as integer discriminants (e.g. SMALLINT / INT status columns).
+/// 
+/// Each backend's `DataKind` implements `From<E>` for every `E: DbEnumInt`, so an enum
+/// value can be bound directly with `push_bind(DataKind::from(Status::Active))` and is
+/// sent to the database as its `i32` discriminant.
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use kitx::common::conversion::DbEnumInt;
+/// 
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Status { Draft = 0, Active = 1 }
+/// 
+/// impl DbEnumInt for Status {
+///     fn to_i32(&self) -> i32 { *self as i32 }
+///     fn from_i32(value: i32) -> Option<Self> {
+///         match value {
+///             0 => Some(Status::Draft),
+///             1 => Some(Status::Active),
+///             _ => None,
+///         }
+///     }
+/// }
+/// ```
+/// 
+/// 以整数判别值存储的枚举的 trait（例如 SMALLINT / INT 状态列）。
+/// 
+/// 各数据库的 `DataKind` 为所有 `E: DbEnumInt` 实现了 `From<E>`，因此枚举值可以直接通过
+/// `push_bind(DataKind::from(Status::Active))` 绑定，并以其 `i32` 判别值发送到数据库。
+pub trait DbEnumInt: Sized {
+    /// Returns the integer stored in the database for this variant.
+    /// 
+    /// 返回该变体在数据库中存储的整数。
+    fn to_i32(&self) -> i32;
+
+    /// Maps a stored integer back to a variant, or `None` if it is unknown.
+    /// 
+    /// 将存储的整数映射回变体，未知值返回 `None`。
+    fn from_i32(value: i32) -> Option<Self>;
+}
 
 #[cfg(test)]
 mod tests {
@@ -432,4 +793,4 @@ mod tests {
         assert!(!is_empty_or_none(&filled_slice)); // Non-empty slice should be false       
     }
     
-}
\ No newline at end of file
+}
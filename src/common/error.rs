@@ -41,9 +41,10 @@ use sqlx::Error as SqlxError;
 /// let error = KitxError::new("数据库连接失败".to_string());
 /// println!("错误: {}", error);
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct KitxError {
     message: String,
+    query: Option<QueryError>,
 }
 
 /// Query-specific error types for database operations.
@@ -61,6 +62,18 @@ pub struct KitxError {
 /// - `NoEntitiesProvided`: No entities provided for batch operations
 /// - `ValueInvalid`: Invalid value for a specific column
 /// - `DuplicateWhereClause`: Duplicate WHERE clause detected
+/// - `PlaceholderMismatch`: Placeholder count does not match the number of bound values
+/// - `OrderByRequired`: ORDER BY clause is required for the operation
+/// - `CursorInvalid`: Opaque cursor token could not be decoded
+/// - `LockNotAvailable`: Row lock could not be acquired immediately (NOWAIT)
+/// - `WhereClauseRequired`: A WHERE condition is required to avoid writing to every row
+/// - `SubqueryColumnCount`: IN subquery must project exactly one column
+/// - `InvalidIdentifier`: Table or column name is not a plain identifier
+/// - `GroupByAfterHaving`: GROUP BY added after the HAVING clause was written
+/// - `DistinctOnOrder`: DISTINCT ON columns do not lead the ORDER BY
+/// - `CursorOrderConflict`: Existing ORDER BY conflicts with the cursor ordering
+/// - `ClauseAfterFrom`: A projection method called after the FROM clause was generated
+/// - `UnsupportedSyntax`: SQL syntax not supported by the current database
 /// - `Other`: Generic error with custom message
 /// 
 /// 数据库操作的查询特定错误类型。
@@ -77,8 +90,20 @@ pub struct KitxError {
 /// - `NoEntitiesProvided`: 批量操作未提供实体
 /// - `ValueInvalid`: 特定列的值无效
 /// - `DuplicateWhereClause`: 检测到重复的WHERE子句
+/// - `PlaceholderMismatch`: 占位符数量与绑定值数量不匹配
+/// - `OrderByRequired`: 操作需要 ORDER BY 子句
+/// - `CursorInvalid`: 无法解码不透明游标令牌
+/// - `LockNotAvailable`: 无法立即获取行锁（NOWAIT）
+/// - `WhereClauseRequired`: 需要 WHERE 条件以避免写入全表
+/// - `SubqueryColumnCount`: IN 子查询必须只投影一列
+/// - `InvalidIdentifier`: 表名或列名不是普通标识符
+/// - `GroupByAfterHaving`: 在已写入 HAVING 子句之后添加 GROUP BY
+/// - `DistinctOnOrder`: DISTINCT ON 的列不是 ORDER BY 的前导列
+/// - `CursorOrderConflict`: 已有的 ORDER BY 与游标排序冲突
+/// - `ClauseAfterFrom`: 在已生成 FROM 子句之后调用设置查询列的方法
+/// - `UnsupportedSyntax`: 当前数据库不支持的 SQL 语法
 /// - `Other`: 带有自定义消息的通用错误
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum QueryError {
     /// Database pool is not initialized / 数据库连接池未初始化
     DBPoolNotInitialized,
@@ -96,6 +121,30 @@ pub enum QueryError {
     ValueInvalid(String),
     /// Duplicate WHERE clause detected / 检测到重复的WHERE子句
     DuplicateWhereClause,
+    /// Placeholder count does not match bound values (placeholders, values) / 占位符数量与绑定值数量不匹配（占位符数, 值数）
+    PlaceholderMismatch(usize, usize),
+    /// ORDER BY clause is required / 需要 ORDER BY 子句
+    OrderByRequired,
+    /// Cursor token could not be decoded / 游标令牌无法解码
+    CursorInvalid(String),
+    /// Row lock not available with NOWAIT / NOWAIT 模式下行锁不可用
+    LockNotAvailable,
+    /// WHERE condition is required for the write / 写操作需要 WHERE 条件
+    WhereClauseRequired,
+    /// IN subquery does not project exactly one column / IN 子查询投影的列数不为一
+    SubqueryColumnCount(usize),
+    /// Identifier rejected in strict mode (identifier) / 严格模式下被拒绝的标识符（标识符）
+    InvalidIdentifier(String),
+    /// GROUP BY added after HAVING / 在 HAVING 之后添加 GROUP BY
+    GroupByAfterHaving,
+    /// ORDER BY item not among the leading DISTINCT ON columns (item) / ORDER BY 中不属于 DISTINCT ON 前导列的排序项（排序项）
+    DistinctOnOrder(String),
+    /// Existing ORDER BY item conflicting with the cursor ordering / 已有的 ORDER BY 中与游标排序冲突的项
+    CursorOrderConflict(String),
+    /// Projection method called after FROM was generated (method) / 在生成 FROM 之后调用的设置查询列的方法（方法）
+    ClauseAfterFrom(String),
+    /// SQL syntax not supported by the current database (syntax) / 当前数据库不支持的 SQL 语法（语法）
+    UnsupportedSyntax(String),
     /// Generic error with custom message / 带有自定义消息的通用错误
     Other(String),
 }
@@ -166,9 +215,58 @@ impl QueryError {
             Self::ColumnsListEmpty => "No valid fields provided".to_string(),
             Self::NoEntitiesProvided => "No entities provided".to_string(),
             Self::DuplicateWhereClause => "Duplicate WHERE clause".to_string(),
+            Self::PlaceholderMismatch(placeholders, values) => format!("Expected {} bound values for the placeholders, got {}", placeholders, values),
+            Self::OrderByRequired => "ORDER BY clause is required".to_string(),
+            Self::CursorInvalid(reason) => format!("Invalid cursor: {}", reason),
+            Self::LockNotAvailable => "Row lock not available".to_string(),
+            Self::WhereClauseRequired => "A WHERE condition is required to avoid a full-table write".to_string(),
+            Self::SubqueryColumnCount(count) => format!("IN subquery must select exactly one column, got {}", count),
+            Self::InvalidIdentifier(name) => format!("Invalid identifier: {}", name),
+            Self::GroupByAfterHaving => "GROUP BY must come before HAVING".to_string(),
+            Self::DistinctOnOrder(item) => format!("DISTINCT ON columns must lead the ORDER BY, got {}", item),
+            Self::CursorOrderConflict(item) => format!("ORDER BY conflicts with the cursor ordering at {}", item),
+            Self::ClauseAfterFrom(method) => format!("{} must be called before the FROM clause is generated", method),
+            Self::UnsupportedSyntax(syntax) => format!("{} is not supported by this database", syntax),
             Self::Other(msg) => msg.to_owned(),
         }
     }
+
+    /// Checks whether a sqlx error was produced from this query error.
+    /// 
+    /// Query errors are carried inside `sqlx::Error::Database` as a `KitxError`
+    /// that keeps the original variant, so this compares the variant and its values
+    /// rather than the rendered message.
+    /// 
+    /// # Arguments
+    /// * `err` - The sqlx error to check
+    /// 
+    /// # Returns
+    /// `true` if `err` wraps this query error.
+    /// 
+    /// # Examples
+    /// ```rust
+    /// use kitx::common::error::QueryError;
+    /// 
+    /// let err: sqlx::Error = QueryError::LockNotAvailable.into();
+    /// assert!(QueryError::LockNotAvailable.matches(&err));
+    /// ```
+    /// 
+    /// 检查 sqlx 错误是否由该查询错误产生。
+    /// 
+    /// 查询错误以 `KitxError` 的形式包装在 `sqlx::Error::Database` 中，并保留原始变体，
+    /// 因此该方法比较变体及其携带的值，而不是比较渲染后的消息。
+    /// 
+    /// # 参数
+    /// * `err` - 要检查的 sqlx 错误
+    /// 
+    /// # 返回值
+    /// 如果 `err` 包装了该查询错误则返回 `true`。
+    pub fn matches(&self, err: &SqlxError) -> bool {
+        err.as_database_error()
+            .and_then(|e| e.try_downcast_ref::<KitxError>())
+            .and_then(|e| e.query.as_ref())
+            .is_some_and(|query| query == self)
+    }
 }
 
 impl RelationError {
@@ -231,7 +329,7 @@ impl KitxError {
     /// # 参数
     /// * `message` - 错误描述信息
     pub fn new(message: String) -> Self {
-        KitxError { message }
+        KitxError { message, query: None }
     }
 }
 
@@ -259,7 +357,7 @@ impl From<QueryError> for KitxError {
     /// # 返回值
     /// 带有来自QueryError的错误消息的新KitxError实例。
     fn from(err: QueryError) -> Self {
-        KitxError {  message: err.message() }
+        KitxError { message: err.message(), query: Some(err) }
     }
 }
 
@@ -286,7 +384,7 @@ impl From<QueryError> for SqlxError {
     /// # 返回值
     /// 包含封装在KitxError中的QueryError的SqlxError。
     fn from(err: QueryError) -> Self {
-        SqlxError::Database(Box::new(KitxError {  message: err.message() }))
+        SqlxError::Database(Box::new(KitxError::from(err)))
     }
 }
 
@@ -313,7 +411,7 @@ impl From<RelationError> for SqlxError {
     /// # 返回值
     /// 包含封装在KitxError中的RelationError的SqlxError。
     fn from(err: RelationError) -> Self {
-        SqlxError::Database(Box::new(KitxError { message: err.message() }))
+        SqlxError::Database(Box::new(KitxError::new(err.message())))
     }
 }
 
@@ -12,8 +12,12 @@
 //! 这些工具对于构建动态SQL查询和参数绑定至关重要。
 
 use field_access::{FieldAccess, Fields};
+#[cfg(any(feature = "sqlite" , feature = "postgres"))]
+use sqlx::Error;
 
-use super::conversion::{ValueConvert, is_empty_or_none};
+use super::{conversion::{ValueConvert, convert_field, is_empty_or_none}, helper::{get_table_name, is_generated_column}, types::PrimaryKey};
+#[cfg(any(feature = "sqlite" , feature = "postgres"))]
+use super::error::QueryError;
 
 /// Extract all fields and values from a struct.
 /// 
@@ -76,7 +80,7 @@ where
 
     for (name, field) in fields {
         cols_names.push(name);
-        cols_values.push(VAL::convert(field.as_any()));
+        cols_values.push(convert_field("", name, field.as_any(), false));
     }
     (cols_names, cols_values)
 }
@@ -160,14 +164,41 @@ pub fn extract_with_bind<VAL, F>(
     mut bind_fn: F
 ) -> (Vec<&'static str>, Vec<VAL>)
 where
-    VAL: ValueConvert,
+    VAL: ValueConvert + Clone,
     F: FnMut(&str, VAL)
+{
+    let (names, values) = extract_table_fields::<VAL>("", fields, filter_columns, skip_non_null, None);
+    for (name, value) in names.iter().zip(&values) {
+        bind_fn(name, value.clone());
+    }
+    (names, values)
+}
+
+/// `extract_with_filter` for the fields of an entity of `table`, applying the column
+/// transformers registered for that table and skipping its generated columns. Each value
+/// is converted once, so a transformer's `encode` runs once per field.
+/// `write_keys` is `Some` when the values are written: the text normalizer then applies
+/// to every column except these key columns, which stay raw to match the WHERE clause.
+/// 
+/// 针对 `table` 实体字段的 `extract_with_filter`，会应用为该表注册的列转换器并跳过其生成列。
+/// 每个值只转换一次，因此列转换器的 `encode` 对每个字段只执行一次。
+/// 值将被写入时 `write_keys` 为 `Some`：此时文本规范化函数应用于这些键列以外的所有列，
+/// 键列保持原值以与 WHERE 条件一致。
+pub(crate) fn extract_table_fields<VAL>(
+    table: &str,
+    fields: Fields,
+    filter_columns: &[&str],
+    skip_non_null: bool,
+    write_keys: Option<&[&str]>,
+) -> (Vec<&'static str>, Vec<VAL>)
+where
+    VAL: ValueConvert,
 {
     let mut cols_names = Vec::new();
     let mut cols_values = Vec::new();
 
     for (name, field) in fields {
-        if filter_columns.contains(&name) {
+        if filter_columns.contains(&name) || is_generated_column(table, name) {
             continue;
         }
 
@@ -175,9 +206,9 @@ where
         if skip_non_null && is_empty_or_none(any_value) {
             continue;
         }
+        let normalize = write_keys.is_some_and(|keys| !keys.contains(&name));
         cols_names.push(name);
-        cols_values.push(VAL::convert(any_value));        
-        bind_fn(name, VAL::convert(any_value));
+        cols_values.push(convert_field(table, name, any_value, normalize));
     }
     (cols_names, cols_values)
 }
@@ -252,24 +283,22 @@ pub fn extract_with_filter<VAL>(
 where
     VAL: ValueConvert,
 {
-    extract_with_bind::<VAL, _>(
-        fields,
-        filter_columns,
-        skip_non_null,
-        |_, _|{}
-    )
+    extract_table_fields("", fields, filter_columns, skip_non_null, None)
 }
 
 /// Extract field data from multiple entities for batch operations.
 /// 
 /// This function processes multiple entities and extracts their field data
 /// in a format suitable for batch database operations like bulk inserts.
-/// All entities are expected to have the same field structure, and the
-/// field names are extracted from the first entity.
+/// The column list is fixed once, in the order columns are first seen across
+/// the entities, and every entity's values are emitted in exactly that order,
+/// so rows stay aligned even if `fields()` iterates differently per entity or
+/// `skip_non_null` skips different columns. A column one entity lacks is filled
+/// with the default value.
 /// 
 /// # Type Parameters
 /// * `ET` - The entity type that implements `FieldAccess`
-/// * `VAL` - The target value type that implements `ValueConvert`
+/// * `VAL` - The target value type that implements `ValueConvert + Default`
 /// 
 /// # Arguments
 /// * `entities` - Slice of entity references to process
@@ -278,8 +307,8 @@ where
 /// 
 /// # Returns
 /// A tuple containing:
-/// * `Vec<&'static str>` - Vector of field names (from first entity)
-/// * `Vec<Vec<VAL>>` - Vector of value vectors, one per entity
+/// * `Vec<&'static str>` - Vector of field names shared by all rows
+/// * `Vec<Vec<VAL>>` - Vector of value vectors, one per entity, aligned with the field names
 /// 
 /// # Examples
 /// ```rust
@@ -299,11 +328,13 @@ where
 /// 从多个实体中提取字段数据用于批量操作。
 /// 
 /// 此函数处理多个实体并提取它们的字段数据，格式适用于批量数据库操作如批量插入。
-/// 所有实体预期具有相同的字段结构，字段名从第一个实体中提取。
+/// 列列表只确定一次，按各实体中首次出现的顺序排列，每个实体的值都严格按该顺序输出，
+/// 因此即使各实体的 `fields()` 迭代顺序不同，或 `skip_non_null` 跳过的列不同，行也保持对齐。
+/// 某实体缺少的列以默认值填充。
 /// 
 /// # 类型参数
 /// * `ET` - 实现了 `FieldAccess` 的实体类型
-/// * `VAL` - 实现了 `ValueConvert` 的目标值类型
+/// * `VAL` - 实现了 `ValueConvert + Default` 的目标值类型
 /// 
 /// # 参数
 /// * `entities` - 要处理的实体引用切片
@@ -312,8 +343,8 @@ where
 /// 
 /// # 返回值
 /// 包含以下内容的元组：
-/// * `Vec<&'static str>` - 字段名向量（来自第一个实体）
-/// * `Vec<Vec<VAL>>` - 值向量的向量，每个实体一个
+/// * `Vec<&'static str>` - 所有行共用的字段名向量
+/// * `Vec<Vec<VAL>>` - 值向量的向量，每个实体一个，与字段名对齐
 /// 
 /// # 示例
 /// ```rust
@@ -336,20 +367,27 @@ pub fn batch_extract<ET, VAL>(
 ) -> (Vec<&'static str>, Vec<Vec<VAL>>)
 where 
     ET: FieldAccess,
-    VAL: ValueConvert,
+    VAL: ValueConvert + Default,
 {
-    let mut entities_names = Vec::new();
-    let mut entities_values = Vec::with_capacity(entities.len());
-
+    let table = get_table_name::<ET>();
+    let mut entities_names: Vec<&'static str> = Vec::new();
     for entity in entities {
-        let value = extract_with_filter::<VAL>(entity.fields(), filter_columns, skip_non_null);
-        let (names, values) = value;
-        if entities_names.is_empty() {
-            entities_names = names;
+        for (name, field) in entity.fields() {
+            if entities_names.contains(&name)
+                || filter_columns.contains(&name)
+                || is_generated_column(&table, name)
+                || (skip_non_null && is_empty_or_none(field.as_any()))
+            {
+                continue;
+            }
+            entities_names.push(name);
         }
-        entities_values.push(values);
     }
 
+    let entities_values = entities.iter()
+        .map(|entity| get_write_values::<ET, VAL>(*entity, &entities_names))
+        .collect();
+
     (entities_names, entities_values)
 }
 
@@ -427,6 +465,28 @@ where
     values
 }
 
+/// `get_values` for values about to be written, applying the text normalizer and the
+/// column transformers registered for the entity's table.
+/// 
+/// 用于待写入值的 `get_values`，会应用文本规范化函数及为实体表注册的列转换器。
+pub(crate) fn get_write_values<ET, VAL>(
+    entity: &ET, 
+    columns: &[&str]
+) -> Vec<VAL>
+where
+    ET: FieldAccess,
+    VAL: ValueConvert + Default,
+{
+    let table = get_table_name::<ET>();
+    columns.iter()
+        .map(|col| {
+            entity.field_as_any(col)
+                .map(|value| convert_field(&table, col, value, true))
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
 /// Get a single value for a specific column from an entity.
 /// 
 /// This function extracts the value for a single column name from an entity.
@@ -495,3 +555,55 @@ where
         .map(|value| VAL::convert(value))
         .unwrap_or_default()
 }
+
+/// Check whether an entity's primary key still holds default values.
+/// 
+/// Every key column must be default (e.g. `0` or empty) for the entity to count as
+/// not yet persisted, which is how `save` decides between insert and update.
+/// 
+/// # Arguments
+/// * `entity` - Reference to the entity to inspect
+/// * `primary_key` - Primary key definition
+/// 
+/// # Returns
+/// True if all primary key values are default.
+/// 
+/// 检查实体的主键是否仍为默认值。
+/// 
+/// 所有主键列均为默认值（如 `0` 或空）时，实体被视为尚未持久化，`save` 据此在插入和更新之间选择。
+/// 
+/// # 参数
+/// * `entity` - 要检查的实体引用
+/// * `primary_key` - 主键定义
+/// 
+/// # 返回值
+/// 所有主键值均为默认值时返回 true。
+pub fn is_default_pk<ET, VAL>(
+    entity: &ET,
+    primary_key: &PrimaryKey,
+) -> bool
+where
+    ET: FieldAccess,
+    VAL: ValueConvert + Default,
+{
+    primary_key.get_keys()
+        .iter()
+        .all(|key| VAL::is_default_value(&get_value::<ET, VAL>(entity, key)))
+}
+
+/// 校验各列均为实体字段：列为空时返回 `QueryError::ColumnsListEmpty`，
+/// 否则对第一个实体没有的字段返回 `QueryError::ValueInvalid`
+#[cfg(any(feature = "sqlite" , feature = "postgres"))]
+pub(crate) fn ensure_entity_columns<ET>(columns: &[&str]) -> Result<(), Error>
+where
+    ET: FieldAccess + Default,
+{
+    if columns.is_empty() {
+        return Err(QueryError::ColumnsListEmpty.into());
+    }
+    let entity = ET::default();
+    match columns.iter().find(|col| entity.field_as_any(col).is_none()) {
+        Some(col) => Err(QueryError::ValueInvalid(col.to_string()).into()),
+        None => Ok(()),
+    }
+}
@@ -11,10 +11,12 @@
 //! 包括主键处理、条件绑定功能。
 //! 这些函数设计用于与 sqlx QueryBuilder 配合使用，以构建安全高效的数据库查询。
 
+use std::any::{Any, TypeId};
+
 use field_access::FieldAccess;
-use sqlx::{Database, Encode, QueryBuilder, Type};
+use sqlx::{query_builder::Separated, Database, Encode, Error, QueryBuilder, Type};
 
-use crate::common::{conversion::ValueConvert, fields::get_value, types::{PrimaryKey}};
+use crate::common::{conversion::ValueConvert, error::QueryError, fields::{extract_with_filter, get_value}, types::{FilterTree, PrimaryKey}};
 
 /// Push a primary key and value condition binding to the query builder
 /// 
@@ -117,4 +119,868 @@ pub fn push_primary_key_conditions<'a, ET, DB, VAL>(
           .push(" = ")
           .push_bind(value);
     }
-}
\ No newline at end of file
+}
+
+/// Push "query by example" conditions built from an entity to the query builder
+/// 
+/// Every non-empty field of the example entity (see `is_empty_or_none`) produces a
+/// `col = ?` condition, and the conditions are joined with AND. Columns listed in `skip`
+/// are ignored, which is handy for fields such as numeric ids or flags whose zero value
+/// is not considered empty. When no field qualifies, `1 = 1` is pushed so the
+/// surrounding WHERE clause stays valid.
+/// 
+/// # Type Parameters
+/// * `ET` - The entity type that implements FieldAccess trait
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode, Type and ValueConvert traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `example` - Reference to the partially filled example entity
+/// * `skip` - Column names that should never take part in the conditions
+/// 
+/// # Returns
+/// The number of conditions pushed
+/// 
+/// 将基于示例实体的"按例查询"条件推送到查询构建器
+/// 
+/// 示例实体中每个非空字段（参见 `is_empty_or_none`）都会生成一个 `col = ?` 条件，
+/// 各条件之间以 AND 连接。`skip` 中列出的列会被忽略，适用于数值 id 或标志位等
+/// 零值不被视为空值的字段。若没有符合条件的字段，则推入 `1 = 1` 以保证 WHERE 子句有效。
+/// 
+/// # 类型参数
+/// * `ET` - 实现 FieldAccess trait 的实体类型
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode、Type 和 ValueConvert traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `example` - 部分填充的示例实体的引用
+/// * `skip` - 不参与条件构建的列名
+/// 
+/// # 返回值
+/// 推入的条件数量
+pub fn push_example_conditions<'a, ET, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    example: &ET,
+    skip: &[&str],
+) -> usize
+where
+    ET: FieldAccess,
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+{
+    let (names, values) = extract_with_filter::<VAL>(example.fields(), skip, true);
+    if names.is_empty() {
+        qb.push("1 = 1");
+        return 0;
+    }
+
+    let count = names.len();
+    for (i, (name, value)) in names.into_iter().zip(values).enumerate() {
+        if i > 0 {
+            qb.push(" AND ");
+        }
+        qb.push(name)
+          .push(" = ")
+          .push_bind(value);
+    }
+    count
+}
+
+
+/// Push a raw SQL fragment with its bound parameters to the query builder
+/// 
+/// Every `?` in `sql` is replaced by a bind of the next value, so the fragment stays
+/// parameterized on every backend (PostgreSQL receives `$n` placeholders numbered in
+/// sequence with the rest of the query). Use it as an escape hatch for vendor-specific
+/// predicates. Note that a literal `?` cannot be expressed in the fragment.
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `sql` - SQL fragment using `?` as placeholders
+/// * `values` - Values to bind, one per placeholder and in order
+/// 
+/// # Returns
+/// `Ok(())`, or `QueryError::PlaceholderMismatch` (nothing is pushed) when the counts differ
+/// 
+/// 将带绑定参数的原始 SQL 片段推送到查询构建器
+/// 
+/// `sql` 中的每个 `?` 会依次替换为下一个值的绑定，因此片段在所有数据库上都保持参数化
+/// （PostgreSQL 会得到与查询其余部分连续编号的 `$n` 占位符）。
+/// 可作为数据库特有谓词的通用出口。注意片段中无法表达字面量 `?`。
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `sql` - 使用 `?` 作为占位符的 SQL 片段
+/// * `values` - 要绑定的值，与占位符一一对应且顺序一致
+/// 
+/// # 返回值
+/// 成功返回 `Ok(())`；数量不一致时返回 `QueryError::PlaceholderMismatch`（不会推入任何内容）
+pub fn push_raw_bind<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    sql: &str,
+    values: Vec<VAL>,
+) -> Result<(), Error>
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + 'a,
+{
+    let placeholders = sql.matches('?').count();
+    if placeholders != values.len() {
+        return Err(QueryError::PlaceholderMismatch(placeholders, values.len()).into());
+    }
+
+    let mut segments = sql.split('?');
+    if let Some(first) = segments.next() {
+        qb.push(first);
+    }
+    for (segment, value) in segments.zip(values) {
+        qb.push_bind(value).push(segment);
+    }
+    Ok(())
+}
+
+/// Push a compiled filter tree to the query builder
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `tree` - Filter tree to compile, see `FilterTree::compile`
+/// 
+/// # Returns
+/// `Ok(())`, or `QueryError::PlaceholderMismatch` (nothing is pushed) when the leaves' placeholders and values differ
+/// 
+/// 将编译后的过滤条件树推送到查询构建器
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `tree` - 要编译的过滤条件树，参见 `FilterTree::compile`
+/// 
+/// # 返回值
+/// 成功返回 `Ok(())`；叶子的占位符与值数量不一致时返回 `QueryError::PlaceholderMismatch`（不会推入任何内容）
+pub fn push_filter_tree<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    tree: FilterTree<VAL>,
+) -> Result<(), Error>
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + 'a,
+{
+    let (sql, values) = tree.compile();
+    push_raw_bind(qb, &sql, values)
+}
+
+/// Push a bitwise AND expression `(column & ?)` to the query builder
+/// 
+/// The expression can be followed by any comparison, e.g. `qb.push(" <> ").push_bind(0)`.
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Bitmask column
+/// * `value` - Value to AND with the column
+/// 
+/// 推入按位与表达式 `(column & ?)` 到查询构建器
+/// 
+/// 表达式之后可以继续拼接任意比较，例如 `qb.push(" <> ").push_bind(0)`。
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 位掩码列
+/// * `value` - 与列进行按位与的值
+pub fn push_bit_and<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    value: VAL,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + 'a,
+{
+    qb.push("(").push(column).push(" & ").push_bind(value).push(")");
+}
+
+/// Push a bitwise OR expression `(column | ?)` to the query builder
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Bitmask column
+/// * `value` - Value to OR with the column
+/// 
+/// 推入按位或表达式 `(column | ?)` 到查询构建器
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 位掩码列
+/// * `value` - 与列进行按位或的值
+pub fn push_bit_or<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    value: VAL,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + 'a,
+{
+    qb.push("(").push(column).push(" | ").push_bind(value).push(")");
+}
+
+/// Push a range condition `column BETWEEN ? AND ?` to the query builder, binding both bounds
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Column to check
+/// * `low` - Lower bound, inclusive
+/// * `high` - Upper bound, inclusive
+/// 
+/// 推入范围条件 `column BETWEEN ? AND ?` 到查询构建器，两个边界都作为绑定值
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 要检查的列
+/// * `low` - 下界（包含）
+/// * `high` - 上界（包含）
+pub fn push_between<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    low: VAL,
+    high: VAL,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + 'a,
+{
+    qb.push(column).push(" BETWEEN ").push_bind(low).push(" AND ").push_bind(high);
+}
+
+/// Push a range condition `column BETWEEN low AND high` whose bounds are SQL expressions
+/// 
+/// The bounds are written as is and nothing is bound, so they can reference other columns,
+/// e.g. of a joined table: `push_between_expr(qb, "o.created_at", "p.starts_at", "p.ends_at")`.
+/// Never pass user input as a bound; use `push_between` for values.
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Column to check
+/// * `low` - Lower bound expression, inclusive
+/// * `high` - Upper bound expression, inclusive
+/// 
+/// 推入边界为 SQL 表达式的范围条件 `column BETWEEN low AND high`
+/// 
+/// 边界按原样写入且不绑定任何值，因此可以引用其他列，例如关联表的列：
+/// `push_between_expr(qb, "o.created_at", "p.starts_at", "p.ends_at")`。
+/// 切勿将用户输入作为边界传入，绑定值请使用 `push_between`。
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 要检查的列
+/// * `low` - 下界表达式（包含）
+/// * `high` - 上界表达式（包含）
+pub fn push_between_expr<DB: Database>(
+    qb: &mut QueryBuilder<'_, DB>,
+    column: &str,
+    low: impl Into<String>,
+    high: impl Into<String>,
+) {
+    qb.push(column).push(" BETWEEN ").push(low.into()).push(" AND ").push(high.into());
+}
+
+/// Push a case-insensitive pattern match to the query builder
+/// 
+/// On PostgreSQL this emits `column ILIKE ?`. `ILIKE` does not exist on the other databases,
+/// so there it maps to `LOWER(column) LIKE LOWER(?)`, which matches the same rows.
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Column to match
+/// * `pattern` - LIKE pattern, e.g. `"%rust%"`
+/// 
+/// 推入不区分大小写的模式匹配到查询构建器
+/// 
+/// 在 PostgreSQL 上生成 `column ILIKE ?`。其他数据库没有 `ILIKE`，
+/// 因此映射为 `LOWER(column) LIKE LOWER(?)`，匹配的行相同。
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 要匹配的列
+/// * `pattern` - LIKE 模式，例如 `"%rust%"`
+pub fn push_ilike<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    pattern: VAL,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + 'a,
+{
+    push_pattern_match(qb, column, pattern, false, true);
+}
+
+/// Push a negated pattern match `column NOT LIKE ?` to the query builder
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Column to match
+/// * `pattern` - LIKE pattern to exclude, e.g. `"%draft%"`
+/// 
+/// 推入取反的模式匹配 `column NOT LIKE ?` 到查询构建器
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 要匹配的列
+/// * `pattern` - 要排除的 LIKE 模式，例如 `"%draft%"`
+pub fn push_not_like<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    pattern: VAL,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + 'a,
+{
+    push_pattern_match(qb, column, pattern, true, false);
+}
+
+/// Push a negated case-insensitive pattern match to the query builder
+/// 
+/// Emits `column NOT ILIKE ?` on PostgreSQL and `LOWER(column) NOT LIKE LOWER(?)` elsewhere,
+/// see `push_ilike`.
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Column to match
+/// * `pattern` - LIKE pattern to exclude, e.g. `"%draft%"`
+/// 
+/// 推入取反的不区分大小写模式匹配到查询构建器
+/// 
+/// 在 PostgreSQL 上生成 `column NOT ILIKE ?`，其他数据库生成 `LOWER(column) NOT LIKE LOWER(?)`，
+/// 参见 `push_ilike`。
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 要匹配的列
+/// * `pattern` - 要排除的 LIKE 模式，例如 `"%draft%"`
+pub fn push_not_ilike<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    pattern: VAL,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + 'a,
+{
+    push_pattern_match(qb, column, pattern, true, true);
+}
+
+/// 推入 LIKE 类模式匹配，`not` 控制取反，`ignore_case` 控制是否不区分大小写
+fn push_pattern_match<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    pattern: VAL,
+    not: bool,
+    ignore_case: bool,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + 'a,
+{
+    let not = if not { " NOT" } else { "" };
+    if !ignore_case {
+        qb.push(column).push(not).push(" LIKE ").push_bind(pattern);
+    } else if DB::NAME == "PostgreSQL" {
+        qb.push(column).push(not).push(" ILIKE ").push_bind(pattern);
+    } else {
+        qb.push("LOWER(").push(column).push(")").push(not).push(" LIKE LOWER(").push_bind(pattern).push(")");
+    }
+}
+
+/// Push a condition checking that all bits of `mask` are set: `(column & ?) = ?`
+/// 
+/// The mask is bound twice, first for the AND and then for the comparison.
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode, Type and Clone traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Bitmask column
+/// * `mask` - Flags that must all be set
+/// 
+/// 推入检查 `mask` 所有位均已设置的条件：`(column & ?) = ?`
+/// 
+/// 掩码会绑定两次，先用于按位与，再用于比较。
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode、Type 和 Clone traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 位掩码列
+/// * `mask` - 必须全部设置的标志位
+pub fn push_has_flag<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    mask: VAL,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + Clone + 'a,
+{
+    push_bit_and(qb, column, mask.clone());
+    qb.push(" = ").push_bind(mask);
+}
+
+/// Push a conditional count aggregate with an alias to the query builder
+/// 
+/// On PostgreSQL this emits `COUNT(*) FILTER (WHERE condition) AS alias`; on other databases
+/// it emits the portable `COUNT(CASE WHEN condition THEN 1 END) AS alias`. Both return 0, not
+/// NULL, when no row matches. The condition uses `?` placeholders bound in order from `values`,
+/// as with `push_raw_bind`.
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `condition` - Condition with `?` placeholders, e.g. `"status = ?"`
+/// * `values` - Values for the placeholders, in order
+/// * `alias` - Column alias of the count
+/// 
+/// # Returns
+/// `Ok(())`, or `QueryError::PlaceholderMismatch` if the counts differ (nothing is pushed)
+/// 
+/// 推入带别名的条件计数聚合到查询构建器
+/// 
+/// 在 PostgreSQL 上生成 `COUNT(*) FILTER (WHERE condition) AS alias`；在其他数据库上生成
+/// 可移植的 `COUNT(CASE WHEN condition THEN 1 END) AS alias`。没有匹配行时两者都返回 0 而不是 NULL。
+/// 条件中的 `?` 占位符按顺序绑定 `values` 中的值，与 `push_raw_bind` 相同。
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `condition` - 带 `?` 占位符的条件，例如 `"status = ?"`
+/// * `values` - 占位符对应的值，按顺序排列
+/// * `alias` - 计数列的别名
+/// 
+/// # 返回值
+/// `Ok(())`；数量不一致时返回 `QueryError::PlaceholderMismatch`（不推入任何内容）
+pub fn push_count_filter<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    condition: &str,
+    values: Vec<VAL>,
+    alias: &str,
+) -> Result<(), Error>
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + 'a,
+{
+    let placeholders = condition.matches('?').count();
+    if placeholders != values.len() {
+        return Err(QueryError::PlaceholderMismatch(placeholders, values.len()).into());
+    }
+
+    if DB::NAME == "PostgreSQL" {
+        qb.push("COUNT(*) FILTER (WHERE ");
+        push_raw_bind(qb, condition, values)?;
+        qb.push(")");
+    } else {
+        qb.push("COUNT(CASE WHEN ");
+        push_raw_bind(qb, condition, values)?;
+        qb.push(" THEN 1 END)");
+    }
+    qb.push(" AS ").push(alias);
+    Ok(())
+}
+
+/// Push a string aggregate with an alias to the query builder, e.g. all tags of an article
+/// 
+/// Emits `STRING_AGG(column, ?)` on PostgreSQL and `GROUP_CONCAT(column, ?)` on SQLite, binding
+/// the separator. MySQL only accepts a literal after `SEPARATOR`, so there the separator is
+/// written as an escaped string: `GROUP_CONCAT(column SEPARATOR 'sep')`. On PostgreSQL a
+/// non-text column must be cast, e.g. `"id::text"`.
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Column or expression to concatenate
+/// * `separator` - Separator placed between the values
+/// * `alias` - Column alias of the aggregate
+/// 
+/// 推入带别名的字符串聚合到查询构建器，例如文章的所有标签
+/// 
+/// 在 PostgreSQL 上生成 `STRING_AGG(column, ?)`，在 SQLite 上生成 `GROUP_CONCAT(column, ?)`，
+/// 并绑定分隔符。MySQL 的 `SEPARATOR` 后只接受字面量，因此分隔符以转义后的字符串写入：
+/// `GROUP_CONCAT(column SEPARATOR 'sep')`。在 PostgreSQL 上非文本列需要转换，例如 `"id::text"`。
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 要拼接的列或表达式
+/// * `separator` - 值之间的分隔符
+/// * `alias` - 聚合列的别名
+pub fn push_group_concat<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    separator: &str,
+    alias: &str,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + From<String> + 'a,
+{
+    match DB::NAME {
+        "PostgreSQL" => {
+            qb.push("STRING_AGG(").push(column).push(", ")
+                .push_bind(VAL::from(separator.to_string()))
+                .push(")");
+        }
+        "MySQL" => {
+            let literal = separator.replace('\\', "\\\\").replace('\'', "''");
+            qb.push("GROUP_CONCAT(").push(column)
+                .push(format!(" SEPARATOR '{}')", literal));
+        }
+        _ => {
+            qb.push("GROUP_CONCAT(").push(column).push(", ")
+                .push_bind(VAL::from(separator.to_string()))
+                .push(")");
+        }
+    }
+    qb.push(" AS ").push(alias);
+}
+
+/// Number of values per `IN (...)` list when a large set is split into chunks
+/// 
+/// 大集合拆分时每个 `IN (...)` 列表的值数量
+const IN_CHUNK_SIZE: usize = 500;
+
+/// Push a membership condition for a large set of values, using the most efficient form
+/// for the database
+/// 
+/// On PostgreSQL, `i32`, `i64`, `String` and `Uuid` sets are bound as a single array
+/// parameter: `column = ANY(?)`. Otherwise the set is split into `IN` lists of 500 values
+/// joined with `OR`: `(column IN (?, ...) OR column IN (?, ...))`. An empty set pushes a
+/// condition that matches nothing.
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `T` - Element type of the set
+/// * `VAL` - The value type that implements Encode, Type and ValueConvert traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Column to match
+/// * `values` - Values to match against
+/// 
+/// 推入针对大量值集合的成员条件，按数据库选择最高效的形式
+/// 
+/// 在 PostgreSQL 上，`i32`、`i64`、`String` 和 `Uuid` 集合作为单个数组参数绑定：
+/// `column = ANY(?)`。其他情况下集合会被拆分为每组 500 个值的 `IN` 列表并以 `OR` 连接：
+/// `(column IN (?, ...) OR column IN (?, ...))`。空集合会推入不匹配任何行的条件。
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `T` - 集合的元素类型
+/// * `VAL` - 实现 Encode、Type 和 ValueConvert traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 要匹配的列
+/// * `values` - 要匹配的值
+pub fn push_in_large<'a, DB, T, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    values: Vec<T>,
+)
+where
+    DB: Database,
+    T: Any,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+{
+    if values.is_empty() {
+        qb.push("1 = 0");
+        return;
+    }
+
+    if DB::NAME == "PostgreSQL" && is_pg_array_element::<T>() {
+        qb.push(column).push(" = ANY(").push_bind(VAL::convert(&values)).push(")");
+        return;
+    }
+
+    qb.push("(");
+    for (i, chunk) in values.chunks(IN_CHUNK_SIZE).enumerate() {
+        if i > 0 {
+            qb.push(" OR ");
+        }
+        qb.push(column).push(" IN (");
+        let mut separated = qb.separated(", ");
+        for value in chunk {
+            separated.push_bind(VAL::convert(value));
+        }
+        qb.push(")");
+    }
+    qb.push(")");
+}
+
+/// Whether a set of `T` can be bound as a PostgreSQL array parameter
+/// 
+/// `T` 的集合是否可以作为 PostgreSQL 数组参数绑定
+fn is_pg_array_element<T: Any>() -> bool {
+    let id = TypeId::of::<T>();
+    id == TypeId::of::<i32>()
+        || id == TypeId::of::<i64>()
+        || id == TypeId::of::<String>()
+        || id == TypeId::of::<sqlx::types::Uuid>()
+}
+
+/// Push a PostGIS distance condition: rows whose geometry lies within `meters` of a point
+/// 
+/// Emits `ST_DWithin(column::geography, ST_GeomFromText(?, srid)::geography, ?)`, binding
+/// the point WKT and the distance. Casting to `geography` makes the distance meters for
+/// geographic SRIDs such as 4326. PostgreSQL with PostGIS only.
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode and Type traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Geometry column
+/// * `point_wkt` - Reference point as WKT, e.g. `"POINT(116.39 39.91)"`
+/// * `srid` - SRID of the reference point
+/// * `meters` - Maximum distance in meters
+/// 
+/// 推入 PostGIS 距离条件：几何值位于某点 `meters` 米范围内的行
+/// 
+/// 生成 `ST_DWithin(column::geography, ST_GeomFromText(?, srid)::geography, ?)`，绑定点的 WKT
+/// 和距离。转换为 `geography` 后，对于 4326 等地理 SRID，距离单位为米。仅适用于安装了
+/// PostGIS 的 PostgreSQL。
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode 和 Type traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 几何列
+/// * `point_wkt` - 参考点的 WKT，例如 `"POINT(116.39 39.91)"`
+/// * `srid` - 参考点的 SRID
+/// * `meters` - 最大距离（米）
+pub fn push_dwithin<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    point_wkt: &str,
+    srid: i32,
+    meters: f64,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + From<String> + From<f64> + 'a,
+{
+    qb.push("ST_DWithin(").push(column)
+        .push("::geography, ST_GeomFromText(").push_bind(VAL::from(point_wkt.to_string()))
+        .push(format!(", {})::geography, ", srid)).push_bind(VAL::from(meters))
+        .push(")");
+}
+
+/// Push an equality condition with an explicitly cast placeholder to the query builder
+/// 
+/// Emits `column = ?::sql_type` on PostgreSQL, for parameters whose type cannot be inferred,
+/// such as text bound against a `uuid` or `jsonb` column. Other databases get the standard
+/// `column = CAST(? AS sql_type)`. Exactly one value is bound.
+/// 
+/// # Type Parameters
+/// * `DB` - The database type that implements the Database trait
+/// * `VAL` - The value type that implements Encode, Type and ValueConvert traits
+/// 
+/// # Arguments
+/// * `qb` - Mutable reference to the QueryBuilder to modify
+/// * `column` - Column to compare
+/// * `value` - Value to bind
+/// * `sql_type` - SQL type to cast the placeholder to, e.g. `"uuid"`
+/// 
+/// 推入占位符带显式类型转换的相等条件到查询构建器
+/// 
+/// 在 PostgreSQL 上生成 `column = ?::sql_type`，用于无法推断类型的参数，
+/// 例如与 `uuid` 或 `jsonb` 列比较的文本。其他数据库生成标准的 `column = CAST(? AS sql_type)`。
+/// 只绑定一个值。
+/// 
+/// # 类型参数
+/// * `DB` - 实现 Database trait 的数据库类型
+/// * `VAL` - 实现 Encode、Type 和 ValueConvert traits 的值类型
+/// 
+/// # 参数
+/// * `qb` - 要修改的 QueryBuilder 的可变引用
+/// * `column` - 要比较的列
+/// * `value` - 要绑定的值
+/// * `sql_type` - 占位符要转换成的 SQL 类型，例如 `"uuid"`
+pub fn push_eq_cast<'a, DB, VAL>(
+    qb: &mut QueryBuilder<'a, DB>,
+    column: &str,
+    value: VAL,
+    sql_type: &str,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+{
+    qb.push(column).push(" = ");
+    push_cast_value_bind(qb, value, sql_type);
+}
+
+/// 返回将占位符转换为 `sql_type` 的前后缀：PostgreSQL 为 `?::type`，其他数据库为 `CAST(? AS type)`
+fn cast_affixes<DB: Database>(sql_type: &str) -> (&'static str, String) {
+    if DB::NAME == "PostgreSQL" {
+        ("", format!("::{}", sql_type))
+    } else {
+        ("CAST(", format!(" AS {})", sql_type))
+    }
+}
+
+/// 推入转换为 `sql_type` 的绑定值，参见 `push_eq_cast`
+pub(crate) fn push_cast_value_bind<'a, DB, VAL>(qb: &mut QueryBuilder<'a, DB>, value: VAL, sql_type: &str)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+{
+    let (prefix, suffix) = cast_affixes::<DB>(sql_type);
+    qb.push(prefix);
+    push_value_bind(qb, value);
+    qb.push(suffix);
+}
+
+/// 用于分隔列表的 `push_cast_value_bind`
+pub(crate) fn push_separated_cast_value_bind<'qb, 'a, DB, VAL>(
+    b: &mut Separated<'qb, 'a, DB, &'static str>,
+    value: VAL,
+    sql_type: &str,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+{
+    let (prefix, suffix) = cast_affixes::<DB>(sql_type);
+    b.push(prefix);
+    match VAL::placeholder_wrap(&value) {
+        Some((wrap_prefix, wrap_suffix)) => {
+            b.push_unseparated(wrap_prefix).push_bind_unseparated(value).push_unseparated(wrap_suffix);
+        }
+        None => {
+            b.push_bind_unseparated(value);
+        }
+    }
+    b.push_unseparated(suffix);
+}
+
+/// Push a bound value, wrapped in the SQL given by `ValueConvert::placeholder_wrap` if any
+/// 
+/// 推入绑定值；若 `ValueConvert::placeholder_wrap` 给出了包裹 SQL，则一并推入
+pub(crate) fn push_value_bind<'a, DB, VAL>(qb: &mut QueryBuilder<'a, DB>, value: VAL)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+{
+    match VAL::placeholder_wrap(&value) {
+        Some((prefix, suffix)) => {
+            qb.push(prefix).push_bind(value).push(suffix);
+        }
+        None => {
+            qb.push_bind(value);
+        }
+    }
+}
+
+/// `push_value_bind` for a separated list, such as a row of `push_values`
+/// 
+/// 用于分隔列表（例如 `push_values` 的一行）的 `push_value_bind`
+pub(crate) fn push_separated_value_bind<'qb, 'a, DB, VAL>(
+    b: &mut Separated<'qb, 'a, DB, &'static str>,
+    value: VAL,
+)
+where
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+{
+    match VAL::placeholder_wrap(&value) {
+        Some((prefix, suffix)) => {
+            b.push(prefix).push_bind_unseparated(value).push_unseparated(suffix);
+        }
+        None => {
+            b.push_bind(value);
+        }
+    }
+}
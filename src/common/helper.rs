@@ -8,7 +8,7 @@
 //! 该模块提供了支持数据库操作的实用函数和结构体，
 //! 包括类型名称转换和查询条件管理。
 
-use std::{any::type_name, marker::PhantomData, sync::Arc};
+use std::{any::type_name, collections::HashMap, marker::PhantomData, sync::{Arc, RwLock}};
 
 /// Returns the name of the given type
 /// 
@@ -57,6 +57,163 @@ pub fn get_table_name<T>() -> String {
     result
 }
 
+/// Checks whether a string is a plain SQL identifier
+/// 
+/// Uses the conservative pattern `[A-Za-z_][A-Za-z0-9_.]*`, which allows qualified names
+/// such as `article.id` but rejects quotes, whitespace, operators and comments. Table and
+/// column names are interpolated into SQL, so dynamic names should pass this check first.
+/// 
+/// # Arguments
+/// * `s` - Table or column name to check
+/// 
+/// # Returns
+/// True if `s` is a plain identifier
+/// 
+/// 检查字符串是否为普通 SQL 标识符
+/// 
+/// 使用保守的模式 `[A-Za-z_][A-Za-z0-9_.]*`，允许 `article.id` 这样的限定名，
+/// 但拒绝引号、空白、运算符和注释。表名和列名会被直接拼接到 SQL 中，因此动态名称应先通过此检查。
+/// 
+/// # 参数
+/// * `s` - 要检查的表名或列名
+/// 
+/// # 返回值
+/// `s` 为普通标识符时返回 true
+pub fn is_valid_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+static GENERATED_COLUMNS: RwLock<Option<HashMap<String, Vec<String>>>> = RwLock::new(None);
+
+/// Declares database-generated columns of a table (e.g. PostgreSQL `GENERATED ALWAYS AS (...) STORED`)
+/// 
+/// Declared columns are left out when values are extracted from entities, so INSERT,
+/// UPSERT (including the `DO UPDATE SET` list) and UPDATE statements never write them.
+/// Declaring again for the same table replaces the previous list.
+/// 
+/// # Arguments
+/// * `table` - Table name
+/// * `columns` - Generated column names
+/// 
+/// 声明表中由数据库生成的列（例如 PostgreSQL 的 `GENERATED ALWAYS AS (...) STORED`）
+/// 
+/// 从实体中提取值时会排除已声明的列，因此 INSERT、UPSERT（包括 `DO UPDATE SET` 列表）
+/// 和 UPDATE 语句都不会写入这些列。对同一表再次声明会替换之前的列表。
+/// 
+/// # 参数
+/// * `table` - 表名
+/// * `columns` - 生成列的列名
+pub fn register_generated_columns(table: &str, columns: &[&str]) {
+    let mut guard = GENERATED_COLUMNS.write().unwrap_or_else(|e| e.into_inner());
+    guard.get_or_insert_with(Default::default)
+        .insert(table.to_string(), columns.iter().map(|c| c.to_string()).collect());
+}
+
+/// Removes all generated column declarations
+/// 
+/// 移除所有生成列声明
+pub fn clear_generated_columns() {
+    let mut guard = GENERATED_COLUMNS.write().unwrap_or_else(|e| e.into_inner());
+    *guard = None;
+}
+
+/// Checks whether a column was declared as generated for a table
+/// 
+/// 检查某列是否被声明为表的生成列
+pub(crate) fn is_generated_column(table: &str, column: &str) -> bool {
+    let guard = GENERATED_COLUMNS.read().unwrap_or_else(|e| e.into_inner());
+    guard.as_ref()
+        .and_then(|tables| tables.get(table))
+        .is_some_and(|columns| columns.iter().any(|c| c == column))
+}
+
+/// Rewrites generated SQL right before it is executed
+/// 
+/// Registered interceptors run in registration order, each receiving the output of the
+/// previous one, and are applied by the query module executors that run a QueryBuilder
+/// (`execute`, `execute_with_trans`, `execute_all` and the `fetch_*` functions). Values stay
+/// bound to their placeholders, so a rewrite must keep every placeholder and their order
+/// intact (`?` on MySQL/SQLite, `$n` on PostgreSQL); it should only add text such as a
+/// schema prefix, a hint or a comment. Closures `Fn(String, &str) -> String` implement
+/// this trait.
+/// 
+/// 在执行前改写生成的 SQL
+/// 
+/// 已注册的拦截器按注册顺序执行，每个拦截器接收前一个的输出，由执行 QueryBuilder 的
+/// 查询模块函数（`execute`、`execute_with_trans`、`execute_all` 及 `fetch_*` 系列函数）调用。
+/// 绑定值仍对应原占位符，因此改写必须保持所有占位符及其顺序不变（MySQL/SQLite 为 `?`，
+/// PostgreSQL 为 `$n`），只应添加模式前缀、提示或注释之类的文本。
+/// 闭包 `Fn(String, &str) -> String` 实现了该 trait。
+pub trait QueryInterceptor: Send + Sync {
+    /// Rewrite the SQL of a query
+    /// 
+    /// # Arguments
+    /// * `sql` - SQL to execute, possibly already rewritten by earlier interceptors
+    /// * `backend` - Database name: `"SQLite"`, `"MySQL"` or `"PostgreSQL"`
+    /// 
+    /// # Returns
+    /// The SQL to execute
+    /// 
+    /// 改写查询的 SQL
+    /// 
+    /// # 参数
+    /// * `sql` - 要执行的 SQL，可能已被之前的拦截器改写
+    /// * `backend` - 数据库名称：`"SQLite"`、`"MySQL"` 或 `"PostgreSQL"`
+    /// 
+    /// # 返回值
+    /// 要执行的 SQL
+    fn rewrite(&self, sql: String, backend: &str) -> String;
+}
+
+impl<F> QueryInterceptor for F
+where
+    F: Fn(String, &str) -> String + Send + Sync,
+{
+    fn rewrite(&self, sql: String, backend: &str) -> String {
+        self(sql, backend)
+    }
+}
+
+static INTERCEPTORS: RwLock<Vec<Arc<dyn QueryInterceptor>>> = RwLock::new(Vec::new());
+
+/// Registers a query interceptor, run after those registered before it
+/// 
+/// # Arguments
+/// * `interceptor` - Interceptor to add
+/// 
+/// 注册查询拦截器，在之前注册的拦截器之后执行
+/// 
+/// # 参数
+/// * `interceptor` - 要添加的拦截器
+pub fn register_interceptor(interceptor: impl QueryInterceptor + 'static) {
+    let mut guard = INTERCEPTORS.write().unwrap_or_else(|e| e.into_inner());
+    guard.push(Arc::new(interceptor));
+}
+
+/// Removes all query interceptors
+/// 
+/// 移除所有查询拦截器
+pub fn clear_interceptors() {
+    let mut guard = INTERCEPTORS.write().unwrap_or_else(|e| e.into_inner());
+    guard.clear();
+}
+
+/// Applies the registered interceptors to a SQL string, `None` when none is registered
+/// 
+/// 对 SQL 应用已注册的拦截器，未注册任何拦截器时返回 `None`
+pub(crate) fn apply_interceptors(sql: &str, backend: &str) -> Option<String> {
+    let interceptors = INTERCEPTORS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if interceptors.is_empty() {
+        return None;
+    }
+    Some(interceptors.iter().fold(sql.to_string(), |sql, interceptor| interceptor.rewrite(sql, backend)))
+}
+
 /// A query condition wrapper for concurrent use
 /// 
 /// This struct wraps query condition closures to enable safe concurrent usage.
@@ -132,4 +289,14 @@ mod tests {
     fn test_get_type_name() {
         assert_eq!(get_table_name::<ArticleTag>(), "article_tag");
     }   
+
+    #[test]
+    fn test_is_valid_identifier() {
+        for name in ["id", "_tmp", "article.created_at", "Col9"] {
+            assert!(is_valid_identifier(name), "{}", name);
+        }
+        for name in ["", "9col", "id; DROP TABLE article", "name--", "a b", "`id`", "\"id\"", "id)", "views DESC"] {
+            assert!(!is_valid_identifier(name), "{}", name);
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,275 @@
+//! Batched reads for async handlers
+//!
+//! This module provides [BatchLoader], a DataLoader-style coalescer: individual `load(key)`
+//! calls made within the same scheduler tick are collected and served by a single batch query,
+//! typically `WHERE key IN (...)`, avoiding N+1 queries.
+//!
+//! 异步处理程序的批量读取
+//!
+//! 该模块提供 [BatchLoader]，一种 DataLoader 风格的合并器：同一调度周期内的多个 `load(key)`
+//! 调用会被收集起来，由一次批量查询（通常为 `WHERE key IN (...)`）统一完成，从而避免 N+1 查询。
+
+use std::{
+    borrow::Cow, collections::{HashMap, HashSet}, error::Error as StdError, fmt::{self, Display, Formatter},
+    future::Future, hash::Hash, io, pin::Pin, sync::{Arc, Mutex},
+};
+
+use sqlx::{error::{DatabaseError, ErrorKind}, Error};
+use tokio::sync::oneshot;
+
+use super::error::{KitxError, QueryError};
+
+type BatchFuture<T> = Pin<Box<dyn Future<Output = Result<Vec<T>, Error>> + Send>>;
+type Waiter<T> = oneshot::Sender<Result<Option<T>, Arc<Error>>>;
+type Pending<K, T> = Vec<(K, Waiter<T>)>;
+
+/// Coalesces individual key lookups into batch queries
+///
+/// The first `load` of a tick schedules a dispatch task; every `load` made before that task
+/// runs joins the same batch. The batch function receives the distinct keys and returns the
+/// matching rows, which are handed back to the callers by key. Cloning the loader shares the
+/// pending batch.
+///
+/// # Type Parameters
+/// * `K` - Key type, e.g. the primary key value
+/// * `T` - Row type
+///
+/// 将单个键查询合并为批量查询
+///
+/// 每个调度周期内的第一次 `load` 会安排一个分发任务，在该任务执行前发起的 `load` 都会加入同一批次。
+/// 批量函数接收去重后的键并返回匹配的行，这些行按键分发给各调用方。克隆的加载器共享待处理批次。
+///
+/// # 类型参数
+/// * `K` - 键类型，例如主键值
+/// * `T` - 行类型
+pub struct BatchLoader<K, T> {
+    fetch: Arc<dyn Fn(Vec<K>) -> BatchFuture<T> + Send + Sync>,
+    key_of: Arc<dyn Fn(&T) -> K + Send + Sync>,
+    pending: Arc<Mutex<Pending<K, T>>>,
+}
+
+impl<K, T> Clone for BatchLoader<K, T> {
+    fn clone(&self) -> Self {
+        Self {
+            fetch: Arc::clone(&self.fetch),
+            key_of: Arc::clone(&self.key_of),
+            pending: Arc::clone(&self.pending),
+        }
+    }
+}
+
+impl<K, T> BatchLoader<K, T>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+    T: Clone + Send + 'static,
+{
+    /// Create a BatchLoader from a batch query and a key extractor
+    ///
+    /// # Arguments
+    /// * `fetch` - Loads the rows of the given keys, e.g. a `Select` with `push_in_large` run by `fetch_all`
+    /// * `key_of` - Returns the key of a loaded row
+    ///
+    /// # Returns
+    /// A new BatchLoader with no pending keys
+    ///
+    /// 由批量查询和键提取函数创建 BatchLoader
+    ///
+    /// # 参数
+    /// * `fetch` - 加载给定键对应的行，例如通过 `fetch_all` 执行带 `push_in_large` 的 `Select`
+    /// * `key_of` - 返回已加载行的键
+    ///
+    /// # 返回值
+    /// 没有待处理键的新 BatchLoader
+    pub fn new<F, Fut, G>(fetch: F, key_of: G) -> Self
+    where
+        F: Fn(Vec<K>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<T>, Error>> + Send + 'static,
+        G: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        Self {
+            fetch: Arc::new(move |keys| Box::pin(fetch(keys))),
+            key_of: Arc::new(key_of),
+            pending: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Load the row of a key, batched with the other loads of the same tick
+    ///
+    /// Must be called within a Tokio runtime. If the batch query fails, every caller of the
+    /// batch receives the same error variant, e.g. `RowNotFound`, or the database error with its
+    /// code, so `QueryError::matches` still applies.
+    ///
+    /// # Arguments
+    /// * `key` - Key to load
+    ///
+    /// # Returns
+    /// The row, `None` if the key matched nothing, or an Error
+    ///
+    /// 加载某个键对应的行，与同一调度周期内的其他加载合并执行
+    ///
+    /// 必须在 Tokio 运行时中调用。批量查询失败时，该批次的每个调用方都会收到相同变体的错误，
+    /// 例如 `RowNotFound` 或保留错误码的数据库错误，因此仍可使用 `QueryError::matches` 判断。
+    ///
+    /// # 参数
+    /// * `key` - 要加载的键
+    ///
+    /// # 返回值
+    /// 对应的行，键没有匹配时返回 `None`，失败时返回 Error
+    pub async fn load(&self, key: K) -> Result<Option<T>, Error> {
+        let (sender, receiver) = oneshot::channel();
+        let first = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.push((key, sender));
+            pending.len() == 1
+        };
+        if first {
+            let loader = self.clone();
+            tokio::spawn(async move {
+                tokio::task::yield_now().await;
+                loader.dispatch().await;
+            });
+        }
+
+        receiver.await
+            .map_err(|_| QueryError::Other("batch load was cancelled".to_string()))?
+            .map_err(shared_error)
+    }
+
+    /// 取出待处理批次，执行一次批量查询并将结果按键分发给等待的调用方
+    async fn dispatch(&self) {
+        let batch = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut seen = HashSet::new();
+        let keys = batch.iter()
+            .filter(|(key, _)| seen.insert(key.clone()))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        match (self.fetch)(keys).await {
+            Ok(rows) => {
+                let rows: HashMap<K, T> = rows.into_iter()
+                    .map(|row| ((self.key_of)(&row), row))
+                    .collect();
+                for (key, sender) in batch {
+                    let _ = sender.send(Ok(rows.get(&key).cloned()));
+                }
+            }
+            Err(err) => {
+                let err = Arc::new(err);
+                for (_, sender) in batch {
+                    let _ = sender.send(Err(Arc::clone(&err)));
+                }
+            }
+        }
+    }
+}
+
+/// 将批次共享的错误还原为独立的 `Error`：最后一个持有者直接取回原错误，
+/// 其他调用方得到相同变体的副本，无法复制的错误源通过共享引用保留
+fn shared_error(err: Arc<Error>) -> Error {
+    let shared = match Arc::try_unwrap(err) {
+        Ok(err) => return err,
+        Err(shared) => shared,
+    };
+    let source = || Box::new(SharedSource(Arc::clone(&shared)));
+    match &*shared {
+        Error::RowNotFound => Error::RowNotFound,
+        Error::PoolTimedOut => Error::PoolTimedOut,
+        Error::PoolClosed => Error::PoolClosed,
+        Error::WorkerCrashed => Error::WorkerCrashed,
+        Error::Protocol(message) => Error::Protocol(message.clone()),
+        Error::ColumnNotFound(column) => Error::ColumnNotFound(column.clone()),
+        Error::TypeNotFound { type_name } => Error::TypeNotFound { type_name: type_name.clone() },
+        Error::ColumnIndexOutOfBounds { index, len } => Error::ColumnIndexOutOfBounds { index: *index, len: *len },
+        Error::Io(err) => Error::Io(io::Error::new(err.kind(), err.to_string())),
+        Error::Database(err) => match err.try_downcast_ref::<KitxError>() {
+            Some(err) => Error::Database(Box::new(err.clone())),
+            None => Error::Database(Box::new(SharedDatabaseError(Arc::clone(&shared)))),
+        },
+        Error::ColumnDecode { index, .. } => Error::ColumnDecode { index: index.clone(), source: source() },
+        Error::Configuration(_) => Error::Configuration(source()),
+        Error::Tls(_) => Error::Tls(source()),
+        Error::Encode(_) => Error::Encode(source()),
+        Error::Decode(_) => Error::Decode(source()),
+        _ => Error::AnyDriverError(source()),
+    }
+}
+
+/// 共享的错误源，显示为原错误的来源（没有来源时显示原错误本身）
+#[derive(Debug)]
+struct SharedSource(Arc<Error>);
+
+impl Display for SharedSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0.source() {
+            Some(source) => Display::fmt(source, f),
+            None => Display::fmt(&self.0, f),
+        }
+    }
+}
+
+impl StdError for SharedSource {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.0.source().and_then(StdError::source)
+    }
+}
+
+/// 共享的驱动数据库错误，消息、错误码、约束和错误类别均取自原错误
+#[derive(Debug)]
+struct SharedDatabaseError(Arc<Error>);
+
+impl SharedDatabaseError {
+    fn inner(&self) -> &dyn DatabaseError {
+        self.0.as_database_error()
+            .expect("SharedDatabaseError is only built from Error::Database")
+    }
+}
+
+impl Display for SharedDatabaseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.inner(), f)
+    }
+}
+
+impl StdError for SharedDatabaseError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.inner().source()
+    }
+}
+
+impl DatabaseError for SharedDatabaseError {
+    fn message(&self) -> &str {
+        self.inner().message()
+    }
+
+    fn code(&self) -> Option<Cow<'_, str>> {
+        self.inner().code()
+    }
+
+    fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+        self
+    }
+
+    fn is_transient_in_connect_phase(&self) -> bool {
+        self.inner().is_transient_in_connect_phase()
+    }
+
+    fn constraint(&self) -> Option<&str> {
+        self.inner().constraint()
+    }
+
+    fn table(&self) -> Option<&str> {
+        self.inner().table()
+    }
+
+    fn kind(&self) -> ErrorKind {
+        self.inner().kind()
+    }
+}
@@ -4,4 +4,5 @@ pub mod conversion;
 pub mod fields;
 pub mod filter;
 pub mod types;
-pub mod relation;
\ No newline at end of file
+pub mod relation;
+pub mod loader;
\ No newline at end of file
@@ -9,11 +9,13 @@
 //! 该模块定义了在整个 kitx crate 中用于数据库操作的通用数据结构和类型。
 //! 包括分页结果、排序顺序、主键定义和基于游标的分页结构。
 
-use std::fmt::Debug;
+use std::{collections::HashMap, fmt::Debug};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use field_access::FieldAccess;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use crate::common::{conversion::ValueConvert, fields::get_value};
+use crate::common::{conversion::ValueConvert, error::QueryError, fields::get_value};
 
 /// Sort order enum
 /// 
@@ -63,6 +65,364 @@ pub enum JoinType {
     Cross
 }
 
+/// Index hint enum (MySQL only)
+/// 
+/// # Variants
+/// * [Use](IndexHint::Use) - `USE INDEX (...)`, the optimizer may still pick a table scan
+/// * [Force](IndexHint::Force) - `FORCE INDEX (...)`, a table scan is only used if no listed index applies
+/// * [Ignore](IndexHint::Ignore) - `IGNORE INDEX (...)`, the listed indexes are never used
+/// 
+/// 索引提示枚举（仅 MySQL）
+/// 
+/// # 变体
+/// * [Use](IndexHint::Use) - `USE INDEX (...)`，优化器仍可能选择全表扫描
+/// * [Force](IndexHint::Force) - `FORCE INDEX (...)`，仅在列出的索引都不可用时才全表扫描
+/// * [Ignore](IndexHint::Ignore) - `IGNORE INDEX (...)`，不使用列出的索引
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexHint {
+    Use(Vec<String>),
+    Force(Vec<String>),
+    Ignore(Vec<String>),
+}
+
+impl IndexHint {
+    /// Render the hint as a SQL fragment
+    /// 
+    /// # Returns
+    /// The hint clause, e.g. `USE INDEX (idx_tenant)`
+    /// 
+    /// 将索引提示渲染为 SQL 片段
+    /// 
+    /// # 返回值
+    /// 索引提示子句，例如 `USE INDEX (idx_tenant)`
+    pub fn to_sql(&self) -> String {
+        let (keyword, indexes) = match self {
+            IndexHint::Use(indexes) => ("USE INDEX", indexes),
+            IndexHint::Force(indexes) => ("FORCE INDEX", indexes),
+            IndexHint::Ignore(indexes) => ("IGNORE INDEX", indexes),
+        };
+        format!("{} ({})", keyword, indexes.join(", "))
+    }
+}
+
+/// Table sampling method enum (PostgreSQL only)
+/// 
+/// # Variants
+/// * [System](SampleMethod::System) - Block-level sampling, fast but less uniform
+/// * [Bernoulli](SampleMethod::Bernoulli) - Row-level sampling, slower but more uniform
+/// 
+/// 表采样方法枚举（仅 PostgreSQL）
+/// 
+/// # 变体
+/// * [System](SampleMethod::System) - 按数据块采样，速度快但均匀性较差
+/// * [Bernoulli](SampleMethod::Bernoulli) - 按行采样，速度较慢但更均匀
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleMethod {
+    System,
+    Bernoulli,
+}
+
+impl SampleMethod {
+    /// Convert SampleMethod to string representation
+    /// 
+    /// 将SampleMethod转换为字符串表示
+    pub fn as_str(&self) -> &str {
+        match self {
+            SampleMethod::System => "SYSTEM",
+            SampleMethod::Bernoulli => "BERNOULLI",
+        }
+    }
+}
+
+/// Row locking mode of a SELECT (MySQL 8.0+ / PostgreSQL)
+/// 
+/// # Variants
+/// * [Update](LockMode::Update) - `FOR UPDATE`, exclusive lock, waits for other holders
+/// * [Share](LockMode::Share) - `FOR SHARE`, shared lock, blocks writers only
+/// * [UpdateNoWait](LockMode::UpdateNoWait) - `FOR UPDATE NOWAIT`, errors instead of waiting
+/// * [UpdateSkipLocked](LockMode::UpdateSkipLocked) - `FOR UPDATE SKIP LOCKED`, skips rows locked by others
+/// 
+/// SELECT 的行锁模式（MySQL 8.0+ / PostgreSQL）
+/// 
+/// # 变体
+/// * [Update](LockMode::Update) - `FOR UPDATE`，排他锁，等待其他持有者释放
+/// * [Share](LockMode::Share) - `FOR SHARE`，共享锁，只阻塞写入
+/// * [UpdateNoWait](LockMode::UpdateNoWait) - `FOR UPDATE NOWAIT`，无法加锁时立即报错
+/// * [UpdateSkipLocked](LockMode::UpdateSkipLocked) - `FOR UPDATE SKIP LOCKED`，跳过已被其他事务锁定的行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    Update,
+    Share,
+    UpdateNoWait,
+    UpdateSkipLocked,
+}
+
+impl LockMode {
+    /// Convert LockMode to its locking clause
+    /// 
+    /// 将LockMode转换为行锁子句
+    pub fn as_str(&self) -> &str {
+        match self {
+            LockMode::Update => "FOR UPDATE",
+            LockMode::Share => "FOR SHARE",
+            LockMode::UpdateNoWait => "FOR UPDATE NOWAIT",
+            LockMode::UpdateSkipLocked => "FOR UPDATE SKIP LOCKED",
+        }
+    }
+}
+
+/// Execution mode for running several independent statements
+/// 
+/// # Variants
+/// * [Transaction](BatchMode::Transaction) - Run sequentially in one transaction, all or nothing
+/// * [Concurrent](BatchMode::Concurrent) - Run concurrently on separate pool connections (autocommit),
+///   with at most the given number in flight
+/// 
+/// 执行多条独立语句的方式
+/// 
+/// # 变体
+/// * [Transaction](BatchMode::Transaction) - 在同一事务中依次执行，全部成功或全部回滚
+/// * [Concurrent](BatchMode::Concurrent) - 在不同的连接池连接上并发执行（自动提交），
+///   同时执行的数量不超过给定值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchMode {
+    Transaction,
+    Concurrent(usize),
+}
+
+/// Outcome of updating a single row by primary key
+/// 
+/// # Variants
+/// * [Updated](UpdateOutcome::Updated) - A row with the key exists and was updated
+/// * [NotFound](UpdateOutcome::NotFound) - No row matched the key
+/// 
+/// 按主键更新单行的结果
+/// 
+/// # 变体
+/// * [Updated](UpdateOutcome::Updated) - 存在该键对应的行且已更新
+/// * [NotFound](UpdateOutcome::NotFound) - 没有与该键匹配的行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    Updated,
+    NotFound,
+}
+
+impl UpdateOutcome {
+    /// Derive the outcome from the number of affected rows
+    /// 
+    /// 根据受影响的行数得出结果
+    pub fn from_rows_affected(rows_affected: u64) -> Self {
+        if rows_affected > 0 {
+            UpdateOutcome::Updated
+        } else {
+            UpdateOutcome::NotFound
+        }
+    }
+}
+
+/// Boolean tree of filter conditions
+/// 
+/// Leaves hold a SQL condition with `?` placeholders and the values bound to them,
+/// as accepted by `push_raw_bind`. Every leaf and every group of two or more children,
+/// including the root, is parenthesized on compile, so the tree shape decides the precedence
+/// and the result can be appended after other conditions, e.g. `tenant_id = ? AND <tree>`.
+/// 
+/// # Variants
+/// * [And](FilterTree::And) - All children must hold; empty is always true
+/// * [Or](FilterTree::Or) - Any child must hold; empty is always false
+/// * [Leaf](FilterTree::Leaf) - Condition with `?` placeholders and its values
+/// 
+/// 过滤条件的布尔树
+/// 
+/// 叶子节点保存带 `?` 占位符的 SQL 条件及其绑定值，格式与 `push_raw_bind` 相同。
+/// 编译时每个叶子以及包含两个及以上子节点的分组（含根节点）都会加上括号，因此优先级由树结构决定，
+/// 编译结果也可以安全地追加在其他条件之后，例如 `tenant_id = ? AND <tree>`。
+/// 
+/// # 变体
+/// * [And](FilterTree::And) - 所有子节点都须成立；为空时恒为真
+/// * [Or](FilterTree::Or) - 任一子节点成立即可；为空时恒为假
+/// * [Leaf](FilterTree::Leaf) - 带 `?` 占位符的条件及其值
+#[derive(Debug, Clone)]
+pub enum FilterTree<T> {
+    And(Vec<FilterTree<T>>),
+    Or(Vec<FilterTree<T>>),
+    Leaf(String, Vec<T>),
+}
+
+impl<T> FilterTree<T> {
+    /// Create a leaf condition
+    /// 
+    /// 创建叶子条件
+    pub fn leaf(condition: impl Into<String>, values: Vec<T>) -> Self {
+        FilterTree::Leaf(condition.into(), values)
+    }
+
+    /// Combine with another condition using AND, appending to this node if it is already an AND group
+    /// 
+    /// 以 AND 与另一个条件组合；自身已是 AND 分组时直接追加
+    pub fn and(self, other: FilterTree<T>) -> Self {
+        match self {
+            FilterTree::And(mut children) => {
+                children.push(other);
+                FilterTree::And(children)
+            }
+            node => FilterTree::And(vec![node, other]),
+        }
+    }
+
+    /// Combine with another condition using OR, appending to this node if it is already an OR group
+    /// 
+    /// 以 OR 与另一个条件组合；自身已是 OR 分组时直接追加
+    pub fn or(self, other: FilterTree<T>) -> Self {
+        match self {
+            FilterTree::Or(mut children) => {
+                children.push(other);
+                FilterTree::Or(children)
+            }
+            node => FilterTree::Or(vec![node, other]),
+        }
+    }
+
+    /// Compile the tree into a condition and its values in placeholder order
+    /// 
+    /// # Returns
+    /// The SQL condition with `?` placeholders and the values to bind, e.g.
+    /// `And[Or[a, b], c]` compiles to `(((a) OR (b)) AND (c))`
+    /// 
+    /// 将树编译为条件及按占位符顺序排列的值
+    /// 
+    /// # 返回值
+    /// 带 `?` 占位符的 SQL 条件及要绑定的值，例如 `And[Or[a, b], c]` 编译为 `(((a) OR (b)) AND (c))`
+    pub fn compile(self) -> (String, Vec<T>) {
+        let mut sql = String::new();
+        let mut values = Vec::new();
+        self.compile_into(&mut sql, &mut values);
+        (sql, values)
+    }
+
+    fn compile_into(self, sql: &mut String, values: &mut Vec<T>) {
+        let (children, separator, empty) = match self {
+            FilterTree::Leaf(condition, mut leaf_values) => {
+                sql.push('(');
+                sql.push_str(&condition);
+                sql.push(')');
+                values.append(&mut leaf_values);
+                return;
+            }
+            FilterTree::And(children) => (children, " AND ", "1 = 1"),
+            FilterTree::Or(children) => (children, " OR ", "1 = 0"),
+        };
+
+        match children.len() {
+            0 => sql.push_str(empty),
+            1 => children.into_iter().for_each(|child| child.compile_into(sql, values)),
+            _ => {
+                sql.push('(');
+                for (i, child) in children.into_iter().enumerate() {
+                    if i > 0 {
+                        sql.push_str(separator);
+                    }
+                    child.compile_into(sql, values);
+                }
+                sql.push(')');
+            }
+        }
+    }
+}
+
+/// Node of a tree assembled from adjacency-list rows (`id`, `parent_id`)
+/// 
+/// # Fields
+/// * `node` - The row itself
+/// * `children` - Rows whose parent column points at this row, in fetch order
+/// 
+/// 由邻接表行（`id`、`parent_id`）组装而成的树节点
+/// 
+/// # 字段
+/// * `node` - 行本身
+/// * `children` - 父列指向该行的子行，按查询顺序排列
+#[derive(Debug, Clone, Serialize)]
+pub struct TreeNode<T> {
+    pub node: T,
+    pub children: Vec<TreeNode<T>>,
+}
+
+impl<T> TreeNode<T>
+where
+    T: FieldAccess,
+{
+    /// Assemble rows into a forest
+    /// 
+    /// A row becomes a root when its parent value does not match the id of any other
+    /// row in `rows`, which covers NULL or zero parents as well as subtrees cut off by
+    /// the query condition. Rows that are only reachable through a parent cycle are not dropped:
+    /// after the regular roots, the first fetched row of each cycle also becomes a root, with the
+    /// rest of the cycle nested below it.
+    /// 
+    /// # Arguments
+    /// * `rows` - Rows to assemble
+    /// * `parent_column` - Column holding the parent id
+    /// * `id_column` - Column holding the row id
+    /// 
+    /// # Returns
+    /// The root nodes, in fetch order
+    /// 
+    /// 将行组装为森林
+    /// 
+    /// 当某行的父值与 `rows` 中其他行的 id 都不匹配时，该行成为根节点，
+    /// 包括父值为 NULL 或 0 的行，以及被查询条件截断的子树。只能通过父级环到达的行不会被丢弃：
+    /// 在常规根节点之后，每个环中最先查询到的行也成为根节点，环中其余行嵌套在其下。
+    /// 
+    /// # 参数
+    /// * `rows` - 要组装的行
+    /// * `parent_column` - 保存父 id 的列
+    /// * `id_column` - 保存行 id 的列
+    /// 
+    /// # 返回值
+    /// 根节点，按查询顺序排列，环中的行排在最后
+    pub fn build<VAL>(rows: Vec<T>, parent_column: &str, id_column: &str) -> Vec<TreeNode<T>>
+    where
+        VAL: ValueConvert + Default + PartialEq + Debug,
+    {
+        // 值类型（如 DataKind）不一定实现 Hash，按其 Debug 表示建立 id 到行下标的索引
+        let mut index: HashMap<String, Vec<usize>> = HashMap::with_capacity(rows.len());
+        for (i, row) in rows.iter().enumerate() {
+            let id: VAL = get_value(row, id_column);
+            index.entry(format!("{:?}", id)).or_default().push(i);
+        }
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); rows.len()];
+        let mut roots = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            let parent: VAL = get_value(row, parent_column);
+            let found = index.get(&format!("{:?}", parent))
+                .and_then(|candidates| candidates.iter().find(|&&j| j != i));
+            match found {
+                Some(&j) => children[j].push(i),
+                None => roots.push(i),
+            }
+        }
+
+        let mut slots: Vec<Option<T>> = rows.into_iter().map(Some).collect();
+        let mut forest: Vec<TreeNode<T>> = roots.into_iter()
+            .filter_map(|i| Self::assemble(i, &mut slots, &children))
+            .collect();
+        for i in 0..slots.len() {
+            if let Some(node) = Self::assemble(i, &mut slots, &children) {
+                forest.push(node);
+            }
+        }
+        forest
+    }
+
+    fn assemble(index: usize, slots: &mut [Option<T>], children: &[Vec<usize>]) -> Option<TreeNode<T>> {
+        let node = slots[index].take()?;
+        let children = children[index].iter()
+            .filter_map(|&child| Self::assemble(child, slots, children))
+            .collect();
+        Some(TreeNode { node, children })
+    }
+}
+
 /// Primary key struct
 /// 
 /// # Variants
@@ -114,6 +474,207 @@ impl <'a> PrimaryKey<'a> {
     }
 }
 
+/// Child table definition for application-side cascade deletes
+/// 
+/// 应用层级联删除的子表定义
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChildSpec<'a> {
+    /// Child table name
+    /// 
+    /// 子表名
+    pub table: &'a str,
+
+    /// Column in the child table referencing the parent key
+    /// 
+    /// 子表中引用父表键的列
+    pub foreign_key: &'a str,
+}
+
+impl<'a> ChildSpec<'a> {
+    /// Create a new ChildSpec
+    /// 
+    /// 创建新的 ChildSpec
+    pub const fn new(table: &'a str, foreign_key: &'a str) -> Self {
+        Self { table, foreign_key }
+    }
+}
+
+/// Soft-delete configuration: the column, the predicate selecting live rows and the value
+/// written when a row is deleted
+/// 
+/// Covers boolean flags (`deleted = FALSE` / `true`), integer states (`state = 0` / `1`) and
+/// timestamps (`deleted_at IS NULL` / the current time).
+/// 
+/// # Type Parameters
+/// * `VAL` - Value type of the deleted value
+/// 
+/// 软删除配置：软删除列、筛选未删除记录的谓词，以及删除记录时写入的值
+/// 
+/// 适用于布尔标志（`deleted = FALSE` / `true`）、整数状态（`state = 0` / `1`）
+/// 和时间戳（`deleted_at IS NULL` / 当前时间）。
+/// 
+/// # 类型参数
+/// * `VAL` - 删除值的类型
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoftDelete<'a, VAL> {
+    /// Soft-delete column
+    /// 
+    /// 软删除列
+    pub column: &'a str,
+
+    /// Predicate applied to the column to select live rows, e.g. `"= 0"` or `"IS NULL"`
+    /// 
+    /// 作用于该列、筛选未删除记录的谓词，例如 `"= 0"` 或 `"IS NULL"`
+    pub live_predicate: &'a str,
+
+    /// Value bound to the column when a row is deleted
+    /// 
+    /// 删除记录时绑定到该列的值
+    pub deleted_value: VAL,
+}
+
+impl<'a, VAL> SoftDelete<'a, VAL> {
+    /// Create a new SoftDelete
+    /// 
+    /// 创建新的 SoftDelete
+    pub const fn new(column: &'a str, live_predicate: &'a str, deleted_value: VAL) -> Self {
+        Self { column, live_predicate, deleted_value }
+    }
+
+    /// Boolean flag column: live rows are `column = FALSE`, deleting sets `true`
+    /// 
+    /// 布尔标志列：未删除记录为 `column = FALSE`，删除时设置为 `true`
+    pub fn flag(column: &'a str) -> Self
+    where
+        VAL: From<bool>,
+    {
+        Self::new(column, "= FALSE", VAL::from(true))
+    }
+
+    /// Condition selecting live rows, e.g. `state = 0`, for use in `filter`
+    /// 
+    /// 筛选未删除记录的条件，例如 `state = 0`，用于 `filter`
+    pub fn live_condition(&self) -> String {
+        format!("{} {}", self.column, self.live_predicate)
+    }
+}
+
+/// Window function column: `function OVER (PARTITION BY ... ORDER BY ...) AS alias`
+/// 
+/// Add it to a query with `Select::window`, or render it with `to_sql` inside `columns`
+/// next to other columns and aggregates.
+/// 
+/// 窗口函数列：`function OVER (PARTITION BY ... ORDER BY ...) AS alias`
+/// 
+/// 通过 `Select::window` 添加到查询中，或在 `columns` 中通过 `to_sql` 与其他列和聚合一起输出。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WindowFunc {
+    function: String,
+    partition_by: Vec<String>,
+    order_by: Vec<(String, Order)>,
+    alias: String,
+}
+
+impl WindowFunc {
+    /// Create a window column from any window or aggregate function, e.g. `"SUM(views)"`
+    /// 
+    /// # Arguments
+    /// * `function` - Function call placed before `OVER`
+    /// * `alias` - Column alias
+    /// 
+    /// 由任意窗口函数或聚合函数创建窗口列，例如 `"SUM(views)"`
+    /// 
+    /// # 参数
+    /// * `function` - 位于 `OVER` 之前的函数调用
+    /// * `alias` - 列别名
+    pub fn new(function: impl Into<String>, alias: impl Into<String>) -> Self {
+        Self {
+            function: function.into(),
+            partition_by: vec![],
+            order_by: vec![],
+            alias: alias.into(),
+        }
+    }
+
+    /// `ROW_NUMBER()`: sequential number of the row within its partition
+    /// 
+    /// `ROW_NUMBER()`：行在其分区内的序号
+    pub fn row_number(alias: impl Into<String>) -> Self {
+        Self::new("ROW_NUMBER()", alias)
+    }
+
+    /// `RANK()`: rank with gaps after ties
+    /// 
+    /// `RANK()`：并列后留有间隔的排名
+    pub fn rank(alias: impl Into<String>) -> Self {
+        Self::new("RANK()", alias)
+    }
+
+    /// `DENSE_RANK()`: rank without gaps after ties
+    /// 
+    /// `DENSE_RANK()`：并列后不留间隔的排名
+    pub fn dense_rank(alias: impl Into<String>) -> Self {
+        Self::new("DENSE_RANK()", alias)
+    }
+
+    /// `LAG(column, offset)`: the value `offset` rows before the current row
+    /// 
+    /// `LAG(column, offset)`：当前行之前第 `offset` 行的值
+    pub fn lag(column: &str, offset: u32, alias: impl Into<String>) -> Self {
+        Self::new(format!("LAG({}, {})", column, offset), alias)
+    }
+
+    /// `LEAD(column, offset)`: the value `offset` rows after the current row
+    /// 
+    /// `LEAD(column, offset)`：当前行之后第 `offset` 行的值
+    pub fn lead(column: &str, offset: u32, alias: impl Into<String>) -> Self {
+        Self::new(format!("LEAD({}, {})", column, offset), alias)
+    }
+
+    /// Add a partition column; may be called several times
+    /// 
+    /// 添加分区列，可多次调用
+    pub fn partition_by(mut self, column: impl Into<String>) -> Self {
+        self.partition_by.push(column.into());
+        self
+    }
+
+    /// Add an ordering item within the window; may be called several times
+    /// 
+    /// 添加窗口内的排序项，可多次调用
+    pub fn order_by(mut self, column: impl Into<String>, order: Order) -> Self {
+        self.order_by.push((column.into(), order));
+        self
+    }
+
+    /// Partition and ordering columns, checked as identifiers in strict mode
+    /// 
+    /// 分区列和排序列，用于严格模式下的标识符校验
+    pub(crate) fn columns(&self) -> impl Iterator<Item = &str> {
+        self.partition_by.iter()
+            .chain(self.order_by.iter().map(|(column, _)| column))
+            .map(|column| column.as_str())
+    }
+
+    /// Render the column, e.g. `ROW_NUMBER() OVER (PARTITION BY a ORDER BY b DESC) AS rn`
+    /// 
+    /// 输出该列，例如 `ROW_NUMBER() OVER (PARTITION BY a ORDER BY b DESC) AS rn`
+    pub fn to_sql(&self) -> String {
+        let mut clauses = Vec::new();
+        if !self.partition_by.is_empty() {
+            clauses.push(format!("PARTITION BY {}", self.partition_by.join(", ")));
+        }
+        if !self.order_by.is_empty() {
+            let orders = self.order_by.iter()
+                .map(|(column, order)| format!("{} {}", column, order.as_str()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            clauses.push(format!("ORDER BY {}", orders));
+        }
+        format!("{} OVER ({}) AS {}", self.function, clauses.join(" "), self.alias)
+    }
+}
+
 /// Paginated query result structure
 /// 
 /// # Type Parameters
@@ -176,6 +737,30 @@ impl<T> PaginatedResult<T> {
             page_size,
         }
     }
+
+    /// Transform the data records while keeping the pagination metadata
+    /// 
+    /// # Arguments
+    /// * `f` - Function mapping each record, e.g. an entity to a DTO
+    /// 
+    /// # Returns
+    /// A PaginatedResult of the mapped records with the same total, page number and page size
+    /// 
+    /// 转换数据记录，同时保留分页元数据
+    /// 
+    /// # 参数
+    /// * `f` - 映射每条记录的函数，例如将实体转换为 DTO
+    /// 
+    /// # 返回值
+    /// 包含映射后记录的 PaginatedResult，总数、页码和页面大小保持不变
+    pub fn map<R>(self, f: impl FnMut(T) -> R) -> PaginatedResult<R> {
+        PaginatedResult {
+            data: self.data.into_iter().map(f).collect(),
+            total: self.total,
+            page_number: self.page_number,
+            page_size: self.page_size,
+        }
+    }
 }
 
 /// Cursor paginated result structure
@@ -306,4 +891,196 @@ impl<T, C> CursorPaginatedResult<T, C> {
             self.prev_cursor = prev_item.map(|item| get_value::<T, C>(item, column_key));
         }
     }
-}
\ No newline at end of file
+
+    /// Reorder the data for display without changing the cursors
+    /// 
+    /// When paging backwards the query runs in the opposite direction of the order shown to
+    /// users, so the rows come back reversed. Call this after `gen_cursors` to present the
+    /// rows in `display_order`; the cursors and `sort_order` keep describing the query.
+    /// 
+    /// # Arguments
+    /// * `display_order` - Order in which the rows are presented
+    /// 
+    /// 在不改变游标的情况下按显示顺序重排数据
+    /// 
+    /// 向前翻页时查询方向与展示给用户的顺序相反，返回的数据是倒序的。
+    /// 在 `gen_cursors` 之后调用此方法即可按 `display_order` 展示数据；
+    /// 游标和 `sort_order` 仍然对应原查询。
+    /// 
+    /// # 参数
+    /// * `display_order` - 数据展示的顺序
+    pub fn reorder_for_display(&mut self, display_order: Order) {
+        if display_order != self.sort_order {
+            self.data.reverse();
+        }
+    }
+
+    /// Convert into a page whose items carry opaque cursor tokens
+    /// 
+    /// Each item is paired with a token encoding its `key_fields` values, and the
+    /// page's `next`/`prev` tokens follow the same rules as `gen_cursors`. Tokens are
+    /// URL-safe base64 of a JSON object and can be read back with `decode_cursor`.
+    /// 
+    /// # Arguments
+    /// * `key_fields` - Fields that identify an item's position, e.g. `&["created_at", "id"]`
+    /// 
+    /// # Returns
+    /// An EncodedCursorPage, or an Error if an item cannot be serialized
+    /// 
+    /// 转换为每条记录都带有不透明游标令牌的分页结果
+    /// 
+    /// 每条记录与编码了其 `key_fields` 值的令牌配对，页面的 `next`/`prev` 令牌
+    /// 与 `gen_cursors` 的规则一致。令牌为 JSON 对象的 URL 安全 base64 编码，
+    /// 可通过 `decode_cursor` 还原。
+    /// 
+    /// # 参数
+    /// * `key_fields` - 确定记录位置的字段，例如 `&["created_at", "id"]`
+    /// 
+    /// # 返回值
+    /// EncodedCursorPage，记录无法序列化时返回错误
+    pub fn into_encoded(self, key_fields: &[&str]) -> Result<EncodedCursorPage<T>, QueryError>
+    where
+        T: Serialize,
+    {
+        let cursors = self.data.iter()
+            .map(|item| encode_cursor(item, key_fields))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (mut next_cursor, mut prev_cursor) = (None, None);
+        if self.data.len() as u64 == self.limit {
+            let (next, prev) = match self.sort_order {
+                Order::Asc => (cursors.last(), cursors.first()),
+                Order::Desc => (cursors.first(), cursors.last()),
+            };
+            next_cursor = next.cloned();
+            prev_cursor = prev.cloned();
+        }
+
+        let edges = self.data.into_iter()
+            .zip(cursors)
+            .map(|(node, cursor)| CursorEdge { node, cursor })
+            .collect();
+
+        Ok(EncodedCursorPage {
+            edges,
+            next_cursor,
+            prev_cursor,
+            limit: self.limit,
+            sort_order: self.sort_order,
+        })
+    }
+}
+
+/// An item paired with its opaque cursor token
+/// 
+/// 与其不透明游标令牌配对的记录
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq, Hash)]
+pub struct CursorEdge<T> {
+    /// Data record
+    /// 
+    /// 数据记录
+    pub node: T,
+
+    /// Opaque cursor token pointing at this record
+    /// 
+    /// 指向该记录的不透明游标令牌
+    pub cursor: String,
+}
+
+/// Cursor paginated result with pre-encoded cursor tokens
+/// 
+/// Suited to building GraphQL connection or REST cursor responses directly.
+/// 
+/// # Type Parameters
+/// * `T` - The type of data records
+/// 
+/// 带有预编码游标令牌的游标分页结果
+/// 
+/// 适合直接构建 GraphQL connection 或 REST 游标响应。
+/// 
+/// # 类型参数
+/// * `T` - 数据记录的类型
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq, Hash)]
+pub struct EncodedCursorPage<T> {
+    /// Data records with their cursor tokens
+    /// 
+    /// 带游标令牌的数据记录
+    pub edges: Vec<CursorEdge<T>>,
+
+    /// Next page cursor token
+    /// 
+    /// 下一页游标令牌
+    pub next_cursor: Option<String>,
+
+    /// Previous page cursor token
+    /// 
+    /// 上一页游标令牌
+    pub prev_cursor: Option<String>,
+
+    /// Maximum number of records per page
+    /// 
+    /// 每页最大记录数
+    pub limit: u64,
+
+    /// Sort order direction
+    /// 
+    /// 排序方向
+    pub sort_order: Order,
+}
+
+/// Encode the given fields of an item as an opaque cursor token
+/// 
+/// # Arguments
+/// * `item` - The record to encode
+/// * `key_fields` - Fields to include in the token
+/// 
+/// # Returns
+/// URL-safe base64 of a JSON object holding the field values, or an Error
+/// 
+/// 将记录的指定字段编码为不透明游标令牌
+/// 
+/// # 参数
+/// * `item` - 要编码的记录
+/// * `key_fields` - 令牌中包含的字段
+/// 
+/// # 返回值
+/// 包含字段值的 JSON 对象的 URL 安全 base64 编码，或错误
+pub fn encode_cursor<T: Serialize>(item: &T, key_fields: &[&str]) -> Result<String, QueryError> {
+    let value = serde_json::to_value(item)
+        .map_err(|e| QueryError::CursorInvalid(e.to_string()))?;
+
+    let mut keys = Map::new();
+    for field in key_fields {
+        let field_value = value.get(field)
+            .ok_or_else(|| QueryError::ValueInvalid(field.to_string()))?;
+        keys.insert(field.to_string(), field_value.clone());
+    }
+
+    Ok(URL_SAFE_NO_PAD.encode(Value::Object(keys).to_string()))
+}
+
+/// Decode an opaque cursor token produced by `encode_cursor`
+/// 
+/// # Arguments
+/// * `token` - The cursor token
+/// 
+/// # Returns
+/// The key field values by field name, or `QueryError::CursorInvalid`
+/// 
+/// 解码由 `encode_cursor` 生成的不透明游标令牌
+/// 
+/// # 参数
+/// * `token` - 游标令牌
+/// 
+/// # 返回值
+/// 按字段名索引的键字段值，或 `QueryError::CursorInvalid`
+pub fn decode_cursor(token: &str) -> Result<Map<String, Value>, QueryError> {
+    let bytes = URL_SAFE_NO_PAD.decode(token)
+        .map_err(|e| QueryError::CursorInvalid(e.to_string()))?;
+
+    match serde_json::from_slice(&bytes) {
+        Ok(Value::Object(keys)) => Ok(keys),
+        Ok(_) => Err(QueryError::CursorInvalid("not a JSON object".to_string())),
+        Err(e) => Err(QueryError::CursorInvalid(e.to_string())),
+    }
+}
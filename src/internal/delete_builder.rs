@@ -1,11 +1,14 @@
-use std::marker::PhantomData;
+use std::{any::Any, marker::PhantomData};
 
 use field_access::FieldAccess;
-use sqlx::{Database, Encode, QueryBuilder, Type};
+use sqlx::{Database, Encode, Error, QueryBuilder, Type};
 
 use crate::common::{
-    filter::push_primary_key_bind, helper::get_table_name, types::PrimaryKey
+    conversion::ValueConvert, error::QueryError, filter::{push_in_large, push_primary_key_bind}, helper::get_table_name, types::{ChildSpec, PrimaryKey, SoftDelete}
 };
+#[cfg(any(feature = "sqlite" , feature = "postgres"))]
+use crate::common::fields::ensure_entity_columns;
+use crate::internal::subquery::Subquery;
 
 /// Delete query builder
 /// 
@@ -30,7 +33,7 @@ where
 {
     query_builder: QueryBuilder<'a, DB>,
     has_filter: bool,
-    _phantom: PhantomData<(ET, VAL)>,
+    _phantom: PhantomData<(fn() -> ET, VAL)>,
 }
 
 impl<'a, ET, DB, VAL> Delete<'a, ET, DB, VAL>
@@ -86,7 +89,139 @@ where
             _phantom: PhantomData,
         }
     }
+
+    /// Create a soft delete: `UPDATE table SET column = ?`, binding the configured deleted value
+    /// 
+    /// Conditions are added with `filter` as for a regular delete. Live rows are selected with
+    /// `SoftDelete::live_condition`.
+    /// 
+    /// # Arguments
+    /// * `soft_delete` - Soft-delete configuration
+    /// 
+    /// # Returns
+    /// A new Delete instance
+    /// 
+    /// 创建软删除：`UPDATE table SET column = ?`，绑定配置的删除值
+    /// 
+    /// 与普通删除一样通过 `filter` 添加条件。未删除的记录可通过 `SoftDelete::live_condition` 筛选。
+    /// 
+    /// # 参数
+    /// * `soft_delete` - 软删除配置
+    /// 
+    /// # 返回值
+    /// 新的 Delete 实例
+    pub fn soft(soft_delete: &SoftDelete<'_, VAL>) -> Self
+    where
+        VAL: Clone + 'a,
+    {
+        let mut query_builder = QueryBuilder::new("UPDATE ");
+        query_builder.push(get_table_name::<ET>())
+            .push(" SET ")
+            .push(soft_delete.column)
+            .push(" = ")
+            .push_bind(soft_delete.deleted_value.clone());
+
+        Self {
+            query_builder,
+            has_filter: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a DELETE preceded by a common table expression
+    /// 
+    /// Renders `WITH name AS (subquery) DELETE FROM table`, with the subquery's bound values placed
+    /// before those of the statement. The CTE can then be referenced in the conditions,
+    /// e.g. `id IN (SELECT id FROM name)`. Needs MySQL 8.0+ on MySQL.
+    /// 
+    /// # Arguments
+    /// * `name` - Name of the common table expression
+    /// * `cte` - Subquery defining the common table expression
+    /// 
+    /// # Returns
+    /// A new Delete instance
+    /// 
+    /// 创建前置公用表表达式的 DELETE
+    /// 
+    /// 生成 `WITH name AS (subquery) DELETE FROM table`，子查询的绑定值位于语句的绑定值之前。
+    /// 之后可在条件中引用该 CTE，例如 `id IN (SELECT id FROM name)`。MySQL 需要 8.0 及以上版本。
+    /// 
+    /// # 参数
+    /// * `name` - 公用表表达式的名称
+    /// * `cte` - 定义公用表表达式的子查询
+    /// 
+    /// # 返回值
+    /// 新的 Delete 实例
+    pub fn with_cte<CT>(name: &str, cte: Subquery<'a, CT, VAL>) -> Self
+    where
+        CT: FieldAccess + Default,
+        VAL: 'a,
+    {
+        let mut query_builder = QueryBuilder::new(format!("WITH {} AS", name));
+        cte.append_to(&mut query_builder);
+        Self::from_query(query_builder)
+    }
     
+    /// Create a DELETE that removes rows matching rows of another table
+    /// 
+    /// Renders the dialect's join-delete: `DELETE FROM table USING other WHERE condition` on
+    /// PostgreSQL and `DELETE table FROM table JOIN other ON condition` on MySQL. SQLite has no
+    /// multi-table delete, so there it becomes `DELETE FROM table WHERE EXISTS (SELECT 1 FROM other
+    /// WHERE condition)`, which deletes the same rows. Conditions on `other_table` belong in the
+    /// join condition, since only there are its columns visible on every database; further
+    /// conditions on the target table can be added with `filter` or `by_primary_key`.
+    /// 
+    /// # Arguments
+    /// * `other_table` - Table to match against
+    /// * `condition_build_fn` - Function to build the join condition with qualified columns,
+    ///   e.g. `article.id = article_tag.article_id`
+    /// 
+    /// # Returns
+    /// A new Delete instance
+    /// 
+    /// 创建按另一张表的行匹配删除的 DELETE
+    /// 
+    /// 按数据库生成对应的关联删除：PostgreSQL 为 `DELETE FROM table USING other WHERE condition`，
+    /// MySQL 为 `DELETE table FROM table JOIN other ON condition`。SQLite 不支持多表删除，
+    /// 因此生成删除相同行的 `DELETE FROM table WHERE EXISTS (SELECT 1 FROM other WHERE condition)`。
+    /// `other_table` 上的条件应写在关联条件中，只有在那里其列在所有数据库上都可见；
+    /// 目标表上的其他条件可通过 `filter` 或 `by_primary_key` 添加。
+    /// 
+    /// # 参数
+    /// * `other_table` - 用于匹配的表
+    /// * `condition_build_fn` - 构建使用限定列名的关联条件的函数，例如 `article.id = article_tag.article_id`
+    /// 
+    /// # 返回值
+    /// 新的 Delete 实例
+    pub fn using(
+        other_table: &str,
+        condition_build_fn: impl FnOnce(&mut QueryBuilder<'a, DB>),
+    ) -> Self {
+        let table_name = get_table_name::<ET>();
+        let mut query_builder = QueryBuilder::new("");
+        match DB::NAME {
+            "PostgreSQL" => {
+                query_builder.push(format!("DELETE FROM {} USING {} WHERE ", table_name, other_table));
+                condition_build_fn(&mut query_builder);
+            }
+            "MySQL" => {
+                query_builder.push(format!("DELETE {0} FROM {0} JOIN {1} ON ", table_name, other_table));
+                condition_build_fn(&mut query_builder);
+            }
+            _ => {
+                query_builder.push(format!("DELETE FROM {} WHERE EXISTS (SELECT 1 FROM {} WHERE ", table_name, other_table));
+                condition_build_fn(&mut query_builder);
+                query_builder.push(")");
+            }
+        }
+
+        Self {
+            query_builder,
+            has_filter: DB::NAME != "MySQL",
+            _phantom: PhantomData,
+        }
+    }
+
     /// Create a DELETE query by primary key
     /// 
     /// # Arguments
@@ -115,6 +250,254 @@ where
         self
     }
 
+    /// Create a DELETE query matching a key column against a list of keys
+    /// 
+    /// The condition comes from `push_in_large`: on PostgreSQL, `i32`, `i64`, `String` and `Uuid`
+    /// keys are bound as one array parameter in `column = ANY($n)`; otherwise they are split into
+    /// `IN` lists of 500 values. An empty list matches no rows.
+    /// 
+    /// # Arguments
+    /// * `column` - Key column name
+    /// * `values` - Keys to match
+    /// 
+    /// # Returns
+    /// The Delete instance with the condition added
+    /// 
+    /// 创建按键列表匹配键列的 DELETE 查询
+    /// 
+    /// 条件由 `push_in_large` 生成：在 PostgreSQL 上，`i32`、`i64`、`String` 和 `Uuid` 键
+    /// 作为单个数组参数绑定为 `column = ANY($n)`；其他情况拆分为每组 500 个值的 `IN` 列表。
+    /// 键列表为空时不匹配任何行。
+    /// 
+    /// # 参数
+    /// * `column` - 键列名
+    /// * `values` - 要匹配的键
+    /// 
+    /// # 返回值
+    /// 添加了条件的 Delete 实例
+    pub fn by_keys<T: Any>(mut self, column: &str, values: Vec<T>) -> Self
+    where
+        VAL: ValueConvert + 'a,
+    {
+        if !self.has_filter {
+            self.query_builder.push(" WHERE ");
+            self.has_filter = true;
+        } else {
+            self.query_builder.push(" AND ");
+        }
+        push_in_large::<DB, T, VAL>(&mut self.query_builder, column, values);
+        self
+    }
+
+    /// Create a DELETE query matching a key column against an array (PostgreSQL only)
+    /// 
+    /// Generates `column = ANY($n)` with the whole key list bound as one array parameter,
+    /// instead of one placeholder per key as an `IN (...)` list would need. Use it when the
+    /// keys are already an array value; `by_keys` produces the same condition on PostgreSQL.
+    /// 
+    /// # Arguments
+    /// * `column` - Key column name
+    /// * `values` - Array value holding all keys, e.g. `DataKind::Int8Array`
+    /// 
+    /// # Returns
+    /// The Delete instance with the condition added
+    /// 
+    /// 创建按数组匹配键列的 DELETE 查询（仅 PostgreSQL）
+    /// 
+    /// 生成 `column = ANY($n)`，整个键列表作为一个数组参数绑定，
+    /// 而不是像 `IN (...)` 那样为每个键生成一个占位符。已持有数组值时使用；
+    /// `by_keys` 在 PostgreSQL 上生成相同的条件。
+    /// 
+    /// # 参数
+    /// * `column` - 键列名
+    /// * `values` - 包含所有键的数组值，例如 `DataKind::Int8Array`
+    /// 
+    /// # 返回值
+    /// 添加了条件的 Delete 实例
+    #[cfg(feature = "postgres")]
+    pub fn by_keys_any(mut self, column: &str, values: VAL) -> Self
+    where
+        VAL: 'a,
+    {
+        if !self.has_filter {
+            self.query_builder.push(" WHERE ");
+            self.has_filter = true;
+        } else {
+            self.query_builder.push(" AND ");
+        }
+        self.query_builder.push(column)
+            .push(" = ANY(")
+            .push_bind(values)
+            .push(")");
+        self
+    }
+
+    /// Create cascading DELETE queries emulating `ON DELETE CASCADE`
+    /// 
+    /// Returns one DELETE per child table (matching `foreign_key = key_value`) followed by
+    /// the DELETE of the parent row. The query module's `cascade_by_key` runs them in a single
+    /// transaction, so a failure leaves no orphaned or half-deleted rows.
+    /// 
+    /// # Arguments
+    /// * `key_column` - Parent key column name
+    /// * `key_value` - Parent key value
+    /// * `children` - Child tables referencing the parent key
+    /// 
+    /// # Returns
+    /// The DELETE QueryBuilders, children first and parent last
+    /// 
+    /// 创建模拟 `ON DELETE CASCADE` 的级联 DELETE 查询
+    /// 
+    /// 为每个子表返回一条 DELETE（匹配 `foreign_key = key_value`），最后是删除父记录的 DELETE。
+    /// 查询模块的 `cascade_by_key` 会在同一事务中执行这些语句，避免失败时留下孤立或部分删除的记录。
+    /// 
+    /// # 参数
+    /// * `key_column` - 父表键列名
+    /// * `key_value` - 父表键值
+    /// * `children` - 引用父表键的子表
+    /// 
+    /// # 返回值
+    /// DELETE 查询构建器列表，子表在前，父表在后
+    pub fn cascade_by_key(
+        key_column: &str,
+        key_value: VAL,
+        children: &[ChildSpec<'_>],
+    ) -> Vec<QueryBuilder<'a, DB>>
+    where
+        VAL: Clone + 'a,
+    {
+        let mut builders: Vec<_> = children.iter()
+            .map(|child| {
+                let mut qb = QueryBuilder::new("DELETE FROM ");
+                qb.push(child.table)
+                    .push(" WHERE ")
+                    .push(child.foreign_key)
+                    .push(" = ")
+                    .push_bind(key_value.clone());
+                qb
+            })
+            .collect();
+
+        let mut parent = Self::table().query_builder;
+        parent.push(" WHERE ")
+            .push(key_column)
+            .push(" = ")
+            .push_bind(key_value);
+        builders.push(parent);
+
+        builders
+    }
+
+    /// Create queries that archive a row and then soft-delete it
+    /// 
+    /// Returns an `INSERT INTO archive_table (...) SELECT ... FROM table WHERE key = ? AND
+    /// soft_delete_column = FALSE` copying the row with all entity columns unless it is already
+    /// soft-deleted, followed by an `UPDATE table SET soft_delete_column = ? WHERE key = ?` marking
+    /// it deleted. The query module's `archive_by_key` runs both in a single transaction.
+    /// 
+    /// # Arguments
+    /// * `key_column` - Key column name
+    /// * `key_value` - Key value of the row
+    /// * `archive_table` - Table receiving the copy, with the same columns as the entity
+    /// * `soft_delete_column` - Boolean column flagging the row as deleted
+    /// 
+    /// # Returns
+    /// The INSERT ... SELECT and UPDATE QueryBuilders, in execution order
+    /// 
+    /// 创建先归档再软删除记录的查询
+    /// 
+    /// 返回 `INSERT INTO archive_table (...) SELECT ... FROM table WHERE key = ? AND soft_delete_column = FALSE`，
+    /// 以实体的全部列复制该记录（已软删除的记录不会重复归档），随后是
+    /// `UPDATE table SET soft_delete_column = ? WHERE key = ?` 将其标记为已删除。
+    /// 查询模块的 `archive_by_key` 会在同一事务中执行这两条语句。
+    /// 
+    /// # 参数
+    /// * `key_column` - 键列名
+    /// * `key_value` - 记录的键值
+    /// * `archive_table` - 接收副本的表，列与实体相同
+    /// * `soft_delete_column` - 标记记录已删除的布尔列
+    /// 
+    /// # 返回值
+    /// INSERT ... SELECT 和 UPDATE 查询构建器，按执行顺序排列
+    pub fn archive_by_key(
+        key_column: &str,
+        key_value: VAL,
+        archive_table: &str,
+        soft_delete_column: &str,
+    ) -> Vec<QueryBuilder<'a, DB>>
+    where
+        ET: Default,
+        VAL: From<bool> + Clone + 'a,
+    {
+        let table_name = get_table_name::<ET>();
+        let columns = ET::default().field_names().join(", ");
+        let flag = SoftDelete::flag(soft_delete_column);
+
+        let mut archive = QueryBuilder::new("INSERT INTO ");
+        archive.push(archive_table)
+            .push(format!(" ({}) SELECT {} FROM {} WHERE ", columns, columns, table_name))
+            .push(key_column)
+            .push(" = ")
+            .push_bind(key_value.clone())
+            .push(" AND ")
+            .push(flag.live_condition());
+
+        let soft_delete = Self::soft(&flag)
+            .filter(|qb| {
+                qb.push(key_column).push(" = ").push_bind(key_value);
+            })
+            .finish();
+
+        vec![archive, soft_delete]
+    }
+
+    /// Create a DELETE query removing duplicate rows, keeping the latest row of each group
+    /// 
+    /// Rows are ranked with `ROW_NUMBER() OVER (PARTITION BY group_columns ORDER BY
+    /// order_column DESC, key_column DESC)` and every row ranked below the first is deleted.
+    /// The ranking is wrapped in a derived table so MySQL accepts the self-referencing delete.
+    /// 
+    /// # Arguments
+    /// * `key_column` - Column uniquely identifying a row, usually the primary key
+    /// * `group_columns` - Columns whose values define a group of duplicates
+    /// * `order_column` - Column deciding which row is the latest, e.g. `created_at`
+    /// 
+    /// # Returns
+    /// A QueryBuilder with the DELETE query, or `QueryError::ColumnsListEmpty` if `group_columns` is empty
+    /// 
+    /// 创建删除重复记录的 DELETE 查询，每组保留最新的一条
+    /// 
+    /// 使用 `ROW_NUMBER() OVER (PARTITION BY group_columns ORDER BY order_column DESC, key_column DESC)`
+    /// 为记录排名，删除每组中排名第一之外的所有记录。排名放在派生表中，以便 MySQL 接受引用自身表的删除。
+    /// 
+    /// # 参数
+    /// * `key_column` - 唯一标识一条记录的列，通常是主键
+    /// * `group_columns` - 其值决定一组重复记录的列
+    /// * `order_column` - 决定哪条记录最新的列，例如 `created_at`
+    /// 
+    /// # 返回值
+    /// 包含 DELETE 查询的 QueryBuilder，`group_columns` 为空时返回 `QueryError::ColumnsListEmpty`
+    pub fn dedupe_keep_latest(
+        key_column: &str,
+        group_columns: &[&str],
+        order_column: &str,
+    ) -> Result<QueryBuilder<'a, DB>, Error> {
+        if group_columns.is_empty() {
+            return Err(QueryError::ColumnsListEmpty.into());
+        }
+
+        let mut query_builder = Self::table().query_builder;
+        query_builder.push(format!(
+            " WHERE {key} IN (SELECT {key} FROM (SELECT {key}, ROW_NUMBER() OVER (PARTITION BY {groups} ORDER BY {order} DESC, {key} DESC) AS row_num FROM {table}) AS ranked WHERE row_num > 1)",
+            key = key_column,
+            groups = group_columns.join(", "),
+            order = order_column,
+            table = get_table_name::<ET>(),
+        ));
+
+        Ok(query_builder)
+    }
+
     /// Create a DELETE query with custom WHERE conditions
     /// 
     /// # Arguments
@@ -134,7 +517,12 @@ where
         mut self,
         filter_build_fn: impl FnOnce(&mut QueryBuilder<'a, DB>),
     ) -> Self {
-        self.query_builder.push(" WHERE ");
+        if !self.has_filter {
+            self.query_builder.push(" WHERE ");
+            self.has_filter = true;
+        } else {
+            self.query_builder.push(" AND ");
+        }
         filter_build_fn(&mut self.query_builder);
 
         self
@@ -146,16 +534,19 @@ where
     /// * `columns` - 要返回的列
     /// 
     /// # 返回值
-    /// 更新后的构建器实例
+    /// 更新后的构建器实例；列为空时不添加子句，与 `returning_none` 相同
     #[cfg(any(feature = "sqlite" , feature = "postgres"))]
     pub fn returning<I, S>(mut self, columns: I) -> Self
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        self.query_builder.push(" RETURNING ");
-        
         let cols: Vec<String> = columns.into_iter().map(|s| s.as_ref().to_string()).collect();
+        if cols.is_empty() {
+            return self;
+        }
+
+        self.query_builder.push(" RETURNING ");
         let mut separated = self.query_builder.separated(", ");
         for col in cols {
             separated.push(col);
@@ -164,6 +555,35 @@ where
         self
     }
 
+    /// 添加 RETURNING 子句，并校验各列均为实体字段，避免返回整行或拼错列名
+    /// 
+    /// # 参数
+    /// * `columns` - 要返回的列
+    /// 
+    /// # 返回值
+    /// 更新后的构建器实例；列为空时返回 `QueryError::ColumnsListEmpty`，
+    /// 包含实体没有的字段时返回 `QueryError::ValueInvalid`
+    #[cfg(any(feature = "sqlite" , feature = "postgres"))]
+    pub fn try_returning(self, columns: &[&str]) -> Result<Self, Error>
+    where
+        ET: Default,
+    {
+        ensure_entity_columns::<ET>(columns)?;
+        Ok(self.returning(columns))
+    }
+
+    /// 明确不添加 RETURNING 子句，语句只返回受影响的行数
+    /// 
+    /// 仅作为标记，不修改 SQL：之前调用 `returning` 已写入的 RETURNING 子句不会被移除，
+    /// 因此应在 `returning` 与 `returning_none` 之间二选一。
+    /// 
+    /// # 返回值
+    /// 原构建器实例
+    #[cfg(any(feature = "sqlite" , feature = "postgres"))]
+    pub fn returning_none(self) -> Self {
+        self
+    }
+
     /// 添加自定义查询部分
     /// 
     /// # 参数
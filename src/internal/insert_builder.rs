@@ -4,8 +4,10 @@ use field_access::FieldAccess;
 use sqlx::{Database, Encode, Error, QueryBuilder, Type};
 
 use crate::common::{
-    conversion::ValueConvert, error::QueryError, fields::batch_extract, helper::get_table_name, types::PrimaryKey
+    conversion::ValueConvert, error::QueryError, fields::{batch_extract, get_write_values}, filter::{push_separated_cast_value_bind, push_separated_value_bind}, helper::get_table_name, types::PrimaryKey
 };
+#[cfg(any(feature = "sqlite" , feature = "postgres"))]
+use crate::common::fields::ensure_entity_columns;
 
 /// INSERT 查询构建器
 /// 
@@ -23,14 +25,14 @@ where
 {
     query_builder: QueryBuilder<'a, DB>,
     columns_specified: bool,
-    _phantom: PhantomData<(ET, VAL)>,
+    _phantom: PhantomData<(fn() -> ET, VAL)>,
 }
 
 impl<'a, ET, DB, VAL> Insert<'a, ET, DB, VAL>
 where
     ET: FieldAccess,
     DB: Database,
-    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + Default + 'a,
 {
     /// 开始构建 INSERT 查询（使用实体的默认表名）
     /// 
@@ -119,6 +121,40 @@ where
         models: impl IntoIterator<Item = &'a ET>, 
         primary_key: &PrimaryKey<'a>
     ) -> Result<QueryBuilder<'a, DB>, Error>
+    {
+        Self::many_with_casts(models, primary_key, &[])
+    }
+
+    /// Create multiple records insert operation, casting the placeholders of some columns
+    /// 
+    /// Each placeholder of a listed column is rendered as `?::sql_type` on PostgreSQL and
+    /// `CAST(? AS sql_type)` elsewhere, e.g. to write text into a `uuid` or `jsonb` column.
+    /// 
+    /// # Arguments
+    /// * `models` - Collection of entity models to insert
+    /// * `primary_key` - Primary key definition
+    /// * `casts` - `(column, sql_type)` pairs
+    /// 
+    /// # Returns
+    /// A QueryBuilder with the INSERT query or an Error
+    /// 
+    /// 创建多条记录插入操作，并对部分列的占位符做类型转换
+    /// 
+    /// 所列列的每个占位符在 PostgreSQL 上渲染为 `?::sql_type`，其他数据库为 `CAST(? AS sql_type)`，
+    /// 例如向 `uuid` 或 `jsonb` 列写入文本。
+    /// 
+    /// # 参数
+    /// * `models` - 要插入的实体模型集合
+    /// * `primary_key` - 主键定义
+    /// * `casts` - `(列名, SQL 类型)` 对
+    /// 
+    /// # 返回值
+    /// 包含 INSERT 查询的 QueryBuilder 或错误
+    pub fn many_with_casts(
+        models: impl IntoIterator<Item = &'a ET>, 
+        primary_key: &PrimaryKey<'a>,
+        casts: &[(&str, &str)],
+    ) -> Result<QueryBuilder<'a, DB>, Error>
     {
         let models: Vec<_> = models.into_iter().collect();
         if models.is_empty() {
@@ -131,13 +167,19 @@ where
             vec![]
         };
         let (names, values) = batch_extract::<ET, VAL>(&models, &keys, false);
+        let row_casts: Vec<Option<&str>> = names.iter()
+            .map(|name| casts.iter().find(|(col, _)| col == name).map(|(_, sql_type)| *sql_type))
+            .collect();
         let mut query_builder = Self::table().query_builder;
         query_builder.push(" (").push(names.join(", ")).push(") ");
         query_builder.push_values(
             values,
             |mut b, row| {
-                for value in row {
-                    b.push_bind(value);
+                for (value, cast) in row.into_iter().zip(&row_casts) {
+                    match cast {
+                        Some(sql_type) => push_separated_cast_value_bind(&mut b, value, sql_type),
+                        None => push_separated_value_bind(&mut b, value),
+                    }
                 }
             }
         );
@@ -145,6 +187,45 @@ where
         Ok(query_builder)
     }
 
+    /// Create multiple records insert operation that skips rows conflicting with existing ones
+    /// 
+    /// Appends `ON CONFLICT (conflict_target) DO NOTHING`, or a bare `ON CONFLICT DO NOTHING`
+    /// matching any unique constraint when `conflict_target` is empty. Skipped rows are not an
+    /// error, they just do not count in `rows_affected`. Supported by PostgreSQL and SQLite 3.24+.
+    /// 
+    /// # Arguments
+    /// * `models` - Collection of entity models to insert
+    /// * `primary_key` - Primary key definition
+    /// * `conflict_target` - Unique columns to check, empty for any unique constraint
+    /// 
+    /// # Returns
+    /// A QueryBuilder with the INSERT query or an Error
+    /// 
+    /// 创建跳过与已有行冲突的行的多条记录插入操作
+    /// 
+    /// 追加 `ON CONFLICT (conflict_target) DO NOTHING`，`conflict_target` 为空时追加匹配任意唯一约束的
+    /// `ON CONFLICT DO NOTHING`。被跳过的行不视为错误，只是不计入 `rows_affected`。
+    /// 支持 PostgreSQL 和 SQLite 3.24+。
+    /// 
+    /// # 参数
+    /// * `models` - 要插入的实体模型集合
+    /// * `primary_key` - 主键定义
+    /// * `conflict_target` - 要检查的唯一列，为空时匹配任意唯一约束
+    /// 
+    /// # 返回值
+    /// 包含 INSERT 查询的 QueryBuilder 或错误
+    #[cfg(any(feature = "sqlite" , feature = "postgres"))]
+    pub fn many_or_ignore(
+        models: impl IntoIterator<Item = &'a ET>,
+        primary_key: &PrimaryKey<'a>,
+        conflict_target: &[&str],
+    ) -> Result<QueryBuilder<'a, DB>, Error>
+    {
+        let mut query_builder = Self::many(models, primary_key)?;
+        push_on_conflict_do_nothing(&mut query_builder, conflict_target);
+        Ok(query_builder)
+    }
+
     /// Create single record insert operation
     /// 
     /// # Arguments
@@ -170,30 +251,233 @@ where
         Self::many(once(model), primary_key)
     }
 
+    /// Create a single record insert writing only the given columns
+    /// 
+    /// Columns not listed are omitted from the statement and take their database defaults.
+    /// 
+    /// # Arguments
+    /// * `model` - Entity model to insert
+    /// * `columns` - Columns to insert, taking their values from the entity
+    /// 
+    /// # Returns
+    /// A QueryBuilder with the INSERT query, or an Error if `columns` is empty or names an unknown field
+    /// 
+    /// 创建只写入指定列的单条记录插入操作
+    /// 
+    /// 未列出的列不会出现在语句中，并使用数据库默认值。
+    /// 
+    /// # 参数
+    /// * `model` - 要插入的实体模型
+    /// * `columns` - 要插入的列，值取自实体
+    /// 
+    /// # 返回值
+    /// 包含 INSERT 查询的 QueryBuilder，如果 `columns` 为空或包含未知字段则返回错误
+    pub fn one_with_columns(
+        model: &'a ET,
+        columns: &[&str],
+    ) -> Result<QueryBuilder<'a, DB>, Error>
+    {
+        if columns.is_empty() {
+            return Err(QueryError::ColumnsListEmpty.into());
+        }
+        if let Some(col) = columns.iter().find(|col| model.field_as_any(col).is_none()) {
+            return Err(QueryError::ValueInvalid(col.to_string()).into());
+        }
+
+        let values = get_write_values::<ET, VAL>(model, columns);
+        let mut query_builder = Self::table().columns(columns).query_builder;
+        query_builder.push(" ");
+        query_builder.push_values(
+            once(values),
+            |mut b, row| {
+                for value in row {
+                    push_separated_value_bind(&mut b, value);
+                }
+            }
+        );
+
+        Ok(query_builder)
+    }
+
+    /// Create an insert that writes explicit values into identity columns (PostgreSQL only)
+    /// 
+    /// All fields are written, including the primary key, and `OVERRIDING SYSTEM VALUE`
+    /// is added so that `GENERATED ALWAYS AS IDENTITY` columns accept the provided ids.
+    /// 
+    /// # Arguments
+    /// * `models` - Collection of entity models to insert
+    /// 
+    /// # Returns
+    /// A QueryBuilder with the INSERT query or an Error
+    /// 
+    /// 创建向标识列写入显式值的插入操作（仅 PostgreSQL）
+    /// 
+    /// 会写入包括主键在内的所有字段，并添加 `OVERRIDING SYSTEM VALUE`，
+    /// 使 `GENERATED ALWAYS AS IDENTITY` 列接受提供的 id。
+    /// 
+    /// # 参数
+    /// * `models` - 要插入的实体模型集合
+    /// 
+    /// # 返回值
+    /// 包含 INSERT 查询的 QueryBuilder 或错误
+    #[cfg(feature = "postgres")]
+    pub fn many_with_explicit_id(
+        models: impl IntoIterator<Item = &'a ET>,
+    ) -> Result<QueryBuilder<'a, DB>, Error>
+    {
+        let models: Vec<_> = models.into_iter().collect();
+        if models.is_empty() {
+            return Err(QueryError::NoEntitiesProvided.into());
+        }
+
+        let (names, values) = batch_extract::<ET, VAL>(&models, &[], false);
+        let mut query_builder = Self::table()
+            .columns(names)
+            .overriding_system_value()
+            .query_builder;
+        query_builder.push_values(
+            values,
+            |mut b, row| {
+                for value in row {
+                    push_separated_value_bind(&mut b, value);
+                }
+            }
+        );
+
+        Ok(query_builder)
+    }
+
+    /// Create a single record insert with an explicit identity value (PostgreSQL only)
+    /// 
+    /// # Arguments
+    /// * `model` - Entity model to insert
+    /// 
+    /// # Returns
+    /// A QueryBuilder with the INSERT query or an Error
+    /// 
+    /// 创建带显式标识值的单条记录插入操作（仅 PostgreSQL）
+    /// 
+    /// # 参数
+    /// * `model` - 要插入的实体模型
+    /// 
+    /// # 返回值
+    /// 包含 INSERT 查询的 QueryBuilder 或错误
+    #[cfg(feature = "postgres")]
+    pub fn one_with_explicit_id(
+        model: &'a ET,
+    ) -> Result<QueryBuilder<'a, DB>, Error>
+    {
+        Self::many_with_explicit_id(once(model))
+    }
+
+    /// 添加 OVERRIDING SYSTEM VALUE 子句（仅 PostgreSQL）
+    /// 
+    /// 允许向 `GENERATED ALWAYS AS IDENTITY` 列写入显式值，需在 `columns` 之后、VALUES 之前调用。
+    /// 
+    /// # 返回值
+    /// 更新后的构建器实例
+    #[cfg(feature = "postgres")]
+    pub fn overriding_system_value(mut self) -> Self {
+        self.query_builder.push(" OVERRIDING SYSTEM VALUE ");
+        self
+    }
+
+    /// 添加 `ON CONFLICT (conflict_target) DO NOTHING` 子句（PostgreSQL / SQLite 3.24+），需在 VALUES 之后调用
+    /// 
+    /// # 参数
+    /// * `conflict_target` - 要检查的唯一列，为空时生成匹配任意唯一约束的 `ON CONFLICT DO NOTHING`
+    /// 
+    /// # 返回值
+    /// 更新后的构建器实例
+    #[cfg(any(feature = "sqlite" , feature = "postgres"))]
+    pub fn on_conflict_do_nothing(mut self, conflict_target: &[&str]) -> Self {
+        push_on_conflict_do_nothing(&mut self.query_builder, conflict_target);
+        self
+    }
+
+    /// 添加 `ON CONFLICT ON CONSTRAINT name DO UPDATE SET col = EXCLUDED.col, ...` 子句（仅 PostgreSQL），需在 VALUES 之后调用
+    /// 
+    /// 按约束名指定冲突目标，适用于复合或部分唯一索引等不便列出冲突列的情况。
+    /// 
+    /// # 参数
+    /// * `constraint` - 唯一约束或排他约束的名称
+    /// * `update_cols` - 冲突时从 `EXCLUDED` 更新的列，为空时生成 `DO NOTHING`
+    /// 
+    /// # 返回值
+    /// 更新后的构建器实例
+    #[cfg(feature = "postgres")]
+    pub fn on_conflict_on_constraint(mut self, constraint: &str, update_cols: &[&str]) -> Self {
+        self.query_builder.push(" ON CONFLICT ON CONSTRAINT ").push(constraint);
+        if update_cols.is_empty() {
+            self.query_builder.push(" DO NOTHING");
+        } else {
+            let assignments = update_cols.iter()
+                .map(|col| format!("{} = EXCLUDED.{}", col, col))
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.query_builder.push(" DO UPDATE SET ").push(assignments);
+        }
+        self
+    }
+
     /// 添加 RETURNING 子句
     /// 
+    /// 需要 PostgreSQL 或 SQLite 3.35+。MySQL 不支持 RETURNING，可使用 `insert_one_returning` 读回插入的行。
+    /// 
     /// # 参数
     /// * `columns` - 要返回的列
     /// 
     /// # 返回值
-    /// 更新后的构建器实例
+    /// 更新后的构建器实例；列为空时不添加子句，与 `returning_none` 相同
     #[cfg(any(feature = "sqlite" , feature = "postgres"))]
     pub fn returning<I, S>(mut self, columns: I) -> Self
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
+        let cols: Vec<String> = columns.into_iter().map(|s| s.as_ref().to_string()).collect();
+        if cols.is_empty() {
+            return self;
+        }
+
         self.query_builder.push(" RETURNING ");
-        
-        let cols = columns.into_iter();
         let mut separated = self.query_builder.separated(", ");
         for col in cols {
-            separated.push(col.as_ref());
+            separated.push(col);
         }
         
         self
     }
 
+    /// 添加 RETURNING 子句，并校验各列均为实体字段，避免返回整行或拼错列名
+    /// 
+    /// # 参数
+    /// * `columns` - 要返回的列
+    /// 
+    /// # 返回值
+    /// 更新后的构建器实例；列为空时返回 `QueryError::ColumnsListEmpty`，
+    /// 包含实体没有的字段时返回 `QueryError::ValueInvalid`
+    #[cfg(any(feature = "sqlite" , feature = "postgres"))]
+    pub fn try_returning(self, columns: &[&str]) -> Result<Self, Error>
+    where
+        ET: Default,
+    {
+        ensure_entity_columns::<ET>(columns)?;
+        Ok(self.returning(columns))
+    }
+
+    /// 明确不添加 RETURNING 子句，语句只返回受影响的行数
+    /// 
+    /// 仅作为标记，不修改 SQL：之前调用 `returning` 已写入的 RETURNING 子句不会被移除，
+    /// 因此应在 `returning` 与 `returning_none` 之间二选一。
+    /// 
+    /// # 返回值
+    /// 原构建器实例
+    #[cfg(any(feature = "sqlite" , feature = "postgres"))]
+    pub fn returning_none(self) -> Self {
+        self
+    }
+
     /// 添加自定义查询部分
     /// 
     /// # 参数
@@ -216,4 +500,14 @@ where
     pub fn finish(self) -> QueryBuilder<'a, DB> {
         self.query_builder
     }
-}
\ No newline at end of file
+}
+
+/// 推入 `ON CONFLICT (conflict_target) DO NOTHING`，冲突目标为空时省略括号部分
+#[cfg(any(feature = "sqlite" , feature = "postgres"))]
+fn push_on_conflict_do_nothing<DB: Database>(query_builder: &mut QueryBuilder<'_, DB>, conflict_target: &[&str]) {
+    query_builder.push(" ON CONFLICT");
+    if !conflict_target.is_empty() {
+        query_builder.push(" (").push(conflict_target.join(", ")).push(")");
+    }
+    query_builder.push(" DO NOTHING");
+}
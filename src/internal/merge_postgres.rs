@@ -0,0 +1,260 @@
+use std::marker::PhantomData;
+
+use field_access::FieldAccess;
+use sqlx::{Database, Encode, Error, QueryBuilder, Type};
+
+use crate::common::{
+    conversion::ValueConvert, error::QueryError, fields::batch_extract, filter::push_separated_value_bind, helper::get_table_name, types::PrimaryKey
+};
+
+/// Alias of the MERGE source, referenced by the `when_*` helpers
+const SOURCE_ALIAS: &str = "src";
+
+/// PostgreSQL MERGE query builder (PostgreSQL 15+)
+///
+/// This struct builds `MERGE INTO target USING source ON condition WHEN ... THEN ...` statements,
+/// a more expressive alternative to `INSERT ... ON CONFLICT`: each branch can update, delete or
+/// insert, and no unique constraint is needed on the match columns.
+///
+/// The source is aliased `src`. Actions are emitted in the order the `when_*` methods are
+/// called, and PostgreSQL applies the first branch whose condition holds.
+///
+/// # Type Parameters
+/// * `ET` - Entity type that implements FieldAccess trait
+/// * `DB` - Database type that implements sqlx::Database trait
+/// * `VAL` - Value type that implements Encode, Type, and ValueConvert traits
+///
+/// PostgreSQL MERGE 查询构建器（PostgreSQL 15+）
+///
+/// 该结构体用于构建 `MERGE INTO target USING source ON condition WHEN ... THEN ...` 语句，
+/// 是比 `INSERT ... ON CONFLICT` 更灵活的更新插入方式：每个分支都可以更新、删除或插入，
+/// 且匹配列上不需要唯一约束。
+///
+/// 数据源别名为 `src`。各动作按 `when_*` 方法的调用顺序输出，PostgreSQL 执行第一个条件成立的分支。
+///
+/// # 类型参数
+/// * `ET` - 实现 FieldAccess trait 的实体类型
+/// * `DB` - 实现 sqlx::Database trait 的数据库类型
+/// * `VAL` - 实现 Encode、Type 和 ValueConvert traits 的值类型
+pub struct Merge<'a, ET, DB, VAL>
+where
+    DB: Database,
+{
+    query_builder: QueryBuilder<'a, DB>,
+    source_columns: Vec<String>,
+    key_columns: Vec<String>,
+    auto_generate: bool,
+    _phantom: PhantomData<(fn() -> ET, VAL)>,
+}
+
+impl<'a, ET, DB, VAL> Merge<'a, ET, DB, VAL>
+where
+    ET: FieldAccess,
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + Default + 'a,
+{
+    /// Create a MERGE using entities as a `VALUES` source, matched on the primary key
+    ///
+    /// Generates `MERGE INTO table USING (VALUES (...), ...) AS src (columns) ON table.key = src.key`.
+    ///
+    /// # Arguments
+    /// * `models` - Collection of entity models forming the source rows
+    /// * `primary_key` - Primary key definition, used as the match condition
+    ///
+    /// # Returns
+    /// A new Merge instance, or an Error if no entities are provided
+    ///
+    /// 以实体作为 `VALUES` 数据源创建 MERGE，按主键匹配
+    ///
+    /// 生成 `MERGE INTO table USING (VALUES (...), ...) AS src (columns) ON table.key = src.key`。
+    ///
+    /// # 参数
+    /// * `models` - 组成数据源行的实体模型集合
+    /// * `primary_key` - 主键定义，作为匹配条件
+    ///
+    /// # 返回值
+    /// 新的 Merge 实例，未提供实体时返回错误
+    pub fn using_entities(
+        models: impl IntoIterator<Item = &'a ET>,
+        primary_key: &PrimaryKey<'a>,
+    ) -> Result<Self, Error> {
+        let models: Vec<_> = models.into_iter().collect();
+        if models.is_empty() {
+            return Err(QueryError::NoEntitiesProvided.into());
+        }
+
+        let table_name = get_table_name::<ET>();
+        let keys = primary_key.get_keys();
+        let (names, values) = batch_extract::<ET, VAL>(&models, &[], false);
+
+        let mut query_builder = QueryBuilder::new(format!("MERGE INTO {} USING (", table_name));
+        query_builder.push_values(
+            values,
+            |mut b, row| {
+                for value in row {
+                    push_separated_value_bind(&mut b, value);
+                }
+            }
+        );
+        let on = keys.iter()
+            .map(|key| format!("{}.{} = {}.{}", table_name, key, SOURCE_ALIAS, key))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        query_builder.push(format!(") AS {} ({}) ON {}", SOURCE_ALIAS, names.join(", "), on));
+
+        Ok(Self {
+            query_builder,
+            source_columns: names.iter().map(|name| name.to_string()).collect(),
+            key_columns: keys.iter().map(|key| key.to_string()).collect(),
+            auto_generate: primary_key.auto_generate(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Create a MERGE from a custom source and match condition
+    ///
+    /// The source must be aliased `src` (e.g. `"staging_article AS src"`) and, since its columns
+    /// are unknown, the `when_*` helpers need explicit column lists.
+    ///
+    /// # Arguments
+    /// * `source` - Source table or subquery, aliased `src`
+    /// * `condition` - Match condition, e.g. `"article.id = src.id"`
+    ///
+    /// # Returns
+    /// A new Merge instance
+    ///
+    /// 以自定义数据源和匹配条件创建 MERGE
+    ///
+    /// 数据源别名必须为 `src`（例如 `"staging_article AS src"`），由于其列未知，`when_*` 方法需要显式指定列。
+    ///
+    /// # 参数
+    /// * `source` - 数据源表或子查询，别名为 `src`
+    /// * `condition` - 匹配条件，例如 `"article.id = src.id"`
+    ///
+    /// # 返回值
+    /// 新的 Merge 实例
+    pub fn using(source: &str, condition: &str) -> Self {
+        let query_builder = QueryBuilder::new(format!(
+            "MERGE INTO {} USING {} ON {}", get_table_name::<ET>(), source, condition
+        ));
+
+        Self {
+            query_builder,
+            source_columns: vec![],
+            key_columns: vec![],
+            auto_generate: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Add `WHEN MATCHED THEN UPDATE SET column = src.column, ...`
+    ///
+    /// # Arguments
+    /// * `columns` - Columns to update; empty means all source columns except the key
+    ///
+    /// # Returns
+    /// The updated builder instance, or `QueryError::ColumnsListEmpty` if no column is left to update
+    ///
+    /// 添加 `WHEN MATCHED THEN UPDATE SET column = src.column, ...`
+    ///
+    /// # 参数
+    /// * `columns` - 要更新的列；为空时表示除主键外的所有数据源列
+    ///
+    /// # 返回值
+    /// 更新后的构建器实例，没有可更新的列时返回 `QueryError::ColumnsListEmpty`
+    pub fn when_matched_update(mut self, columns: &[&str]) -> Result<Self, Error> {
+        let columns = self.resolve_columns(columns, true);
+        if columns.is_empty() {
+            return Err(QueryError::ColumnsListEmpty.into());
+        }
+
+        let assignments = columns.iter()
+            .map(|col| format!("{} = {}.{}", col, SOURCE_ALIAS, col))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.query_builder.push(" WHEN MATCHED THEN UPDATE SET ").push(assignments);
+        Ok(self)
+    }
+
+    /// Add `WHEN MATCHED THEN DELETE`
+    ///
+    /// # Returns
+    /// The updated builder instance
+    ///
+    /// 添加 `WHEN MATCHED THEN DELETE`
+    ///
+    /// # 返回值
+    /// 更新后的构建器实例
+    pub fn when_matched_delete(mut self) -> Self {
+        self.query_builder.push(" WHEN MATCHED THEN DELETE");
+        self
+    }
+
+    /// Add `WHEN NOT MATCHED THEN INSERT (columns) VALUES (src.column, ...)`
+    ///
+    /// # Arguments
+    /// * `columns` - Columns to insert; empty means all source columns, without the key if it is auto generated
+    ///
+    /// # Returns
+    /// The updated builder instance, or `QueryError::ColumnsListEmpty` if no column is left to insert
+    ///
+    /// 添加 `WHEN NOT MATCHED THEN INSERT (columns) VALUES (src.column, ...)`
+    ///
+    /// # 参数
+    /// * `columns` - 要插入的列；为空时表示所有数据源列，主键自动生成时不含主键
+    ///
+    /// # 返回值
+    /// 更新后的构建器实例，没有可插入的列时返回 `QueryError::ColumnsListEmpty`
+    pub fn when_not_matched_insert(mut self, columns: &[&str]) -> Result<Self, Error> {
+        let skip_keys = self.auto_generate;
+        let columns = self.resolve_columns(columns, skip_keys);
+        if columns.is_empty() {
+            return Err(QueryError::ColumnsListEmpty.into());
+        }
+
+        let sources = columns.iter()
+            .map(|col| format!("{}.{}", SOURCE_ALIAS, col))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.query_builder.push(" WHEN NOT MATCHED THEN INSERT (")
+            .push(columns.join(", "))
+            .push(") VALUES (")
+            .push(sources)
+            .push(")");
+        Ok(self)
+    }
+
+    /// 添加自定义查询部分，例如带条件的 `WHEN MATCHED AND ... THEN` 分支
+    ///
+    /// # 参数
+    /// * `build_fn` - 自定义构建函数
+    ///
+    /// # 返回值
+    /// 更新后的构建器实例
+    pub fn custom<F>(mut self, build_fn: F) -> Self
+    where
+        F: FnOnce(&mut QueryBuilder<'a, DB>),
+    {
+        build_fn(&mut self.query_builder);
+        self
+    }
+
+    /// 构建最终的查询
+    ///
+    /// # 返回值
+    /// QueryBuilder 实例
+    pub fn finish(self) -> QueryBuilder<'a, DB> {
+        self.query_builder
+    }
+
+    /// 解析动作使用的列：显式指定时原样使用，否则取数据源列（可排除主键）
+    fn resolve_columns(&self, columns: &[&str], skip_keys: bool) -> Vec<String> {
+        if !columns.is_empty() {
+            return columns.iter().map(|col| col.to_string()).collect();
+        }
+        self.source_columns.iter()
+            .filter(|col| !skip_keys || !self.key_columns.contains(col))
+            .cloned()
+            .collect()
+    }
+}
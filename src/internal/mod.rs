@@ -7,9 +7,15 @@ pub mod upsert_sqlite;
 #[cfg(feature = "postgres")]
 pub mod upsert_postgres;
 
+#[cfg(feature = "postgres")]
+pub mod merge_postgres;
+
 #[cfg(feature = "mysql")]
 pub mod upsert_mysql;
 
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+pub mod replace_builder;
+
 pub mod delete_builder;
 pub mod select_builder;
 
@@ -0,0 +1,113 @@
+use std::{iter::once, marker::PhantomData};
+
+use field_access::FieldAccess;
+use sqlx::{Database, Encode, Error, QueryBuilder, Type};
+
+use crate::common::{
+    conversion::ValueConvert, error::QueryError, fields::batch_extract, helper::get_table_name
+};
+
+/// REPLACE INTO query builder (MySQL / SQLite)
+///
+/// Unlike an upsert, `REPLACE INTO` resolves a primary key or unique key conflict by
+/// deleting the existing row and inserting the new one. DELETE and INSERT triggers fire,
+/// auto-increment values may change, and columns not provided are reset to their defaults.
+/// PostgreSQL has no equivalent statement, so this builder is only available for MySQL and SQLite.
+///
+/// # Type Parameters
+/// * `ET` - Entity type that implements FieldAccess trait
+/// * `DB` - Database type that implements sqlx::Database trait
+/// * `VAL` - Value type that implements Encode, Type, and ValueConvert traits
+///
+/// REPLACE INTO 查询构建器（MySQL / SQLite）
+///
+/// 与更新插入不同，`REPLACE INTO` 在主键或唯一键冲突时会先删除已有记录再插入新记录。
+/// 因此会触发 DELETE 和 INSERT 触发器，自增值可能变化，未提供的列会被重置为默认值。
+/// PostgreSQL 没有对应的语句，因此该构建器仅适用于 MySQL 和 SQLite。
+///
+/// # 类型参数
+/// * `ET` - 实现 FieldAccess trait 的实体类型
+/// * `DB` - 实现 sqlx::Database trait 的数据库类型
+/// * `VAL` - 实现 Encode、Type 和 ValueConvert traits 的值类型
+pub struct Replace<'a, ET, DB, VAL>
+where
+    ET: FieldAccess,
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+{
+    _phantom: PhantomData<(&'a ET, DB, VAL)>,
+}
+
+impl<'a, ET, DB, VAL> Replace<'a, ET, DB, VAL>
+where
+    ET: FieldAccess,
+    DB: Database,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + Default + 'a,
+{
+    /// Create multiple records replace operation
+    ///
+    /// All fields are written, including the primary key, since the key is what
+    /// identifies the row to be replaced.
+    ///
+    /// # Arguments
+    /// * `models` - Collection of entity models to replace
+    ///
+    /// # Returns
+    /// A QueryBuilder with the REPLACE INTO query or an Error
+    ///
+    /// 创建多条记录替换操作
+    ///
+    /// 会写入包括主键在内的所有字段，因为主键用于确定要被替换的记录。
+    ///
+    /// # 参数
+    /// * `models` - 要替换的实体模型集合
+    ///
+    /// # 返回值
+    /// 包含 REPLACE INTO 查询的 QueryBuilder 或错误
+    pub fn many(
+        models: impl IntoIterator<Item = &'a ET>,
+    ) -> Result<QueryBuilder<'a, DB>, Error> {
+        let models: Vec<_> = models.into_iter().collect();
+        if models.is_empty() {
+            return Err(QueryError::NoEntitiesProvided.into());
+        }
+
+        let (names, values) = batch_extract::<ET, VAL>(&models, &[], false);
+        let table_name = get_table_name::<ET>();
+
+        let mut query_builder = QueryBuilder::new(
+            format!("REPLACE INTO {} ({}) ", table_name, names.join(", "))
+        );
+        query_builder.push_values(
+            values,
+            |mut b, row| {
+                for value in row {
+                    b.push_bind(value);
+                }
+            }
+        );
+
+        Ok(query_builder)
+    }
+
+    /// Create single record replace operation
+    ///
+    /// # Arguments
+    /// * `model` - Entity model to replace
+    ///
+    /// # Returns
+    /// A QueryBuilder with the REPLACE INTO query or an Error
+    ///
+    /// 创建单条记录替换操作
+    ///
+    /// # 参数
+    /// * `model` - 要替换的实体模型
+    ///
+    /// # 返回值
+    /// 包含 REPLACE INTO 查询的 QueryBuilder 或错误
+    pub fn one(
+        model: &'a ET,
+    ) -> Result<QueryBuilder<'a, DB>, Error> {
+        Self::many(once(model))
+    }
+}
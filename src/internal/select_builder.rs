@@ -1,9 +1,17 @@
-use std::marker::PhantomData;
+use std::{any::Any, marker::PhantomData};
 
-use crate::common::{error::QueryError, filter::push_primary_key_bind, helper::get_table_name, types::{JoinType, PrimaryKey, Order}};
+use crate::{common::{error::QueryError, conversion::ValueConvert, filter::{push_in_large, push_primary_key_bind, push_raw_bind}, helper::{get_table_name, is_valid_identifier}, types::{IndexHint, JoinType, LockMode, PrimaryKey, Order, SampleMethod, WindowFunc}}, internal::subquery::Subquery};
 use field_access::FieldAccess;
 use sqlx::{Database, Encode, Error, QueryBuilder, Type};
 
+/// 尚未写入 ORDER BY 的排序项
+enum PendingOrder<VAL> {
+    /// 列或表达式及排序方向
+    Column(String, Order),
+    /// `order_by_field` 的按值列表排序：列名及期望的值顺序
+    Field(String, Vec<VAL>),
+}
+
 /// Select query builder
 /// 
 /// This struct provides functionality to build complete SELECT SQL queries
@@ -29,12 +37,20 @@ where
 {
     query_builder: QueryBuilder<'a, DB>,
     table_name: String,
+    table_ident: String, // 原始表名或别名，不含索引提示和采样子句，供严格模式校验
     has_from: bool,
     has_filter: bool,
     has_order: bool,
+    order_started: bool,
     has_group_by: bool,
     has_having: bool,
-    _phantom: PhantomData<(ET, VAL)>,
+    strict: bool,
+    invalid_identifier: Option<String>,
+    lock: Option<LockMode>,
+    distinct_on: Vec<String>,
+    orders: Vec<(String, Order)>,
+    pending_orders: Vec<PendingOrder<VAL>>,
+    _phantom: PhantomData<(fn() -> ET, VAL)>,
 }
 
 
@@ -63,14 +79,65 @@ where
     pub fn from_query_with_table(mut qb: QueryBuilder<'a, DB>, table_name: impl Into<String>) -> Self {
         qb.push("SELECT ");
 
+        let table_name = table_name.into();
         Self {
             query_builder: qb,
-            table_name: table_name.into(),
+            table_ident: table_name.clone(),
+            table_name,
             has_from: false,
             has_filter: false,
             has_order: false,
+            order_started: false,
+            has_group_by: false,
+            has_having: false,
+            strict: false,
+            invalid_identifier: None,
+            lock: None,
+            distinct_on: vec![],
+            orders: vec![],
+            pending_orders: vec![],
+            _phantom: PhantomData,
+        }
+    }
+
+    /// 以子查询作为数据源创建 SELECT 构建器
+    /// 
+    /// 生成 `SELECT <实体字段> FROM (subquery) alias`，之后可继续添加过滤、排序和分页。
+    /// 与 `Subquery::into_count` 配合，可由同一个子查询同时派生列表查询和计数查询。
+    /// Select 直接写入 sqlx 的 QueryBuilder，已绑定的参数无法移入另一个构建器，
+    /// 因此不提供 `to_count`，需要计数时应以可克隆的 Subquery 作为基础。
+    /// 
+    /// # Arguments
+    /// * `subquery` - 作为数据源的子查询
+    /// * `alias` - 派生表别名
+    /// 
+    /// # Returns
+    /// 新的 Select 实例
+    pub fn from_subquery<SET>(subquery: Subquery<'a, SET, VAL>, alias: &str) -> Self
+    where
+        SET: FieldAccess + Default,
+    {
+        let mut qb = QueryBuilder::new("SELECT ");
+        qb.push(ET::default().field_names().join(", ")).push(" FROM");
+        subquery.append_to(&mut qb);
+        qb.push(alias);
+
+        Self {
+            query_builder: qb,
+            table_name: alias.to_string(),
+            table_ident: alias.to_string(),
+            has_from: true,
+            has_filter: false,
+            has_order: false,
+            order_started: false,
             has_group_by: false,
             has_having: false,
+            strict: false,
+            invalid_identifier: None,
+            lock: None,
+            distinct_on: vec![],
+            orders: vec![],
+            pending_orders: vec![],
             _phantom: PhantomData,
         }
     }
@@ -92,6 +159,293 @@ where
         self
     }
 
+    /// 以实体字段为查询列，并为可空列提供 `COALESCE` 默认值
+    ///
+    /// 每个 `(col, fallback, alias)` 生成 `COALESCE(col, fallback) AS alias`；别名与实体字段同名时
+    /// 替换该字段，否则追加在实体字段之后。`fallback` 以单引号开头时视为字符串字面量，
+    /// 去掉引号后作为参数绑定（`''` 表示一个单引号）；否则视为列名或表达式原样输出，
+    /// 严格模式下须为普通标识符。与 `columns` 一样，必须在生成 FROM 子句之前调用。
+    ///
+    /// # Arguments
+    /// * `items` - (列名, 回退列或 `'字面量'`, 别名) 列表
+    ///
+    /// # Returns
+    /// 设置了查询列的 Select 实例；字面量引号不成对（如 `'abc`、`'a'b'`），
+    /// 或表达式中的单引号数量为奇数时返回 `QueryError::ValueInvalid`
+    pub fn columns_coalesce(mut self, items: &[(&str, &str, &str)]) -> Result<Self, Error>
+    where
+        VAL: From<String>,
+    {
+        if self.has_from {
+            return Ok(self);
+        }
+        for (col, fallback, alias) in items {
+            let malformed = match fallback.strip_prefix('\'') {
+                Some(rest) => rest.strip_suffix('\'').is_none_or(|literal| literal.replace("''", "").contains('\'')),
+                None => fallback.matches('\'').count() % 2 != 0,
+            };
+            if malformed {
+                return Err(QueryError::ValueInvalid(fallback.to_string()).into());
+            }
+            self.check_identifier(col);
+            if !fallback.starts_with('\'') {
+                self.check_identifier(fallback);
+            }
+            self.check_identifier(alias);
+        }
+
+        let fields: Vec<&str> = ET::default().field_names()
+            .iter()
+            .copied()
+            .filter(|field| !items.iter().any(|(_, _, alias)| alias == field))
+            .collect();
+        let mut separator = "";
+        for field in fields {
+            self.query_builder.push(separator).push(field);
+            separator = ", ";
+        }
+        for (col, fallback, alias) in items {
+            self.query_builder.push(format!("{}COALESCE({}, ", separator, col));
+            match fallback.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+                Some(literal) => self.query_builder.push_bind(VAL::from(literal.replace("''", "'"))),
+                None => self.query_builder.push(*fallback),
+            };
+            self.query_builder.push(format!(") AS {}", alias));
+            separator = ", ";
+        }
+        self.query_builder.push(" FROM ")
+            .push(&self.table_name);
+
+        self.has_from = true;
+        Ok(self)
+    }
+
+    /// 添加索引提示（仅 MySQL 生效）
+    /// 
+    /// 提示会紧跟在表名之后输出，例如 `FROM article USE INDEX (idx_tenant)`。
+    /// PostgreSQL 和 SQLite 没有对应语法，此方法在这些数据库上不做任何处理。
+    /// 必须在 `columns`、`filter`、`join` 等会生成 FROM 子句的方法之前调用。
+    /// 
+    /// # Arguments
+    /// * `hint` - 索引提示
+    /// 
+    /// # Returns
+    /// 添加了索引提示的 Select 实例
+    pub fn index_hint(mut self, hint: IndexHint) -> Self {
+        if DB::NAME == "MySQL" && !self.has_from {
+            self.table_name.push(' ');
+            self.table_name.push_str(&hint.to_sql());
+        }
+        self
+    }
+
+    /// 以实体的全部字段加一个窗口函数列作为查询列
+    /// 
+    /// 生成 `SELECT fields, function OVER (...) AS alias FROM table`。需要多个窗口列或自定义列时，
+    /// 在 `columns` 中使用 `WindowFunc::to_sql`。与 `columns` 一样，必须在生成 FROM 子句之前调用，
+    /// 因此只能调用一次。严格模式下会校验分区列和排序列。
+    /// 
+    /// # Arguments
+    /// * `window` - 窗口函数列
+    /// 
+    /// # Returns
+    /// 添加了窗口函数列的 Select 实例；已生成 FROM 子句时（例如再次调用 `window`，
+    /// 或已调用 `columns`、`distinct`）返回 `QueryError::ClauseAfterFrom`
+    pub fn window(mut self, window: WindowFunc) -> Result<Self, Error> {
+        if self.has_from {
+            return Err(QueryError::ClauseAfterFrom("window".to_string()).into());
+        }
+        for column in window.columns() {
+            self.check_identifier(column);
+        }
+
+        self.query_builder.push(ET::default().field_names().join(", "))
+            .push(", ")
+            .push(window.to_sql())
+            .push(" FROM ")
+            .push(&self.table_name);
+
+        self.has_from = true;
+        Ok(self)
+    }
+
+    /// 以多列去重计数作为查询列
+    /// 
+    /// 按数据库生成不同的写法：
+    /// * PostgreSQL：`COUNT(DISTINCT (a, b))`（行构造器）
+    /// * MySQL：`COUNT(DISTINCT a, b)`
+    /// * SQLite：`COUNT(DISTINCT a || '-' || b)`，拼接后比较，若列值本身包含 `-` 可能产生误合并
+    /// 
+    /// 单列时统一生成 `COUNT(DISTINCT a)`。与 `columns` 一样，必须在生成 FROM 子句之前调用。
+    /// 
+    /// # Arguments
+    /// * `cols` - 参与去重的列名
+    /// 
+    /// # Returns
+    /// 设置了去重计数列的 Select 实例，列为空时返回 `QueryError::ColumnsListEmpty`
+    pub fn count_distinct(mut self, cols: &[&str]) -> Result<Self, Error> {
+        if cols.is_empty() {
+            return Err(QueryError::ColumnsListEmpty.into());
+        }
+        for col in cols {
+            self.check_identifier(col);
+        }
+        self.ensure_identifiers()?;
+        if self.has_from {
+            return Ok(self);
+        }
+
+        let expr = if cols.len() == 1 {
+            cols[0].to_string()
+        } else {
+            match DB::NAME {
+                "PostgreSQL" => format!("({})", cols.join(", ")),
+                "MySQL" => cols.join(", "),
+                _ => cols.join(" || '-' || "),
+            }
+        };
+        self.query_builder.push("COUNT(DISTINCT ")
+            .push(expr)
+            .push(") FROM ")
+            .push(&self.table_name);
+
+        self.has_from = true;
+        Ok(self)
+    }
+
+    /// 设置去重列：`SELECT DISTINCT column FROM table`
+    /// 
+    /// 用于获取某列的所有不同取值（例如筛选下拉框），配合查询模块的 `fetch_column` 使用。
+    /// 软删除等条件可继续通过 `filter` 添加。与 `columns` 一样，必须在生成 FROM 子句之前调用。
+    /// 
+    /// # Arguments
+    /// * `column` - 去重的列名
+    /// 
+    /// # Returns
+    /// 设置了去重列的 Select 实例
+    pub fn distinct(mut self, column: &str) -> Self {
+        if self.has_from {
+            return self;
+        }
+        self.check_identifier(column);
+        self.query_builder.push("DISTINCT ")
+            .push(column)
+            .push(" FROM ")
+            .push(&self.table_name);
+
+        self.has_from = true;
+        self
+    }
+
+    /// 设置 `SELECT DISTINCT ON (columns) ...`（仅 PostgreSQL），每组只保留排序后的第一行
+    /// 
+    /// 常用于"每组最新一行"查询，例如 `distinct_on(&["tenant_id"])` 配合
+    /// `order_by("tenant_id", Asc).order_by("created_at", Desc)`。PostgreSQL 要求 ORDER BY 的前导项
+    /// 与 DISTINCT ON 的列一致，分页、游标和 `try_finish` 会校验这一点，不一致时返回
+    /// `QueryError::DistinctOnOrder`；`finish` 不返回错误，只在调试构建中断言该约束，
+    /// 因此使用 DISTINCT ON 时应以 `try_finish` 结束构建。与 `columns` 一样，必须在生成 FROM 子句之前调用，
+    /// 之后仍可通过 `columns` 指定投影列。
+    /// 
+    /// # Arguments
+    /// * `columns` - 分组去重的列
+    /// 
+    /// # Returns
+    /// 设置了 DISTINCT ON 的 Select 实例；列为空时返回 `QueryError::ColumnsListEmpty`，
+    /// 已生成 FROM 子句时返回 `QueryError::ClauseAfterFrom`，在不支持该语法的 MySQL 和 SQLite 上
+    /// 返回 `QueryError::UnsupportedSyntax`
+    pub fn distinct_on(mut self, columns: &[&str]) -> Result<Self, Error> {
+        if DB::NAME != "PostgreSQL" {
+            return Err(QueryError::UnsupportedSyntax("DISTINCT ON".to_string()).into());
+        }
+        if columns.is_empty() {
+            return Err(QueryError::ColumnsListEmpty.into());
+        }
+        if self.has_from {
+            return Err(QueryError::ClauseAfterFrom("distinct_on".to_string()).into());
+        }
+        for col in columns {
+            self.check_identifier(col);
+        }
+        self.query_builder.push("DISTINCT ON (")
+            .push(columns.join(", "))
+            .push(") ");
+        self.distinct_on = columns.iter().map(|col| col.to_string()).collect();
+        Ok(self)
+    }
+
+    /// 使用 `SELECT * FROM table` 代替默认的实体字段列表
+    ///
+    /// 默认投影按实体字段名显式列出各列，与按名称映射的 `FromRow` 一致，且不受表中额外列或列顺序影响。
+    /// 只有确实需要表的全部列时才使用 `*`，此时返回列的顺序由数据库决定。
+    /// 与 `columns` 一样，必须在生成 FROM 子句之前调用。
+    ///
+    /// # Returns
+    /// 设置了 `*` 投影的 Select 实例
+    pub fn star(mut self) -> Self {
+        if self.has_from {
+            return self;
+        }
+        self.query_builder.push("* FROM ")
+            .push(&self.table_name);
+
+        self.has_from = true;
+        self
+    }
+
+    /// 添加实体的所有字段以及一个 `EXISTS (subquery) AS alias` 计算列
+    /// 
+    /// 子查询可以是关联子查询（例如引用外层表的主键），其绑定值会按顺序传递到外层查询中。
+    /// 与 `columns` 一样，必须在生成 FROM 子句之前调用。
+    /// 
+    /// # Arguments
+    /// * `alias` - 计算列的别名
+    /// * `subquery` - EXISTS 使用的子查询
+    /// 
+    /// # Returns
+    /// 添加了计算列的 Select 实例
+    pub fn column_exists_subquery<SET>(
+        mut self,
+        alias: &str,
+        subquery: Subquery<'a, SET, VAL>,
+    ) -> Self
+    where
+        SET: FieldAccess + Default,
+    {
+        if self.has_from {
+            return self;
+        }
+
+        let columns = ET::default().field_names().join(", ");
+        self.query_builder.push(columns).push(", EXISTS");
+        subquery.append_to(&mut self.query_builder);
+        self.query_builder.push("AS ")
+            .push(alias)
+            .push(" FROM ")
+            .push(&self.table_name);
+
+        self.has_from = true;
+        self
+    }
+
+    /// 添加 TABLESAMPLE 近似采样子句（仅 PostgreSQL 生效）
+    /// 
+    /// 子句紧跟在表名（包括别名）之后、WHERE 之前输出，例如
+    /// `FROM article a TABLESAMPLE SYSTEM (10) WHERE ...`。MySQL 和 SQLite 不支持该语法，
+    /// 此方法在这些数据库上不做任何处理。必须在生成 FROM 子句之前调用。
+    /// 
+    /// # Arguments
+    /// * `method` - 采样方法
+    /// * `percent` - 采样百分比（0 到 100）
+    /// 
+    /// # Returns
+    /// 添加了采样子句的 Select 实例
+    pub fn tablesample(mut self, method: SampleMethod, percent: f64) -> Self {
+        if DB::NAME == "PostgreSQL" && !self.has_from {
+            self.table_name.push_str(&format!(" TABLESAMPLE {} ({})", method.as_str(), percent));
+        }
+        self
+    }
+
     /// 添加所有字段
     fn add_from_clause(&mut self) {
         let columns = ET::default().field_names().join(", ");
@@ -140,18 +494,23 @@ where
         self
     }
 
-    /// 添加 GROUP BY 子句
+    /// 添加 GROUP BY 子句，须在 `having` 之前调用
     /// 
     /// # Arguments
     /// * `field` - 分组字段（可为表达式）
     /// 
     /// # Returns
-    pub fn group_by(mut self, field: impl Into<String>) -> Self {
+    /// 添加了 GROUP BY 的 Select 实例，已写入 HAVING 子句时返回 `QueryError::GroupByAfterHaving`
+    pub fn group_by(mut self, field: impl Into<String>) -> Result<Self, Error> {
+        if self.has_having {
+            return Err(QueryError::GroupByAfterHaving.into());
+        }
         if !self.has_from {
             self.add_from_clause();
         }
 
         let field = field.into();
+        self.check_identifier(&field);
       
         if self.has_group_by {
             self.query_builder.push(", ").push(&field);
@@ -160,24 +519,26 @@ where
             self.has_group_by = true;
         }
         
-        self
+        Ok(self)
     }
 
-    /// 添加 HAVING 子句（必须在 GROUP BY 之后）
+    /// 添加 HAVING 子句（位于 WHERE 和 GROUP BY 之后）
+    /// 
+    /// 多次调用时条件会直接拼接，需自行在条件中写入 `AND` 等连接词。
+    /// 未调用 `group_by` 时整个结果作为一组，例如 `SELECT COUNT(*) FROM t HAVING COUNT(*) > 10`。
     /// 
     /// # Arguments
     /// * `condition` - HAVING 条件构建函数
     /// 
     /// # Returns
-    /// 添加了 HAVING 的 Select 实例   
+    /// 添加了 HAVING 的 Select 实例
     pub fn having(
         mut self,
         condition: impl FnOnce(&mut QueryBuilder<'_, DB>),
     ) -> Self {
-        if !self.has_group_by {
-            return self;
+        if !self.has_from {
+            self.add_from_clause();
         }
-
         if !self.has_having {
             self.query_builder.push(" HAVING ");
             self.has_having = true;
@@ -186,6 +547,35 @@ where
         self
     }
 
+    /// 添加带一个绑定值的 HAVING 条件，例如 `having_bind("COUNT(*) >= ?", value)`
+    /// 
+    /// 条件中的 `?` 占位符绑定 `value`；已有 HAVING 条件时以 `AND` 连接。
+    /// 
+    /// # Arguments
+    /// * `condition` - 带一个 `?` 占位符的条件
+    /// * `value` - 绑定值
+    /// 
+    /// # Returns
+    /// 添加了 HAVING 的 Select 实例，占位符不是恰好一个时返回 `QueryError::PlaceholderMismatch`
+    pub fn having_bind(mut self, condition: &str, value: VAL) -> Result<Self, Error> {
+        let placeholders = condition.matches('?').count();
+        if placeholders != 1 {
+            return Err(QueryError::PlaceholderMismatch(placeholders, 1).into());
+        }
+
+        if !self.has_from {
+            self.add_from_clause();
+        }
+        if self.has_having {
+            self.query_builder.push(" AND ");
+        } else {
+            self.query_builder.push(" HAVING ");
+            self.has_having = true;
+        }
+        push_raw_bind(&mut self.query_builder, condition, vec![value])?;
+        Ok(self)
+    }
+
     /// 通过主键查询
     /// 
     /// # Arguments
@@ -208,6 +598,64 @@ where
         self
     }
 
+    /// 按键列表匹配键列查询
+    /// 
+    /// 条件由 `push_in_large` 生成：在 PostgreSQL 上，`i32`、`i64`、`String` 和 `Uuid` 键
+    /// 作为单个数组参数绑定为 `column = ANY($n)`；其他情况拆分为每组 500 个值的 `IN` 列表。
+    /// 键列表为空时不匹配任何行。
+    /// 
+    /// # Arguments
+    /// * `column` - 键列名
+    /// * `values` - 要匹配的键
+    /// 
+    /// # Returns
+    /// 添加了键条件的 Select 实例
+    pub fn by_keys<T: Any>(mut self, column: &str, values: Vec<T>) -> Self
+    where
+        VAL: ValueConvert,
+    {
+        if !self.has_from {
+            self.add_from_clause();
+        }
+        if !self.has_filter {
+            self.query_builder.push(" WHERE ");
+            self.has_filter = true;
+        } else {
+            self.query_builder.push(" AND ");
+        }
+        push_in_large::<DB, T, VAL>(&mut self.query_builder, column, values);
+        self
+    }
+
+    /// 按数组匹配键列查询（仅 PostgreSQL）
+    /// 
+    /// 生成 `column = ANY($n)`，整个键列表只绑定一个数组参数。已持有数组值（例如
+    /// `DataKind::Int8Array`）时使用；否则使用 `by_keys`，它在 PostgreSQL 上同样生成 `= ANY($n)`。
+    /// 
+    /// # Arguments
+    /// * `column` - 键列名
+    /// * `values` - 包含所有键的数组值，例如 `DataKind::Int8Array`
+    /// 
+    /// # Returns
+    /// 添加了键条件的 Select 实例
+    #[cfg(feature = "postgres")]
+    pub fn by_keys_any(mut self, column: &str, values: VAL) -> Self {
+        if !self.has_from {
+            self.add_from_clause();
+        }
+        if !self.has_filter {
+            self.query_builder.push(" WHERE ");
+            self.has_filter = true;
+        } else {
+            self.query_builder.push(" AND ");
+        }
+        self.query_builder.push(column)
+            .push(" = ANY(")
+            .push_bind(values)
+            .push(")");
+        self
+    }
+
     /// 添加 WHERE 过滤条件
     /// 
     /// # Arguments
@@ -231,6 +679,47 @@ where
         self
     }
 
+    /// 启用严格模式：校验表名及之后传入 `order_by`、`group_by`、`count_distinct`
+    /// 和游标分页的列名是否为普通标识符（参见 `is_valid_identifier`）
+    /// 
+    /// 严格模式下这些参数不能是表达式。发现的第一个非法标识符会在 `try_finish`
+    /// 或返回 `Result` 的方法中以 `QueryError::InvalidIdentifier` 返回。
+    /// 
+    /// # Returns
+    /// 启用了严格模式的 Select 实例
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        let table_ident = self.table_ident.clone();
+        self.check_identifier(&table_ident);
+        self
+    }
+
+    /// 严格模式下记录第一个非法标识符
+    fn check_identifier(&mut self, name: &str) {
+        if self.strict && self.invalid_identifier.is_none() && !is_valid_identifier(name) {
+            self.invalid_identifier = Some(name.to_string());
+        }
+    }
+
+    /// 严格模式下存在非法标识符时返回错误；ORDER BY 前导项与 DISTINCT ON 的列不一致时也返回错误
+    fn ensure_identifiers(&self) -> Result<(), Error> {
+        if let Some(name) = &self.invalid_identifier {
+            return Err(QueryError::InvalidIdentifier(name.clone()).into());
+        }
+        match self.distinct_on_conflict() {
+            Some(item) => Err(QueryError::DistinctOnOrder(item.clone()).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// 返回 ORDER BY 前导项中第一个不属于 DISTINCT ON 列的排序项
+    fn distinct_on_conflict(&self) -> Option<&String> {
+        self.orders.iter()
+            .take(self.distinct_on.len())
+            .map(|(item, _)| item)
+            .find(|item| !self.distinct_on.contains(item))
+    }
+
     /// 添加排序条件
     /// 
     /// # Arguments
@@ -240,26 +729,137 @@ where
     /// # Returns
     /// 添加了排序的 Select 实例
     pub fn order_by(mut self, field: impl Into<String>, order: Order) -> Self {
+        let field = field.into();
+        self.check_identifier(&field);
+        self.push_order(&field, &order);
+        self
+    }
+
+    /// 记录排序项（不做标识符校验），在 `flush_orders` 时才写入 ORDER BY，
+    /// 以便之后的 WHERE 条件（例如游标条件）仍能位于 ORDER BY 之前
+    fn push_order(&mut self, field: &str, order: &Order) {
         if !self.has_from {
             self.add_from_clause();
         }
-        let field = field.into();
-        let order_str = order.as_str();
+        self.has_order = true;
+        self.orders.push((field.to_string(), order.clone()));
+        self.pending_orders.push(PendingOrder::Column(field.to_string(), order.clone()));
+    }
 
-        if !self.has_order {
+    /// 开始或续写 ORDER BY 子句
+    fn push_order_separator(&mut self) {
+        if self.order_started {
+            self.query_builder.push(", ");
+        } else {
             self.query_builder.push(" ORDER BY ");
-            self.has_order = true;
+            self.order_started = true;
+        }
+        self.has_order = true;
+    }
+
+    /// 写入尚未输出的排序项
+    fn flush_orders(&mut self) {
+        for item in std::mem::take(&mut self.pending_orders) {
+            self.push_order_separator();
+            match item {
+                PendingOrder::Column(field, order) => {
+                    self.query_builder.push(field)
+                        .push(" ")
+                        .push(order.as_str());
+                }
+                PendingOrder::Field(column, values) => self.push_field_order(&column, values),
+            }
+        }
+    }
+
+    /// 写入按值列表排序的表达式：MySQL 为 `FIELD(...)`，其他数据库为等价的 `CASE`
+    fn push_field_order(&mut self, column: &str, values: Vec<VAL>) {
+        if DB::NAME == "MySQL" {
+            self.query_builder.push(format!("FIELD({}", column));
+            for value in values {
+                self.query_builder.push(", ").push_bind(value);
+            }
+            self.query_builder.push(")");
         } else {
-            self.query_builder.push(", ");
+            self.query_builder.push(format!("CASE {}", column));
+            for (i, value) in values.into_iter().enumerate() {
+                self.query_builder.push(" WHEN ")
+                    .push_bind(value)
+                    .push(format!(" THEN {}", i + 1));
+            }
+            self.query_builder.push(" ELSE 0 END");
         }
-        self.query_builder.push(&field)
-            .push(" ")
-            .push(order_str);
+    }
+
+    /// 游标分页由游标方法按 `keys` 添加 ORDER BY。之前已设置的排序须与 `keys` 的前导项
+    /// 列名和方向一致，此时不会重复输出；否则会使分页不稳定，返回 `QueryError::CursorOrderConflict`
+    fn ensure_cursor_order(&mut self, keys: &[(&str, Order)]) -> Result<(), Error> {
+        if !self.has_order {
+            return Ok(());
+        }
+        let conflict = if self.order_started {
+            self.orders.first()
+        } else {
+            self.orders.iter()
+                .enumerate()
+                .find(|(i, (item, order))| {
+                    keys.get(*i).is_none_or(|(key, key_order)| key != item || key_order != order)
+                })
+                .map(|(_, item)| item)
+        };
+        if let Some((item, order)) = conflict {
+            return Err(QueryError::CursorOrderConflict(format!("{} {}", item, order.as_str())).into());
+        }
+        self.orders.clear();
+        self.pending_orders.clear();
+        self.has_order = false;
+        Ok(())
+    }
+
+    /// 一次添加多个排序条件，按给定顺序排列
+    /// 
+    /// # Arguments
+    /// * `orders` - (排序字段, 排序方向) 列表
+    /// 
+    /// # Returns
+    /// 添加了排序的 Select 实例
+    pub fn order_by_many(self, orders: &[(&str, Order)]) -> Self {
+        orders.iter()
+            .fold(self, |select, (field, order)| select.order_by(*field, order.clone()))
+    }
+
+    /// 按给定值列表的顺序排序
+    ///
+    /// MySQL 生成 `FIELD(column, ?, ?, ...)`；PostgreSQL 和 SQLite 生成等价的
+    /// `CASE column WHEN ? THEN 1 WHEN ? THEN 2 ... ELSE 0 END`。
+    /// 与 `FIELD` 一致，不在列表中的值排在最前面。`values` 为空时不添加排序。
+    /// 与 `order_by` 一样，排序项在生成最终 SQL 时才写入，因此之后仍可添加过滤条件。
+    ///
+    /// # Arguments
+    /// * `column` - 排序字段
+    /// * `values` - 期望的值顺序
+    ///
+    /// # Returns
+    /// 添加了排序的 Select 实例
+    pub fn order_by_field<T>(mut self, column: &str, values: Vec<T>) -> Self
+    where
+        T: Into<VAL>,
+    {
+        self.check_identifier(column);
+        if values.is_empty() {
+            return self;
+        }
+        if !self.has_from {
+            self.add_from_clause();
+        }
+        self.has_order = true;
+        self.orders.push((format!("CASE {} ... END", column), Order::Asc));
+        self.pending_orders.push(PendingOrder::Field(column.to_string(), values.into_iter().map(Into::into).collect()));
         self
     }
 
     /// 添加传统分页
-    /// 
+    ///
     /// # Arguments
     /// * `page_number` - 页码（从1开始）
     /// * `page_size` - 每页记录数
@@ -272,10 +872,12 @@ where
         if !self.has_from {
             self.add_from_clause();
         }
+        self.ensure_identifiers()?;
         if page_size == 0 || page_number < 1 {
             return Err(QueryError::PageNumberInvalid.into());
         }
         let offset = ((page_number - 1) * page_size) as i64;
+        self.flush_orders();
         let limit = page_size as i64;
         
         self.query_builder
@@ -284,11 +886,135 @@ where
             .push(" OFFSET ")
             .push_bind(VAL::from(offset));
 
+        self.push_lock();
+        Ok(self.query_builder)
+    }
+
+    /// 添加 `FETCH FIRST n ROWS WITH TIES` 子句（仅 PostgreSQL）
+    /// 
+    /// 保留与第 n 行在 ORDER BY 列上取值相同的所有行，因此返回行数可能多于 n。
+    /// 必须先调用 `order_by`，否则返回 `QueryError::OrderByRequired`。
+    /// MySQL 和 SQLite 不支持该语法，会返回错误；可改用窗口函数子查询实现，例如
+    /// `SELECT * FROM (SELECT t.*, RANK() OVER (ORDER BY score DESC) AS rnk FROM t) r WHERE rnk <= n`。
+    /// 
+    /// # Arguments
+    /// * `n` - 保留的行数（不含并列行）
+    /// 
+    /// # Returns
+    pub fn limit_with_ties(mut self, n: u64) -> Result<QueryBuilder<'a, DB>, Error> {
+        if DB::NAME != "PostgreSQL" {
+            return Err(QueryError::Other("FETCH FIRST ... WITH TIES is only supported by PostgreSQL".to_string()).into());
+        }
+        if !self.has_order {
+            return Err(QueryError::OrderByRequired.into());
+        }
+        if n == 0 {
+            return Err(QueryError::LimitInvalid.into());
+        }
+        self.ensure_identifiers()?;
+        self.flush_orders();
+
+        self.query_builder
+            .push(format!(" FETCH FIRST {} ROWS WITH TIES", n));
+
+        self.push_lock();
+        Ok(self.query_builder)
+    }
+
+    /// 随机取一行：按数据库的随机函数排序并只取一行
+    /// 
+    /// SQLite 和 PostgreSQL 使用 `RANDOM()`，MySQL 使用 `RAND()`；配合查询模块的
+    /// `fetch_optional` 使用，没有匹配行时返回 `None`。随机排序需要扫描所有匹配行，
+    /// 适合数据量不大的场景（例如随机推荐）。
+    /// 
+    /// # Returns
+    /// 带 `ORDER BY <随机函数> LIMIT 1` 的 QueryBuilder
+    pub fn random_one(mut self) -> QueryBuilder<'a, DB> {
+        let random = if DB::NAME == "MySQL" { "RAND()" } else { "RANDOM()" };
+        if !self.has_from {
+            self.add_from_clause();
+        }
+        self.flush_orders();
+        self.push_order_separator();
+        self.query_builder.push(random).push(" LIMIT 1");
+        self.push_lock();
+        self.query_builder
+    }
+
+    /// 添加 `FOR UPDATE NOWAIT` 行锁子句（MySQL 8.0+ / PostgreSQL）
+    /// 
+    /// 目标行已被其他事务锁定时立即报错而不是等待；配合查询模块的 `fetch_optional_nowait`
+    /// 在事务中执行，可将该错误转换为 `QueryError::LockNotAvailable`。
+    /// SQLite 没有行级锁，此方法在 SQLite 上不添加任何子句。
+    /// 
+    /// # Returns
+    pub fn for_update_nowait(self) -> QueryBuilder<'a, DB> {
+        self.lock(LockMode::UpdateNoWait).finish()
+    }
+
+    /// 设置行锁模式，锁子句在构建时追加到 ORDER BY / LIMIT / OFFSET 之后
+    /// 
+    /// MySQL 8.0+ 与 PostgreSQL 的语法相同：`FOR UPDATE`、`FOR SHARE`、`FOR UPDATE NOWAIT`、
+    /// `FOR UPDATE SKIP LOCKED`。SQLite 没有行级锁（写事务锁定整个数据库），不添加任何子句。
+    /// 
+    /// # Arguments
+    /// * `mode` - 行锁模式
+    /// 
+    /// # Returns
+    pub fn lock(mut self, mode: LockMode) -> Self {
+        self.lock = Some(mode);
+        self
+    }
+
+    /// 追加行锁子句（SQLite 上不添加）
+    fn push_lock(&mut self) {
+        if let Some(mode) = self.lock.take()
+            && DB::NAME != "SQLite"
+        {
+            self.query_builder.push(" ").push(mode.as_str());
+        }
+    }
+
+    /// 添加多取一行的分页（用于无需 COUNT 的"是否有下一页"判断）
+    /// 
+    /// 实际 LIMIT 为 `page_size + 1`，配合查询模块的 `fetch_page_plus_one` 使用，
+    /// 由其丢弃多取的一行并返回 `has_next`。
+    /// 
+    /// # Arguments
+    /// * `page_number` - 页码（从1开始）
+    /// * `page_size` - 每页记录数
+    /// 
+    /// # Returns
+    pub fn paginate_plus_one(mut self, page_number: u64, page_size: u64) -> Result<QueryBuilder<'a, DB>, Error> 
+    where
+        VAL: From<i64> + 'a,
+    {
+        if !self.has_from {
+            self.add_from_clause();
+        }
+        self.ensure_identifiers()?;
+        if page_size == 0 || page_number < 1 {
+            return Err(QueryError::PageNumberInvalid.into());
+        }
+        let offset = ((page_number - 1) * page_size) as i64;
+        self.flush_orders();
+        let limit = page_size as i64 + 1;
+
+        self.query_builder
+            .push(" LIMIT ")
+            .push_bind(VAL::from(limit))
+            .push(" OFFSET ")
+            .push_bind(VAL::from(offset));
+
+        self.push_lock();
         Ok(self.query_builder)
     }
 
     /// 添加游标分页
     /// 
+    /// 按游标列自动添加 `ORDER BY primary_key sort_order`。之前已通过 `order_by` 设置相同的排序时不会重复添加；
+    /// 设置了其他排序时返回 `QueryError::CursorOrderConflict`，因为其他排序会导致分页不稳定。
+    /// 
     /// # Arguments
     /// * `primary_key` - 主键列名
     /// * `sort_order` - 排序方向
@@ -312,6 +1038,9 @@ where
         if limit < 1 {
             return Err(QueryError::PageNumberInvalid.into());
         }
+        self.check_identifier(primary_key);
+        self.ensure_identifiers()?;
+        self.ensure_cursor_order(&[(primary_key, sort_order.clone())])?;
         if let Some(cursor_value) = current_cursor {
             let operator = if sort_order == Order::Asc { ">" } else { "<" };
             
@@ -327,9 +1056,170 @@ where
                 .push(" ").push_bind(cursor_value);
             
         }
-        self = self.order_by(primary_key, sort_order);        
+        self.push_order(primary_key, &sort_order);
+        self.flush_orders();
         self.query_builder.push(" LIMIT ").push_bind(VAL::from(limit as i64));
         
+        self.push_lock();
+        Ok(self.query_builder)
+    }
+
+    /// 添加可空排序列的游标分页
+    /// 
+    /// 普通的 `>`/`<` 游标比较会跳过 NULL 行。此方法按 `column IS NULL, column, primary_key`
+    /// 排序（NULL 总是排在最后），并生成扩展谓词，保证每一行恰好被访问一次：
+    /// * 游标列非空：`(column > ? OR (column = ? AND primary_key > ?) OR column IS NULL)`
+    /// * 游标列为空：`(column IS NULL AND primary_key > ?)`
+    /// 
+    /// 降序时比较符号取反。排序由此方法添加，之前已设置的排序与之不一致时返回 `QueryError::CursorOrderConflict`。
+    /// 
+    /// # Arguments
+    /// * `column` - 可空的排序列
+    /// * `primary_key` - 用于打破并列的主键列名
+    /// * `sort_order` - 排序方向
+    /// * `current_cursor` - 上一页最后一行的 `(排序列值, 主键值)`
+    /// * `limit` - 返回记录数
+    /// 
+    /// # Returns
+    pub fn cursor_nullable(
+        mut self,
+        column: &'a str,
+        primary_key: &'a str,
+        sort_order: Order,
+        current_cursor: Option<(Option<VAL>, VAL)>,
+        limit: u64
+    ) -> Result<QueryBuilder<'a, DB>, Error>
+    where
+        VAL: From<i64> + Clone + 'a,
+    {
+        if !self.has_from {
+            self.add_from_clause();
+        }
+        if limit < 1 {
+            return Err(QueryError::PageNumberInvalid.into());
+        }
+        self.check_identifier(column);
+        self.check_identifier(primary_key);
+        self.ensure_identifiers()?;
+        let null_order = format!("{} IS NULL", column);
+        self.ensure_cursor_order(&[(&null_order, Order::Asc), (column, sort_order.clone()), (primary_key, sort_order.clone())])?;
+        if let Some((column_value, key_value)) = current_cursor {
+            let operator = if sort_order == Order::Asc { " > " } else { " < " };
+
+            if !self.has_filter {
+                self.query_builder.push(" WHERE ");
+                self.has_filter = true;
+            } else {
+                self.query_builder.push(" AND ");
+            }
+
+            match column_value {
+                Some(column_value) => {
+                    self.query_builder.push("(")
+                        .push(column).push(operator).push_bind(column_value.clone())
+                        .push(" OR (").push(column).push(" = ").push_bind(column_value)
+                        .push(" AND ").push(primary_key).push(operator).push_bind(key_value)
+                        .push(") OR ").push(column).push(" IS NULL)");
+                }
+                None => {
+                    self.query_builder.push("(")
+                        .push(column).push(" IS NULL AND ")
+                        .push(primary_key).push(operator).push_bind(key_value)
+                        .push(")");
+                }
+            }
+        }
+        self.push_order(&null_order, &Order::Asc);
+        self.push_order(column, &sort_order);
+        self.push_order(primary_key, &sort_order);
+        self.flush_orders();
+        self.query_builder.push(" LIMIT ").push_bind(VAL::from(limit as i64));
+
+        self.push_lock();
+        Ok(self.query_builder)
+    }
+
+    /// 添加多列键集游标分页
+    /// 
+    /// 用于排序键单独不唯一的场景，例如按 `(created_at, id)` 排序。所有键方向相同时生成
+    /// 行值比较 `(a, b) > (?, ?)`，方向混合时生成展开形式 `(a > ? OR (a = ? AND b > ?))`，
+    /// 每个键按各自方向取比较符号。最后一个键应唯一（通常为主键），以保证不重不漏。
+    /// 排序由此方法按 `keys` 添加，之前已设置的排序须与 `keys` 的前导项一致，否则返回 `QueryError::CursorOrderConflict`。
+    /// 
+    /// # Arguments
+    /// * `keys` - 排序键及其方向，按优先级排列
+    /// * `current_cursor` - 上一页最后一行的各键值，顺序与 `keys` 一致
+    /// * `limit` - 返回记录数
+    /// 
+    /// # Returns
+    /// 带游标条件、ORDER BY 和 LIMIT 的 QueryBuilder；`keys` 为空时返回 `QueryError::ColumnsListEmpty`，
+    /// 游标值数量与键数量不一致时返回 `QueryError::PlaceholderMismatch`
+    pub fn cursor_multi(
+        mut self,
+        keys: &[(&'a str, Order)],
+        current_cursor: Option<Vec<VAL>>,
+        limit: u64
+    ) -> Result<QueryBuilder<'a, DB>, Error>
+    where
+        VAL: From<i64> + Clone + 'a,
+    {
+        if !self.has_from {
+            self.add_from_clause();
+        }
+        if keys.is_empty() {
+            return Err(QueryError::ColumnsListEmpty.into());
+        }
+        if limit < 1 {
+            return Err(QueryError::PageNumberInvalid.into());
+        }
+        for (key, _) in keys {
+            self.check_identifier(key);
+        }
+        self.ensure_identifiers()?;
+        self.ensure_cursor_order(keys)?;
+        if let Some(values) = current_cursor {
+            if values.len() != keys.len() {
+                return Err(QueryError::PlaceholderMismatch(keys.len(), values.len()).into());
+            }
+            let operator = |order: &Order| if *order == Order::Asc { " > " } else { " < " };
+
+            if !self.has_filter {
+                self.query_builder.push(" WHERE ");
+                self.has_filter = true;
+            } else {
+                self.query_builder.push(" AND ");
+            }
+
+            if keys.iter().all(|(_, order)| *order == keys[0].1) {
+                let columns = keys.iter().map(|(key, _)| *key).collect::<Vec<_>>().join(", ");
+                self.query_builder.push("(").push(columns).push(")").push(operator(&keys[0].1)).push("(");
+                let mut separated = self.query_builder.separated(", ");
+                for value in values {
+                    separated.push_bind(value);
+                }
+                self.query_builder.push(")");
+            } else {
+                self.query_builder.push("(");
+                for (i, (key, order)) in keys.iter().enumerate() {
+                    if i > 0 {
+                        self.query_builder.push(" OR ");
+                    }
+                    self.query_builder.push("(");
+                    for (prefix, value) in keys[..i].iter().zip(values.iter()) {
+                        self.query_builder.push(prefix.0).push(" = ").push_bind(value.clone()).push(" AND ");
+                    }
+                    self.query_builder.push(key).push(operator(order)).push_bind(values[i].clone()).push(")");
+                }
+                self.query_builder.push(")");
+            }
+        }
+        for (key, order) in keys {
+            self.push_order(key, order);
+        }
+        self.flush_orders();
+        self.query_builder.push(" LIMIT ").push_bind(VAL::from(limit as i64));
+
+        self.push_lock();
         Ok(self.query_builder)
     }
 
@@ -337,9 +1227,24 @@ where
     /// 
     /// # Returns
     pub fn finish(mut self) -> QueryBuilder<'a, DB> {
+        debug_assert!(
+            self.distinct_on_conflict().is_none(),
+            "DISTINCT ON columns must lead the ORDER BY, got {:?}", self.distinct_on_conflict()
+        );
         if !self.has_from {
             self.add_from_clause();
         }
+        self.flush_orders();
+        self.push_lock();
         self.query_builder
     }
+
+    /// 构建最终查询，严格模式下校验标识符
+    /// 
+    /// # Returns
+    /// 最终的 QueryBuilder；严格模式下存在非法标识符时返回 `QueryError::InvalidIdentifier`
+    pub fn try_finish(self) -> Result<QueryBuilder<'a, DB>, Error> {
+        self.ensure_identifiers()?;
+        Ok(self.finish())
+    }
 }
\ No newline at end of file
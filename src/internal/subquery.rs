@@ -1,9 +1,9 @@
 use std::marker::PhantomData;
 
 use field_access::FieldAccess;
-use sqlx::{Database, Encode, QueryBuilder, Type};
+use sqlx::{Database, Encode, Error, QueryBuilder, Type};
 
-use crate::common::{helper::get_table_name, types::JoinType};
+use crate::common::{error::QueryError, helper::get_table_name, types::JoinType};
 
 /// Subquery fragment type: strictly distinguishes between text and binding operations
 /// 
@@ -16,6 +16,7 @@ use crate::common::{helper::get_table_name, types::JoinType};
 /// # 变体
 /// * [Text](SubqueryPart::Text) - 文本片段
 /// * [Bind](SubqueryPart::Bind) - 绑定值
+#[derive(Clone)]
 enum SubqueryPart<VAL> {
     Text(String),
     Bind(VAL),
@@ -52,11 +53,31 @@ where
 
     parts: Vec<SubqueryPart<VAL>>,
     table_name: String,
+    column_count: Option<usize>,
     has_from: bool,
     has_filter: bool,
     has_group_by: bool,
     has_having: bool,
-    _phantom: PhantomData<(ET, &'a ())>,
+    _phantom: PhantomData<fn() -> &'a ET>,
+}
+
+impl<'a, ET, VAL> Clone for Subquery<'a, ET, VAL>
+where
+    ET: FieldAccess + Default,
+    VAL: Clone + 'a,
+{
+    fn clone(&self) -> Self {
+        Self {
+            parts: self.parts.clone(),
+            table_name: self.table_name.clone(),
+            column_count: self.column_count,
+            has_from: self.has_from,
+            has_filter: self.has_filter,
+            has_group_by: self.has_group_by,
+            has_having: self.has_having,
+            _phantom: PhantomData,
+        }
+    }
 }
 
 impl<'a, ET, VAL> Subquery<'a, ET, VAL>
@@ -73,6 +94,7 @@ where
         Self {
             parts: vec![SubqueryPart::Text("SELECT ".to_string())],
             table_name: table_name.to_string(),
+            column_count: None,
             has_from: false,  
             has_filter: false, 
             has_group_by: false,
@@ -100,7 +122,12 @@ where
             return self;
         }
 
+        let (start, offset) = match self.parts.last() {
+            Some(SubqueryPart::Text(last)) => (self.parts.len() - 1, last.trim_end().len() + 1),
+            _ => (self.parts.len(), 0),
+        };
         self.push_part(column_build_fn);
+        self.column_count = Some(count_columns(&self.parts[start..], offset));
         self.parts.push(SubqueryPart::Text(format!(" FROM {}", &self.table_name)));
         self.has_from = true;
         self
@@ -164,13 +191,17 @@ where
         self
     }
 
-    /// 添加 GROUP BY 子句
+    /// 添加 GROUP BY 子句，须在 `having` 之前调用
     /// 
     /// # Arguments
     /// * `field` - 分组字段（可为表达式）
     /// 
     /// # Returns
-    pub fn group_by(mut self, field: impl Into<String>) -> Self {
+    /// 添加了 GROUP BY 的 Subquery 实例，已写入 HAVING 子句时返回 `QueryError::GroupByAfterHaving`
+    pub fn group_by(mut self, field: impl Into<String>) -> Result<Self, Error> {
+        if self.has_having {
+            return Err(QueryError::GroupByAfterHaving.into());
+        }
         if !self.has_from {
             self.add_from_clause();
         }
@@ -185,10 +216,12 @@ where
         }
         self.parts.push(SubqueryPart::Text(field.into()));
         
-        self
+        Ok(self)
     }
 
-    /// 添加 HAVING 子句（必须在 GROUP BY 之后）
+    /// 添加 HAVING 子句（位于 WHERE 和 GROUP BY 之后）
+    /// 
+    /// 未调用 `group_by` 时整个结果作为一组。
     /// 
     /// # Arguments
     /// * `condition` - HAVING 条件构建函数
@@ -199,8 +232,8 @@ where
         mut self,
         condition: impl FnOnce(&mut SubqueryBuilder<'_, VAL>),
     ) -> Self {
-        if !self.has_group_by {
-            return self;
+        if !self.has_from {
+            self.add_from_clause();
         }
         if !self.has_having {
             self.parts.push(SubqueryPart::Text(" HAVING ".into()));
@@ -255,6 +288,253 @@ where
         
         query_builder.push(") ");
     }
+
+    /// Check that every placeholder in the subquery has a bound value
+    /// 
+    /// Values added with `push_bind` get their placeholders automatically, so a `?` written
+    /// into `push` text (outside quoted literals) is a placeholder without a value, which
+    /// shifts every later bind. PostgreSQL's `?`, `?|` and `?&` JSONB operators are counted
+    /// too, so `try_append_to` and `append_in_to` skip this check on PostgreSQL, whose
+    /// placeholders are numbered `$n`.
+    /// 
+    /// # Returns
+    /// `Ok(())`, or `QueryError::PlaceholderMismatch(placeholders, values)` for the final SQL
+    /// 
+    /// 检查子查询中的每个占位符都有绑定值
+    /// 
+    /// 通过 `push_bind` 添加的值会自动生成占位符，因此写在 `push` 文本中（引号字面量之外）的 `?`
+    /// 是没有值的占位符，会使之后的所有绑定错位。PostgreSQL 的 `?`、`?|` 和 `?&` JSONB 运算符也会被计入，
+    /// 因此 `try_append_to` 和 `append_in_to` 在 PostgreSQL（占位符为 `$n`）上跳过该检查。
+    /// 
+    /// # 返回值
+    /// `Ok(())`；否则返回最终 SQL 的 `QueryError::PlaceholderMismatch(占位符数, 值数)`
+    pub fn check_placeholders(&self) -> Result<(), Error> {
+        let mut raw = 0;
+        let mut binds = 0;
+        for part in &self.parts {
+            match part {
+                SubqueryPart::Text(text) => raw += count_raw_placeholders(text),
+                SubqueryPart::Bind(_) => binds += 1,
+            }
+        }
+        if raw > 0 {
+            return Err(QueryError::PlaceholderMismatch(raw + binds, binds).into());
+        }
+        Ok(())
+    }
+
+    /// Embed the subquery after checking its placeholders (see `check_placeholders`)
+    /// 
+    /// # Arguments
+    /// * `query_builder` - The parent query builder to append to
+    /// 
+    /// # Returns
+    /// `Ok(())`, or `QueryError::PlaceholderMismatch` without pushing anything
+    /// 
+    /// 检查占位符后嵌入子查询（参见 `check_placeholders`）
+    /// 
+    /// # 参数
+    /// * `query_builder` - 要追加到的父查询构建器
+    /// 
+    /// # 返回值
+    /// `Ok(())`；占位符不匹配时返回 `QueryError::PlaceholderMismatch`，且不推入任何内容
+    pub fn try_append_to<DB>(self, query_builder: &mut QueryBuilder<'a, DB>) -> Result<(), Error>
+    where
+        VAL: Encode<'a, DB> + Type<DB>,
+        DB: Database,
+    {
+        if DB::NAME != "PostgreSQL" {
+            self.check_placeholders()?;
+        }
+        self.append_to(query_builder);
+        Ok(())
+    }
+
+    /// Number of columns the subquery projects
+    /// 
+    /// Custom columns are counted by their top-level commas, so commas inside function
+    /// calls, parentheses or quoted literals are not counted. Without custom columns the
+    /// subquery selects every entity field.
+    /// 
+    /// 子查询投影的列数
+    /// 
+    /// 自定义列按顶层逗号计数，函数调用、括号或引号字面量中的逗号不计入。
+    /// 未自定义列时子查询选择实体的全部字段。
+    pub fn projected_columns(&self) -> usize {
+        self.column_count
+            .unwrap_or_else(|| ET::default().field_names().len())
+    }
+
+    /// Embed the subquery as an `IN` condition: `column IN (subquery)`
+    /// 
+    /// An `IN` subquery must return exactly one column; anything else would produce
+    /// malformed SQL, so it is rejected before anything is pushed.
+    /// 
+    /// # Arguments
+    /// * `query_builder` - The parent query builder to append to
+    /// * `column` - Column (or expression) compared with the subquery
+    /// 
+    /// # Returns
+    /// `Ok(())`, or `QueryError::SubqueryColumnCount` if the subquery does not project one column,
+    /// or `QueryError::PlaceholderMismatch` (see `check_placeholders`)
+    /// 
+    /// 以 `IN` 条件嵌入子查询：`column IN (subquery)`
+    /// 
+    /// `IN` 子查询必须只返回一列，否则会生成错误的 SQL，因此在推入任何内容之前即被拒绝。
+    /// 
+    /// # 参数
+    /// * `query_builder` - 要追加到的父查询构建器
+    /// * `column` - 与子查询比较的列（或表达式）
+    /// 
+    /// # 返回值
+    /// `Ok(())`；子查询投影列数不为一时返回 `QueryError::SubqueryColumnCount`，
+    /// 占位符不匹配时返回 `QueryError::PlaceholderMismatch`（参见 `check_placeholders`）
+    pub fn append_in_to<DB>(self, query_builder: &mut QueryBuilder<'a, DB>, column: &str) -> Result<(), Error>
+    where
+        VAL: Encode<'a, DB> + Type<DB>,
+        DB: Database,
+    {
+        let count = self.projected_columns();
+        if count != 1 {
+            return Err(QueryError::SubqueryColumnCount(count).into());
+        }
+        if DB::NAME != "PostgreSQL" {
+            self.check_placeholders()?;
+        }
+
+        query_builder.push(column).push(" IN");
+        self.append_to(query_builder);
+        Ok(())
+    }
+
+    /// Build a count query over the subquery
+    /// 
+    /// The subquery is wrapped as a derived table, so WHERE, JOIN, GROUP BY and HAVING are all
+    /// preserved and a grouped subquery counts its groups. Clone the subquery first to reuse
+    /// it as the list base, e.g. with `Select::from_subquery`.
+    /// 
+    /// # Type Parameters
+    /// * `DB` - Database type that implements sqlx::Database trait
+    /// 
+    /// # Returns
+    /// A QueryBuilder with `SELECT COUNT(*) FROM (...) AS count_base`
+    /// 
+    /// 基于子查询构建计数查询
+    /// 
+    /// 子查询被包装为派生表，因此 WHERE、JOIN、GROUP BY 和 HAVING 都会保留，
+    /// 分组子查询统计的是分组数。先克隆子查询即可将其同时用作列表查询的基础，
+    /// 例如配合 `Select::from_subquery`。
+    /// 
+    /// # 类型参数
+    /// * `DB` - 实现 sqlx::Database trait 的数据库类型
+    /// 
+    /// # 返回值
+    /// 包含 `SELECT COUNT(*) FROM (...) AS count_base` 的 QueryBuilder
+    pub fn into_count<DB>(self) -> QueryBuilder<'a, DB>
+    where
+        VAL: Encode<'a, DB> + Type<DB>,
+        DB: Database,
+    {
+        let mut query_builder = QueryBuilder::new("SELECT COUNT(*) FROM");
+        self.append_to(&mut query_builder);
+        query_builder.push("AS count_base");
+        query_builder
+    }
+
+    /// Build an existence query over the subquery
+    /// 
+    /// Produces a single-row, single-column result that is true when the subquery returns
+    /// any row; run it with the backend's `fetch_exists`.
+    /// 
+    /// # Type Parameters
+    /// * `DB` - Database type that implements sqlx::Database trait
+    /// 
+    /// # Returns
+    /// A QueryBuilder with `SELECT EXISTS (...)`
+    /// 
+    /// 基于子查询构建存在性查询
+    /// 
+    /// 生成单行单列结果，子查询返回任意行时为真；使用各数据库的 `fetch_exists` 执行。
+    /// 
+    /// # 类型参数
+    /// * `DB` - 实现 sqlx::Database trait 的数据库类型
+    /// 
+    /// # 返回值
+    /// 包含 `SELECT EXISTS (...)` 的 QueryBuilder
+    pub fn into_exists<DB>(self) -> QueryBuilder<'a, DB>
+    where
+        VAL: Encode<'a, DB> + Type<DB>,
+        DB: Database,
+    {
+        let mut query_builder = QueryBuilder::new("SELECT EXISTS");
+        self.append_to(&mut query_builder);
+        query_builder
+    }
+}
+
+/// Count `?` placeholders written as text, ignoring those inside quoted literals
+/// 
+/// 统计以文本形式写入的 `?` 占位符，忽略引号字面量中的 `?`
+fn count_raw_placeholders(text: &str) -> usize {
+    let mut count = 0;
+    let mut quote: Option<char> = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '?') => count += 1,
+            _ => {}
+        }
+    }
+    count
+}
+
+/// Count top-level comma separated items in a column list, skipping the first `offset`
+/// bytes of the first part (text pushed before the columns)
+/// 
+/// 统计列列表中顶层逗号分隔的项数，跳过首个片段的前 `offset` 个字节（列之前已推入的文本）
+fn count_columns<VAL>(parts: &[SubqueryPart<VAL>], offset: usize) -> usize {
+    let mut count = 0;
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut has_item = false;
+
+    for (i, part) in parts.iter().enumerate() {
+        let text = match part {
+            SubqueryPart::Text(text) if i == 0 => text.get(offset..).unwrap_or(""),
+            SubqueryPart::Text(text) => text.as_str(),
+            SubqueryPart::Bind(_) => {
+                has_item = true;
+                continue;
+            }
+        };
+        for c in text.chars() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '\'' | '"' | '`') => {
+                    quote = Some(c);
+                    has_item = true;
+                }
+                (None, '(') => {
+                    depth += 1;
+                    has_item = true;
+                }
+                (None, ')') => depth = depth.saturating_sub(1),
+                (None, ',') if depth == 0 => {
+                    count += 1;
+                    has_item = false;
+                }
+                (None, c) if !c.is_whitespace() => has_item = true,
+                _ => {}
+            }
+        }
+    }
+    if has_item {
+        count += 1;
+    }
+    count
 }
 
 /// Builder dedicated to subquery construction
@@ -4,8 +4,11 @@ use field_access::FieldAccess;
 use sqlx::{Database, Encode, Error, QueryBuilder, Type};
 
 use crate::common::{
-    conversion::ValueConvert, error::QueryError, fields::extract_with_bind, filter::push_primary_key_conditions, helper::get_table_name, types::PrimaryKey
+    conversion::ValueConvert, error::QueryError, fields::{batch_extract, extract_table_fields, get_value, get_write_values}, filter::{push_cast_value_bind, push_primary_key_conditions, push_value_bind}, helper::get_table_name, types::PrimaryKey
 };
+#[cfg(any(feature = "sqlite" , feature = "postgres"))]
+use crate::common::fields::ensure_entity_columns;
+use crate::internal::subquery::Subquery;
 
 /// Update query builder
 /// 
@@ -29,7 +32,9 @@ where
     DB: Database,
 {
     query_builder: QueryBuilder<'a, DB>,
-    _phantom: PhantomData<(ET, VAL)>,
+    has_set: bool,
+    pending_from: Option<(String, String)>,
+    _phantom: PhantomData<(fn() -> ET, VAL)>,
 }
 
 /// Update operations
@@ -87,9 +92,85 @@ where
 
         Self {
             query_builder,
+            has_set: false,
+            pending_from: None,
             _phantom: PhantomData,
         }
     }
+
+    /// Create an UPDATE that reads from a joined table, e.g. to set a column from a related row
+    /// 
+    /// MySQL places the join before SET: `UPDATE table JOIN other ON condition SET ...`.
+    /// PostgreSQL and SQLite (3.33+) use `UPDATE table SET ... FROM other WHERE condition`; the
+    /// FROM clause is written before `filter`'s conditions, which are then joined with `AND`,
+    /// or at `returning`/`finish` when there is no filter.
+    /// 
+    /// # Arguments
+    /// * `other` - Joined table
+    /// * `on_condition` - Join condition, e.g. `"tenant.id = article.tenant_id"`
+    /// 
+    /// # Returns
+    /// A new Update instance
+    /// 
+    /// 创建从关联表读取数据的 UPDATE，例如用关联行的值设置某列
+    /// 
+    /// MySQL 将连接放在 SET 之前：`UPDATE table JOIN other ON condition SET ...`。
+    /// PostgreSQL 和 SQLite（3.33+）使用 `UPDATE table SET ... FROM other WHERE condition`；
+    /// FROM 子句在 `filter` 的条件之前写入，两者以 `AND` 连接；没有 filter 时在 `returning`/`finish` 时写入。
+    /// 
+    /// # 参数
+    /// * `other` - 关联的表
+    /// * `on_condition` - 连接条件，例如 `"tenant.id = article.tenant_id"`
+    /// 
+    /// # 返回值
+    /// 新的 Update 实例
+    pub fn with_join(other: &str, on_condition: &str) -> Self {
+        let table_name = get_table_name::<ET>();
+        if DB::NAME == "MySQL" {
+            return Self::from_query_with_table(
+                QueryBuilder::new(""),
+                format!("{} JOIN {} ON {}", table_name, other, on_condition),
+            );
+        }
+
+        let mut update = Self::with_table(table_name);
+        update.pending_from = Some((other.to_string(), on_condition.to_string()));
+        update
+    }
+
+    /// Create an UPDATE preceded by a common table expression
+    /// 
+    /// Renders `WITH name AS (subquery) UPDATE table SET ...`, with the subquery's bound values placed
+    /// before those of the statement. The CTE can then be referenced in the conditions,
+    /// e.g. `id IN (SELECT id FROM name)`. Needs MySQL 8.0+ on MySQL.
+    /// 
+    /// # Arguments
+    /// * `name` - Name of the common table expression
+    /// * `cte` - Subquery defining the common table expression
+    /// 
+    /// # Returns
+    /// A new Update instance
+    /// 
+    /// 创建前置公用表表达式的 UPDATE
+    /// 
+    /// 生成 `WITH name AS (subquery) UPDATE table SET ...`，子查询的绑定值位于语句的绑定值之前。
+    /// 之后可在条件中引用该 CTE，例如 `id IN (SELECT id FROM name)`。MySQL 需要 8.0 及以上版本。
+    /// 
+    /// # 参数
+    /// * `name` - 公用表表达式的名称
+    /// * `cte` - 定义公用表表达式的子查询
+    /// 
+    /// # 返回值
+    /// 新的 Update 实例
+    pub fn with_cte<CT>(name: &str, cte: Subquery<'a, CT, VAL>) -> Self
+    where
+        CT: FieldAccess + Default,
+        VAL: 'a,
+    {
+        let mut query_builder = QueryBuilder::new(format!("WITH {} AS", name));
+        cte.append_to(&mut query_builder);
+        Self::from_query(query_builder)
+    }
    
     /// Create a single entity update operation
     /// 
@@ -132,22 +213,23 @@ where
         };
 
         let mut query_builder = Self::table().query_builder;
-        let mut first = true;
-        let fields = extract_with_bind::<VAL, _>(
+        let (names, values) = extract_table_fields::<VAL>(
+            &get_table_name::<ET>(),
             model.fields(),
             &filter_keys,
             skip_non_null,
-            |name, value| {
-                if !first {
-                    query_builder.push(", ");
-                }
-                first = false;
-                query_builder.push(format!("{} = ", name)).push_bind(value);
-            },
+            Some(&primary_key.get_keys()),
         );
-        if fields.0.is_empty() {    
+        if names.is_empty() {    
             return Err(QueryError::ColumnsListEmpty.into());
         }
+        for (i, (name, value)) in names.into_iter().zip(values).enumerate() {
+            if i > 0 {
+                query_builder.push(", ");
+            }
+            query_builder.push(format!("{} = ", name));
+            push_value_bind(&mut query_builder, value);
+        }
 
         query_builder.push(" WHERE ");
         push_primary_key_conditions::<ET, DB, VAL>(&mut query_builder, model, &keys);
@@ -155,6 +237,294 @@ where
         Ok(query_builder)
     }
 
+    /// Create a batch update of several entities as a single statement
+    /// 
+    /// Each non-key column gets one `CASE` over the primary key, so `n` entities are written in
+    /// one round trip instead of `n` UPDATE statements:
+    /// `UPDATE t SET col = CASE id WHEN ? THEN ? ... ELSE col END, ... WHERE id IN (?, ...)`.
+    /// Composite keys use `CASE WHEN a = ? AND b = ? THEN ? ...` and an OR of the key conditions.
+    /// The primary key columns are never assigned.
+    /// 
+    /// # Arguments
+    /// * `models` - Collection of entity models to update
+    /// * `primary_key` - Primary key definition
+    /// 
+    /// # Returns
+    /// A QueryBuilder with the UPDATE query, or an Error if no entities or no updatable columns are given
+    /// 
+    /// 以单条语句创建多个实体的批量更新操作
+    /// 
+    /// 每个非主键列生成一个基于主键的 `CASE`，使 `n` 个实体通过一次往返写入，而不是 `n` 条 UPDATE 语句：
+    /// `UPDATE t SET col = CASE id WHEN ? THEN ? ... ELSE col END, ... WHERE id IN (?, ...)`。
+    /// 组合主键使用 `CASE WHEN a = ? AND b = ? THEN ? ...`，WHERE 为各主键条件的 OR。
+    /// 主键列不会被赋值。
+    /// 
+    /// # 参数
+    /// * `models` - 要更新的实体模型集合
+    /// * `primary_key` - 主键定义
+    /// 
+    /// # 返回值
+    /// 包含 UPDATE 查询的 QueryBuilder，未提供实体或没有可更新的列时返回错误
+    pub fn many(
+        models: impl IntoIterator<Item = &'a ET>,
+        primary_key: &PrimaryKey<'a>,
+    ) -> Result<QueryBuilder<'a, DB>, Error>
+    where
+        VAL: ValueConvert + Default,
+    {
+        let models: Vec<_> = models.into_iter().collect();
+        if models.is_empty() {
+            return Err(QueryError::NoEntitiesProvided.into());
+        }
+
+        let keys = primary_key.get_keys();
+        let (names, values) = batch_extract::<ET, VAL>(&models, &keys, false);
+        if names.is_empty() {
+            return Err(QueryError::ColumnsListEmpty.into());
+        }
+
+        let single_key = match keys.as_slice() {
+            [key] => Some(*key),
+            _ => None,
+        };
+        let mut rows: Vec<_> = values.into_iter().map(|row| row.into_iter()).collect();
+        let mut query_builder = Self::table().query_builder;
+        for (i, name) in names.iter().enumerate() {
+            if i > 0 {
+                query_builder.push(", ");
+            }
+            query_builder.push(format!("{} = CASE", name));
+            if let Some(key) = single_key {
+                query_builder.push(format!(" {}", key));
+            }
+            for (model, row) in models.iter().zip(rows.iter_mut()) {
+                query_builder.push(" WHEN ");
+                match single_key {
+                    Some(key) => {
+                        query_builder.push_bind(get_value::<ET, VAL>(*model, key));
+                    }
+                    None => push_primary_key_conditions::<ET, DB, VAL>(&mut query_builder, *model, primary_key),
+                }
+                query_builder.push(" THEN ");
+                push_value_bind(&mut query_builder, row.next().unwrap_or_default());
+            }
+            query_builder.push(format!(" ELSE {} END", name));
+        }
+
+        query_builder.push(" WHERE ");
+        match single_key {
+            Some(key) => {
+                query_builder.push(format!("{} IN (", key));
+                let mut separated = query_builder.separated(", ");
+                for model in &models {
+                    separated.push_bind(get_value::<ET, VAL>(*model, key));
+                }
+                query_builder.push(")");
+            }
+            None => {
+                for (i, model) in models.iter().enumerate() {
+                    if i > 0 {
+                        query_builder.push(" OR ");
+                    }
+                    query_builder.push("(");
+                    push_primary_key_conditions::<ET, DB, VAL>(&mut query_builder, *model, primary_key);
+                    query_builder.push(")");
+                }
+            }
+        }
+
+        Ok(query_builder)
+    }
+
+
+    /// Create an update assigning one value to a column on all rows matching a condition
+    /// 
+    /// As a guard against accidental full-table writes, the condition must not be empty.
+    /// 
+    /// # Arguments
+    /// * `column` - Column to assign
+    /// * `value` - Value to assign
+    /// * `filter_build_fn` - Function to build the WHERE conditions
+    /// 
+    /// # Returns
+    /// A QueryBuilder with the UPDATE query, or `QueryError::WhereClauseRequired` if the condition is empty
+    /// 
+    /// 创建将同一个值赋给所有匹配条件记录的某一列的更新操作
+    /// 
+    /// 为防止意外的全表写入，条件不能为空。
+    /// 
+    /// # 参数
+    /// * `column` - 要赋值的列
+    /// * `value` - 要赋的值
+    /// * `filter_build_fn` - 构建 WHERE 条件的函数
+    /// 
+    /// # 返回值
+    /// 包含 UPDATE 查询的 QueryBuilder，条件为空时返回 `QueryError::WhereClauseRequired`
+    pub fn set_where(
+        column: &str,
+        value: VAL,
+        filter_build_fn: impl FnOnce(&mut QueryBuilder<'a, DB>),
+    ) -> Result<QueryBuilder<'a, DB>, Error>
+    where
+        VAL: ValueConvert,
+    {
+        let mut query_builder = Self::table().query_builder;
+        query_builder.push(column).push(" = ");
+        push_value_bind(&mut query_builder, value);
+        query_builder.push(" WHERE ");
+
+        let sql_len = query_builder.sql().len();
+        filter_build_fn(&mut query_builder);
+        if query_builder.sql()[sql_len..].trim().is_empty() {
+            return Err(QueryError::WhereClauseRequired.into());
+        }
+
+        Ok(query_builder)
+    }
+
+    /// Add `column = ?` assignments taking the values of the given columns from an entity
+    /// 
+    /// Assignments are comma separated from each other and from earlier `set_from_entity`
+    /// calls. Further SET expressions can be appended with `custom`, starting with `", "`.
+    /// 
+    /// # Arguments
+    /// * `model` - Entity model to read the values from
+    /// * `columns` - Columns to assign
+    /// 
+    /// # Returns
+    /// The updated builder instance, or an Error if `columns` is empty or names an unknown field
+    /// 
+    /// 添加 `column = ?` 赋值，值取自实体的指定列
+    /// 
+    /// 各赋值之间以及与之前的 `set_from_entity` 调用之间以逗号分隔。
+    /// 其他 SET 表达式可通过 `custom` 追加，需以 `", "` 开头。
+    /// 
+    /// # 参数
+    /// * `model` - 读取值的实体模型
+    /// * `columns` - 要赋值的列
+    /// 
+    /// # 返回值
+    /// 更新后的构建器实例，如果 `columns` 为空或包含未知字段则返回错误
+    pub fn set_from_entity(
+        mut self,
+        model: &ET,
+        columns: &[&str],
+    ) -> Result<Self, Error>
+    where
+        VAL: ValueConvert + Default,
+    {
+        if columns.is_empty() {
+            return Err(QueryError::ColumnsListEmpty.into());
+        }
+        if let Some(col) = columns.iter().find(|col| model.field_as_any(col).is_none()) {
+            return Err(QueryError::ValueInvalid(col.to_string()).into());
+        }
+
+        let values = get_write_values::<ET, VAL>(model, columns);
+        for (col, value) in columns.iter().zip(values) {
+            if self.has_set {
+                self.query_builder.push(", ");
+            }
+            self.has_set = true;
+            self.query_builder.push(format!("{} = ", col));
+            push_value_bind(&mut self.query_builder, value);
+        }
+
+        Ok(self)
+    }
+
+    /// Add a `column = ?` assignment whose placeholder is cast to `sql_type`
+    ///
+    /// Emits `column = ?::sql_type` on PostgreSQL and `column = CAST(? AS sql_type)` elsewhere,
+    /// e.g. to write text into a `uuid` or `jsonb` column.
+    ///
+    /// # Arguments
+    /// * `column` - Column to assign
+    /// * `value` - Value to assign
+    /// * `sql_type` - SQL type to cast the placeholder to
+    ///
+    /// # Returns
+    /// The updated builder instance
+    ///
+    /// 添加占位符转换为 `sql_type` 的 `column = ?` 赋值
+    ///
+    /// PostgreSQL 生成 `column = ?::sql_type`，其他数据库生成 `column = CAST(? AS sql_type)`，
+    /// 例如向 `uuid` 或 `jsonb` 列写入文本。
+    ///
+    /// # 参数
+    /// * `column` - 要赋值的列
+    /// * `value` - 要赋的值
+    /// * `sql_type` - 占位符要转换成的 SQL 类型
+    ///
+    /// # 返回值
+    /// 更新后的构建器实例
+    pub fn set_cast(mut self, column: &str, value: VAL, sql_type: &str) -> Self
+    where
+        VAL: ValueConvert,
+    {
+        if self.has_set {
+            self.query_builder.push(", ");
+        }
+        self.has_set = true;
+
+        self.query_builder.push(format!("{} = ", column));
+        push_cast_value_bind(&mut self.query_builder, value, sql_type);
+        self
+    }
+
+    /// Add an assignment appending one element to a JSON array column
+    ///
+    /// Emits `column = column || ?::jsonb` on PostgreSQL, `column = JSON_ARRAY_APPEND(column, '$', ?)`
+    /// on MySQL and `column = json_insert(column, '$[#]', ?)` on SQLite, which has no
+    /// `JSON_ARRAY_APPEND`. On PostgreSQL the bound value must be JSON text or a JSON value;
+    /// appending a JSON array there concatenates its elements.
+    ///
+    /// # Arguments
+    /// * `column` - JSON array column to append to
+    /// * `value` - Element to append
+    ///
+    /// # Returns
+    /// The updated builder instance
+    ///
+    /// 添加向 JSON 数组列追加一个元素的赋值
+    ///
+    /// PostgreSQL 生成 `column = column || ?::jsonb`，MySQL 生成 `column = JSON_ARRAY_APPEND(column, '$', ?)`，
+    /// SQLite 没有 `JSON_ARRAY_APPEND`，生成 `column = json_insert(column, '$[#]', ?)`。
+    /// 在 PostgreSQL 上绑定值必须是 JSON 文本或 JSON 值，追加 JSON 数组时会拼接其元素。
+    ///
+    /// # 参数
+    /// * `column` - 要追加的 JSON 数组列
+    /// * `value` - 要追加的元素
+    ///
+    /// # 返回值
+    /// 更新后的构建器实例
+    pub fn json_array_append(mut self, column: &str, value: VAL) -> Self {
+        if self.has_set {
+            self.query_builder.push(", ");
+        }
+        self.has_set = true;
+
+        self.query_builder.push(format!("{} = ", column));
+        match DB::NAME {
+            "PostgreSQL" => {
+                self.query_builder.push(format!("{} || ", column))
+                    .push_bind(value)
+                    .push("::jsonb");
+            }
+            "MySQL" => {
+                self.query_builder.push(format!("JSON_ARRAY_APPEND({}, '$', ", column))
+                    .push_bind(value)
+                    .push(")");
+            }
+            _ => {
+                self.query_builder.push(format!("json_insert({}, '$[#]', ", column))
+                    .push_bind(value)
+                    .push(")");
+            }
+        }
+
+        self
+    }
 
     /// Add custom query parts to the builder
     /// 
@@ -198,8 +568,17 @@ where
         mut self,
         filter_build_fn: impl FnOnce(&mut QueryBuilder<'a, DB>),
     ) -> Self {
-        self.query_builder.push(" WHERE ");
-        filter_build_fn(&mut self.query_builder);
+        match self.pending_from.take() {
+            Some((other, on_condition)) => {
+                self.query_builder.push(format!(" FROM {} WHERE {} AND (", other, on_condition));
+                filter_build_fn(&mut self.query_builder);
+                self.query_builder.push(")");
+            }
+            None => {
+                self.query_builder.push(" WHERE ");
+                filter_build_fn(&mut self.query_builder);
+            }
+        }
 
         self
     }
@@ -210,16 +589,20 @@ where
     /// * `columns` - 要返回的列
     /// 
     /// # 返回值
-    /// 更新后的构建器实例
+    /// 更新后的构建器实例；列为空时不添加子句，与 `returning_none` 相同
     #[cfg(any(feature = "sqlite" , feature = "postgres"))]
     pub fn returning<I, S>(mut self, columns: I) -> Self
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        self.query_builder.push(" RETURNING ");
-        
         let cols: Vec<String> = columns.into_iter().map(|s| s.as_ref().to_string()).collect();
+        if cols.is_empty() {
+            return self;
+        }
+        self.push_pending_from();
+
+        self.query_builder.push(" RETURNING ");
         let mut separated = self.query_builder.separated(", ");
         for col in cols {
             separated.push(col);
@@ -228,6 +611,35 @@ where
         self
     }
 
+    /// 添加 RETURNING 子句，并校验各列均为实体字段，避免返回整行或拼错列名
+    /// 
+    /// # 参数
+    /// * `columns` - 要返回的列
+    /// 
+    /// # 返回值
+    /// 更新后的构建器实例；列为空时返回 `QueryError::ColumnsListEmpty`，
+    /// 包含实体没有的字段时返回 `QueryError::ValueInvalid`
+    #[cfg(any(feature = "sqlite" , feature = "postgres"))]
+    pub fn try_returning(self, columns: &[&str]) -> Result<Self, Error>
+    where
+        ET: Default,
+    {
+        ensure_entity_columns::<ET>(columns)?;
+        Ok(self.returning(columns))
+    }
+
+    /// 明确不添加 RETURNING 子句，语句只返回受影响的行数
+    /// 
+    /// 仅作为标记，不修改 SQL：之前调用 `returning` 已写入的 RETURNING 子句不会被移除，
+    /// 因此应在 `returning` 与 `returning_none` 之间二选一。
+    /// 
+    /// # 返回值
+    /// 原构建器实例
+    #[cfg(any(feature = "sqlite" , feature = "postgres"))]
+    pub fn returning_none(self) -> Self {
+        self
+    }
+
     /// Get the inner QueryBuilder
     /// 
     /// # Returns
@@ -237,8 +649,15 @@ where
     /// 
     /// # 返回值
     /// 内部的 QueryBuilder 实例
-    pub fn finish(self) -> QueryBuilder<'a, DB> {
+    pub fn finish(mut self) -> QueryBuilder<'a, DB> {
+        self.push_pending_from();
         self.query_builder
     }
 
+    /// 写入 `with_join` 尚未输出的 `FROM other WHERE condition`
+    fn push_pending_from(&mut self) {
+        if let Some((other, on_condition)) = self.pending_from.take() {
+            self.query_builder.push(format!(" FROM {} WHERE {}", other, on_condition));
+        }
+    }
 }
\ No newline at end of file
@@ -37,7 +37,7 @@ impl<'a, ET, DB, VAL> Upsert<'a, ET, DB, VAL>
 where
     ET: FieldAccess,
     DB: Database,
-    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + Default + 'a,
 {
 
     /// Create multiple records upsert operation
@@ -4,7 +4,7 @@ use field_access::FieldAccess;
 use sqlx::{Database, Encode, Error, QueryBuilder, Type};
 
 use crate::common::{
-    conversion::{ValueConvert}, error::QueryError, fields::batch_extract, helper::get_table_name, types::PrimaryKey
+    conversion::{ValueConvert}, error::QueryError, fields::batch_extract, filter::push_separated_value_bind, helper::get_table_name, types::PrimaryKey
 };
 
 /// PostgreSQL Upsert query builder
@@ -37,7 +37,7 @@ impl<'a, ET, DB, VAL> Upsert<'a, ET, DB, VAL>
 where
     ET: FieldAccess,
     DB: Database,
-    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + Default + 'a,
 {
 
     /// Create multiple records upsert operation
@@ -82,7 +82,7 @@ where
                     if keys.contains(&names[i]) && VAL::is_default_value(&value) {
                         b.push(" DEFAULT ");
                     } else {
-                        b.push_bind(value);
+                        push_separated_value_bind(&mut b, value);
                     }
                 }
             }
@@ -3,9 +3,14 @@ use std::{iter::once, marker::PhantomData};
 use field_access::FieldAccess;
 use sqlx::{Database, Encode, Error, QueryBuilder, Type};
 
-use crate::common::{
+use crate::{common::{
     conversion::ValueConvert, error::QueryError, fields::batch_extract, helper::get_table_name, types::PrimaryKey
-};
+}, internal::update_builder::Update};
+
+/// (update, insert) statement pairs produced by [`Upsert::many_fallback`]
+/// 
+/// 由 [`Upsert::many_fallback`] 生成的 (更新, 插入) 语句对
+pub type FallbackStatements<'a, DB> = Vec<(QueryBuilder<'a, DB>, QueryBuilder<'a, DB>)>;
 
 /// SQLite Upsert query builder
 /// 
@@ -37,7 +42,7 @@ impl<'a, ET, DB, VAL> Upsert<'a, ET, DB, VAL>
 where
     ET: FieldAccess,
     DB: Database,
-    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + 'a,
+    VAL: Encode<'a, DB> + Type<DB> + ValueConvert + Default + 'a,
 {
     /// 批量执行 UPSERT 操作
     /// 
@@ -131,4 +136,91 @@ where
     {
         Self::many(once(model), primary_key)
     }
-}
\ No newline at end of file
+
+    /// Create the statement pairs for the upsert fallback used when `ON CONFLICT` is unavailable
+    /// 
+    /// Each entity yields an `UPDATE ... WHERE <primary key>` and an `INSERT`. The caller runs
+    /// them inside one transaction, executing the insert only when the update affected no rows
+    /// (see `sqlite::query::upsert_with_fallback`).
+    /// 
+    /// # Arguments
+    /// * `models` - Collection of entity models to upsert
+    /// * `primary_key` - Primary key definition
+    /// 
+    /// # Returns
+    /// A vector of (update, insert) QueryBuilder pairs or an Error
+    /// 
+    /// 创建在不支持 `ON CONFLICT` 时使用的更新插入回退语句对
+    /// 
+    /// 每个实体生成一条 `UPDATE ... WHERE <主键>` 和一条 `INSERT`。调用方在同一事务中执行，
+    /// 仅当更新未影响任何行时才执行插入（参见 `sqlite::query::upsert_with_fallback`）。
+    /// 
+    /// # 参数
+    /// * `models` - 要更新插入的实体模型集合
+    /// * `primary_key` - 主键定义
+    /// 
+    /// # 返回值
+    /// (更新, 插入) QueryBuilder 对的向量或错误
+    pub fn many_fallback(
+        models: impl IntoIterator<Item = &'a ET>,
+        primary_key: &PrimaryKey<'a>,
+    ) -> Result<FallbackStatements<'a, DB>, Error>
+    where
+        VAL: Default,
+    {
+        let models: Vec<_> = models.into_iter().collect();
+        if models.is_empty() {
+            return Err(QueryError::NoEntitiesProvided.into());
+        }
+
+        let keys = primary_key.get_keys();
+        let table_name = get_table_name::<ET>();
+        let mut statements = Vec::with_capacity(models.len());
+
+        for model in models {
+            let update = Update::<ET, DB, VAL>::one(model, primary_key, false)?;
+
+            let (names, mut values) = batch_extract::<ET, VAL>(&[model], &[], false);
+            let mut insert = QueryBuilder::new(
+                format!("INSERT INTO {} ({}) ", table_name, names.join(", "))
+            );
+            insert.push_values(
+                values.pop(),
+                |mut b, row| {
+                    for (i, value) in row.into_iter().enumerate() {
+                        if keys.contains(&names[i]) && VAL::is_default_value(&value) {
+                            b.push(" NULL ");
+                        } else {
+                            b.push_bind(value);
+                        }
+                    }
+                }
+            );
+            statements.push((update, insert));
+        }
+
+        Ok(statements)
+    }
+}
+
+/// Check whether a SQLite version supports `INSERT ... ON CONFLICT DO UPDATE` (3.24.0+)
+/// 
+/// # Arguments
+/// * `version` - Version string as returned by `sqlite_version()`, e.g. `3.45.1`
+/// 
+/// # Returns
+/// True if the native upsert syntax is available
+/// 
+/// 检查 SQLite 版本是否支持 `INSERT ... ON CONFLICT DO UPDATE`（3.24.0 及以上）
+/// 
+/// # 参数
+/// * `version` - `sqlite_version()` 返回的版本字符串，例如 `3.45.1`
+/// 
+/// # 返回值
+/// 支持原生更新插入语法时返回 true
+pub fn supports_on_conflict(version: &str) -> bool {
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) >= (3, 24)
+}
@@ -13,7 +13,7 @@
 
 use sqlx::{QueryBuilder, MySql};
 
-use crate::{internal::{delete_builder, insert_builder, select_builder, subquery, update_builder, upsert_mysql}, mysql::kind::DataKind};
+use crate::{internal::{delete_builder, insert_builder, replace_builder, select_builder, subquery, update_builder, upsert_mysql}, mysql::kind::DataKind};
 
 /// QueryBuilder type alias for MySQL  
 /// MySQL 的 QueryBuilder 类型别名
@@ -95,6 +95,12 @@ pub type SQB<'a> = subquery::SubqueryBuilder<'a, DataKind>;
 /// * `group_by` -  Add GROUP BY clause to the subquery
 /// * `having` - Add HAVING clause to the subquery
 /// * `append_to` - Embed the subquery into a parent query builder
+/// * `append_in_to` - Embed the subquery as `column IN (subquery)`, rejecting projections other than one column
+/// * `projected_columns` - Number of columns the subquery projects
+/// * `check_placeholders` - Check that raw `?` fragments match the bound values
+/// * `try_append_to` - Embed the subquery after checking its placeholders
+/// * `into_count` - Build a `SELECT COUNT(*)` query over the subquery as a derived table
+/// * `into_exists` - Build a `SELECT EXISTS (...)` query over the subquery, run with `fetch_exists`
 /// 
 /// # 公共方法
 /// 
@@ -106,6 +112,12 @@ pub type SQB<'a> = subquery::SubqueryBuilder<'a, DataKind>;
 /// * `group_by` - 向子查询中添加 GROUP BY 子句
 /// * `having` -  向子查询中添加 HAVING 子句
 /// * `append_to` - 将子查询嵌入到父查询构建器中
+/// * `append_in_to` - 以 `column IN (subquery)` 形式嵌入子查询，投影列数不为一时报错
+/// * `projected_columns` - 子查询投影的列数
+/// * `check_placeholders` - 检查原始 `?` 片段与绑定值数量是否一致
+/// * `try_append_to` - 检查占位符后再将子查询嵌入父查询
+/// * `into_count` - 基于子查询（作为派生表）构建 `SELECT COUNT(*)` 查询
+/// * `into_exists` - 基于子查询构建 `SELECT EXISTS (...)` 查询，配合 `fetch_exists` 执行
 /// 
 /// # Examples
 /// 
@@ -139,6 +151,8 @@ pub type Subquery<'a, ET> = subquery::Subquery<'a, ET, DataKind>;
 /// 
 /// * `one` - Create single record insert operation
 /// * `many` - Create multiple records insert operation
+/// * `many_with_casts` - Create multiple records insert operation, casting the placeholders of the given columns
+/// * `one_with_columns` - Create single record insert with only the given columns
 /// * `table` - Create custom table and columns
 /// * `with_table` - Create a insert with a custom table name
 /// * `from_query` - Create an Insert instance from a query
@@ -150,6 +164,8 @@ pub type Subquery<'a, ET> = subquery::Subquery<'a, ET, DataKind>;
 /// 
 /// * `one` - 创建单条记录插入操作
 /// * `many` - 创建多条记录插入操作
+/// * `many_with_casts` - 创建多条记录插入操作，并对指定列的占位符做类型转换
+/// * `one_with_columns` - 创建只写入指定列的单条记录插入操作
 /// * `table` - 创建默认表名的插入操作
 /// * `with_table` - 创建带有自定义表名的插入操作
 /// * `from_query` - 从外部查询中创建 Insert 实例
@@ -183,10 +199,17 @@ pub type Insert<'a, ET> = insert_builder::Insert<'a, ET, MySql, DataKind>;
 /// # Public Methods
 /// 
 /// * `one` - Create a single entity update operation
+/// * `many` - Create a batch update of several entities as one statement with a CASE per column
+/// * `set_where` - Create an update assigning one value to a column on rows matching a condition
 /// * `table` - Create an Update instance with the default table name
 /// * `with_table` - Create an Update instance with a custom table name
 /// * `from_query` - Create an Update instance from a query
 /// * `from_query_with_table` - Create an Update instance from a query with a custom table name
+/// * `with_cte` - Create an UPDATE preceded by a WITH common table expression
+/// * `with_join` - Create an UPDATE reading from a joined table (JOIN on MySQL, FROM on PostgreSQL/SQLite)
+/// * `set_from_entity` - Add `col = ?` assignments using the entity's values for the given columns
+/// * `json_array_append` - Add an assignment appending one element to a JSON array column
+/// * `set_cast` - Add a `col = ?` assignment with the placeholder cast to a SQL type
 /// * `custom` - Custom SET columns or other query statements
 /// * `filter` - Add WHERE condition to the update statement
 /// * `finish` - Finish building, get the internal QueryBuilder
@@ -194,10 +217,17 @@ pub type Insert<'a, ET> = insert_builder::Insert<'a, ET, MySql, DataKind>;
 /// # 公共方法
 /// 
 /// * `one` - 创建单个实体更新操作
+/// * `many` - 以单条语句创建多个实体的批量更新操作，每列一个 CASE
+/// * `set_where` - 创建将同一个值赋给匹配条件记录某一列的更新操作
 /// * `table` - 创建使用默认表名的 Update 实例
 /// * `with_table` - 创建使用自定义表名的 Update 实例
 /// * `from_query` - 从外部查询中创建 Update 实例
 /// * `from_query_with_table` - 从外部查询中创建 Update 实例，可以自定义表名
+/// * `with_cte` - 创建前置 WITH 公用表表达式的 UPDATE
+/// * `with_join` - 创建从关联表读取数据的 UPDATE（MySQL 使用 JOIN，PostgreSQL/SQLite 使用 FROM）
+/// * `set_from_entity` - 添加 `col = ?` 赋值，值取自实体的指定列
+/// * `json_array_append` - 添加向 JSON 数组列追加一个元素的赋值
+/// * `set_cast` - 添加占位符转换为 SQL 类型的 `col = ?` 赋值
 /// * `custom` - 自定义 SET 列或其他查询语句
 /// * `filter` - 向查询中添加过滤查询部分
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
@@ -231,7 +261,14 @@ pub type Update<'a, ET> = update_builder::Update<'a, ET, MySql, DataKind>;
 /// * `with_table` - Create a Delete instance with a custom table name
 /// * `from_query` - Create an Delete instance from a query
 /// * `from_query_with_table` - Create an Delete instance from a query with a custom table name
+/// * `with_cte` - Create a DELETE preceded by a WITH common table expression
+/// * `soft` - Create a soft delete `UPDATE table SET column = ?` from a `SoftDelete` configuration
 /// * `by_primary_key` - Create a DELETE query by primary key
+/// * `by_keys` - Create a DELETE query matching a key column against a list of keys
+/// * `cascade_by_key` - Create cascading DELETE queries for child tables and the parent row
+/// * `archive_by_key` - Create queries copying a row into an archive table and soft-deleting it
+/// * `dedupe_keep_latest` - Create a DELETE removing duplicates, keeping the latest row per group
+/// * `using` - Create a DELETE matching rows of another table (USING / JOIN / EXISTS per database)
 /// * `filter` - Create a DELETE query with custom WHERE conditions
 /// * `finish` - Finish building, get the internal QueryBuilder
 /// 
@@ -241,7 +278,14 @@ pub type Update<'a, ET> = update_builder::Update<'a, ET, MySql, DataKind>;
 /// * `with_table` - 使用自定义表名创建 Delete 实例
 /// * `from_query` - 从外部查询中创建 Delete 实例
 /// * `from_query_with_table` - 从外部查询中创建 Delete 实例，可以自定义表名
+/// * `with_cte` - 创建前置 WITH 公用表表达式的 DELETE
+/// * `soft` - 根据 `SoftDelete` 配置创建软删除 `UPDATE table SET column = ?`
 /// * `by_primary_key` - 通过主键创建 DELETE 查询
+/// * `by_keys` - 创建按键列表匹配键列的 DELETE 查询
+/// * `cascade_by_key` - 创建子表与父记录的级联 DELETE 查询
+/// * `archive_by_key` - 创建将记录复制到归档表并软删除的查询
+/// * `dedupe_keep_latest` - 创建删除重复记录的 DELETE，每组保留最新的一条
+/// * `using` - 创建按另一张表的行匹配删除的 DELETE（按数据库使用 USING / JOIN / EXISTS）
 /// * `filter` - 创建带有自定义 WHERE 条件的 DELETE 查询
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
 /// 
@@ -274,15 +318,37 @@ pub type Delete<'a, ET> = delete_builder::Delete<'a, ET, MySql, DataKind>;
 /// * `with_table` - Create a Select instance with a custom table name
 /// * `from_query` - Create an Select instance from a query
 /// * `from_query_with_table` - Create an Select instance from a query with a custom table name
+/// * `from_subquery` - Create a Select instance reading from a subquery (derived table)
 /// * `columns` - Create a custom column query statement
+/// * `star` - Select every table column with `*` instead of the entity field list
+/// * `columns_coalesce` - Select the entity fields with COALESCE defaults for nullable columns
+/// * `window` - Select all entity fields plus a window function column
+/// * `count_distinct` - Count distinct combinations of one or more columns
+/// * `distinct` - Select the distinct values of one column
+/// * `column_exists_subquery` - Select all entity columns plus an `EXISTS (subquery) AS alias` flag
 /// * `filter` - Create a SELECT query with custom WHERE conditions
 /// * `join` - Create a JOIN query statement
+/// * `index_hint` - Add an index hint after the table name (MySQL only, no-op elsewhere)
+/// * `tablesample` - Add a TABLESAMPLE clause after the table name (PostgreSQL only, no-op elsewhere)
 /// * `group_by` - Create a GROUP BY query statement
 /// * `having` - Create a HAVING clause
+/// * `having_bind` - Add a HAVING condition with one bound value
 /// * `by_primary_key` - Create a SELECT query by primary key
+/// * `by_keys` - Create a SELECT query matching a key column against a list of keys
 /// * `order_by` - Create an ORDER BY clause
+/// * `order_by_many` - Create several ORDER BY terms in the given order
+/// * `order_by_field` - Order by an explicit value list (FIELD on MySQL, CASE elsewhere)
+/// * `strict` - Validate table and column names as plain identifiers
+/// * `try_finish` - Build the final query, reporting identifiers rejected in strict mode
 /// * `paginate` - Create a pagination query statement
+/// * `paginate_plus_one` - Create a pagination query fetching one extra row to detect the next page
+/// * `limit_with_ties` - Add `FETCH FIRST n ROWS WITH TIES` (PostgreSQL only, requires ORDER BY)
+/// * `for_update_nowait` - Add `FOR UPDATE NOWAIT` (MySQL 8.0+ / PostgreSQL, no-op on SQLite)
+/// * `lock` - Set the row lock mode (`LockMode`), appended after ORDER BY / LIMIT (no-op on SQLite)
+/// * `random_one` - Fetch one random matching row (ORDER BY RANDOM()/RAND() LIMIT 1)
 /// * `cursor` - Create a cursor pagination query statement
+/// * `cursor_nullable` - Create a cursor pagination query over a nullable sort column
+/// * `cursor_multi` - Create a keyset cursor pagination query over several sort columns
 /// * `finish` - Finish building, get the internal QueryBuilder
 /// 
 /// # 公共方法
@@ -291,15 +357,37 @@ pub type Delete<'a, ET> = delete_builder::Delete<'a, ET, MySql, DataKind>;
 /// * `with_table` - 创建使用自定义表名的 Select 实例
 /// * `from_query` - 从外部查询中创建 Select 实例
 /// * `from_query_with_table` - 从外部查询中创建 Select 实例，可以自定义表名
+/// * `from_subquery` - 创建以子查询（派生表）为数据源的 Select 实例
 /// * `columns` - 创建自定义列的查询语句
+/// * `star` - 使用 `*` 代替实体字段列表选择表的所有列
+/// * `columns_coalesce` - 以实体字段为查询列，并为可空列提供 COALESCE 默认值
+/// * `window` - 查询实体全部字段及一个窗口函数列
+/// * `count_distinct` - 统计一列或多列组合的去重数量
+/// * `distinct` - 选择某一列的不同取值
+/// * `column_exists_subquery` - 查询实体所有列并附加 `EXISTS (subquery) AS alias` 标志列
 /// * `filter` - 创建带有自定义 WHERE 条件的查询语句
 /// * `join` - 创建 JOIN 查询语句
+/// * `index_hint` - 在表名后添加索引提示（仅 MySQL，其它数据库忽略）
+/// * `tablesample` - 在表名后添加 TABLESAMPLE 采样子句（仅 PostgreSQL，其它数据库忽略）
 /// * `group_by` - 创建 GROUP BY 查询语句 
 /// * `having` - 创建 HAVING 子句
+/// * `having_bind` - 添加带一个绑定值的 HAVING 条件
 /// * `by_primary_key` - 创建按主键条件查询语句
+/// * `by_keys` - 创建按键列表匹配键列的查询语句
 /// * `order_by` - 创建排序子句
+/// * `order_by_many` - 按给定顺序创建多个排序项
+/// * `order_by_field` - 按给定值列表的顺序排序（MySQL 使用 FIELD，其他使用 CASE）
+/// * `strict` - 校验表名和列名为普通标识符
+/// * `try_finish` - 构建最终查询，并报告严格模式下被拒绝的标识符
 /// * `paginate` - 创建分页查询语句
+/// * `paginate_plus_one` - 创建多取一行以判断是否存在下一页的分页查询语句
+/// * `limit_with_ties` - 添加 `FETCH FIRST n ROWS WITH TIES`（仅 PostgreSQL，需要 ORDER BY）
+/// * `for_update_nowait` - 添加 `FOR UPDATE NOWAIT`（MySQL 8.0+ / PostgreSQL，SQLite 上无效果）
+/// * `lock` - 设置行锁模式（`LockMode`），追加在 ORDER BY / LIMIT 之后（SQLite 上无效果）
+/// * `random_one` - 随机取一条匹配的行（ORDER BY RANDOM()/RAND() LIMIT 1）
 /// * `cursor` - 创建游标分页查询语句
+/// * `cursor_nullable` - 创建基于可空排序列的游标分页查询语句
+/// * `cursor_multi` - 创建基于多列排序键的键集游标分页查询语句
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
 /// 
 /// # Examples
@@ -345,14 +433,48 @@ pub type Select<'a, ET> = select_builder::Select<'a, ET, MySql, DataKind>;
 /// ```
 pub type Upsert<'a, ET> = upsert_mysql::Upsert<'a, ET, MySql, DataKind>;
 
+/// Replace builder type alias for MySQL  
+/// MySQL 的 Replace 构建器类型别名
+/// 
+/// Used to build `REPLACE INTO` statements. On a key conflict the existing row is deleted
+/// and the new row inserted, so delete triggers fire and omitted columns fall back to defaults.
+/// 
+/// 用于构建 `REPLACE INTO` 语句。发生键冲突时会删除已有记录并插入新记录，
+/// 因此会触发删除触发器，未提供的列会回退为默认值。
+/// 
+/// # Type Parameters
+/// 
+/// * `ET` - The entity type that this replace builder operates on
+/// * `ET` - 此替换构建器操作的实体类型
+/// 
+/// # Public Methods
+/// 
+/// * `one` - Create single record replace operation
+/// * `many` - Create multiple records replace operation
+/// 
+/// # 公共方法
+/// 
+/// * `one` - 创建单条记录替换操作
+/// * `many` - 创建多条记录替换操作
+/// 
+/// # Examples
+/// 
+/// ```
+/// use kitx::mysql::builder::Replace;
+/// 
+/// let entity = User { id: 1, name: "Charlie".to_string() };
+/// let replace_query = Replace::one(&entity).unwrap();
+/// ```
+pub type Replace<'a, ET> = replace_builder::Replace<'a, ET, MySql, DataKind>;
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        common::types::{CursorPaginatedResult, PaginatedResult, PrimaryKey, Order}, 
-        mysql::{builder::{Delete, Insert, Select, Subquery, Update, Upsert, QB}, 
+        common::{filter::{push_group_concat, push_ilike, push_not_ilike, push_not_like}, types::{CursorPaginatedResult, IndexHint, LockMode, PaginatedResult, PrimaryKey, Order}}, 
+        mysql::{builder::{Delete, Insert, Replace, Select, Subquery, Update, Upsert, QB}, 
         connection, kind::DataKind, 
-        query::{execute, fetch_all, fetch_one, fetch_scalar}}, 
-        test_utils::{article::Article, init::get_database_url}
+        query::{execute, fetch_all, fetch_one, fetch_scalar, fetch_scalar_opt}}, 
+        test_utils::{article::{Article, ArticleTag}, init::get_database_url}
     };
     //use super::*;
     async fn init_pool() {
@@ -599,4 +721,299 @@ mod tests {
         dbg!(&result);
     }
 
+    #[tokio::test]
+    async fn test_replace_many() {
+        let mut entity1 = Article::new(100, "replace-1", None);
+        entity1.id = 9001;
+        let mut entity2 = Article::new(100, "replace-2", None);
+        entity2.id = 9002;
+
+        let binding = [entity1, entity2];
+        let qb = Replace::many(&binding).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "REPLACE INTO article (id, tenant_id, title, content, views, deleted, created_at) VALUES (?, ?, ?, ?, ?, ?, ?), (?, ?, ?, ?, ?, ?, ?)"
+        );
+
+        init_pool().await;
+        let result = execute(qb).await.unwrap();
+        println!("Replaced {} rows.", result.rows_affected());
+    }
+
+    #[tokio::test]
+    async fn test_fixed_length_binary() {
+        let uuid = sqlx::types::Uuid::from_u128(0x6f1c_2a3b_4c5d_4e6f_8a9b_0c1d_2e3f_4a5b);
+        assert!(DataKind::binary(&uuid.as_bytes()[..8], 16).is_err());
+        let id = DataKind::binary(uuid.as_bytes(), 16).unwrap();
+        assert_eq!(id, DataKind::from(*uuid.as_bytes()));
+
+        init_pool().await;
+        execute(QB::new("DROP TABLE IF EXISTS binary_sample")).await.unwrap();
+        execute(QB::new("CREATE TABLE binary_sample (id BINARY(16) PRIMARY KEY)")).await.unwrap();
+
+        let mut qb = QB::new("INSERT INTO binary_sample (id) VALUES (");
+        qb.push_bind(id.clone()).push(")");
+        execute(qb).await.unwrap();
+
+        let mut qb = QB::new("SELECT COUNT(*) FROM binary_sample WHERE id = ");
+        qb.push_bind(id);
+        assert_eq!(fetch_scalar(qb).await.unwrap(), 1);
+
+        let stored: Option<Vec<u8>> = fetch_scalar_opt(QB::new("SELECT id FROM binary_sample")).await.unwrap();
+        assert_eq!(stored.as_deref(), Some(uuid.as_bytes().as_slice()));
+    }
+
+    #[test]
+    fn test_index_hint() {
+        let qb = Select::<Article>::table()
+            .index_hint(IndexHint::Force(vec!["idx_tenant".to_string()]))
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1);
+            })
+            .finish();
+
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article FORCE INDEX (idx_tenant) WHERE tenant_id = ?"
+        );
+    }
+
+    #[test]
+    fn test_count_distinct() {
+        let qb = Select::<Article>::table()
+            .count_distinct(&["tenant_id", "title"]).unwrap()
+            .finish();
+        assert_eq!(qb.sql(), "SELECT COUNT(DISTINCT tenant_id, title) FROM article");
+
+        let qb = Select::<Article>::table()
+            .count_distinct(&["tenant_id"]).unwrap()
+            .finish();
+        assert_eq!(qb.sql(), "SELECT COUNT(DISTINCT tenant_id) FROM article");
+    }
+
+    #[test]
+    fn test_in_large() {
+        use crate::common::filter::push_in_large;
+
+        let ids: Vec<i64> = (1..=2000).collect();
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                push_in_large::<_, _, DataKind>(b, "id", ids);
+            })
+            .finish();
+        let sql = qb.sql();
+        assert!(sql.contains("WHERE (id IN (?, ?"));
+        assert_eq!(sql.matches("id IN (").count(), 4);
+        assert_eq!(sql.matches('?').count(), 2000);
+    }
+
+    #[test]
+    fn test_random_one() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1);
+            })
+            .random_one();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? ORDER BY RAND() LIMIT 1"
+        );
+    }
+
+    #[test]
+    fn test_json_array_append() {
+        let qb = Update::<Article>::table()
+            .json_array_append("tags", DataKind::from("b"))
+            .json_array_append("history", DataKind::from(1488))
+            .filter(|b| {
+                b.push("id = ").push_bind(DataKind::from(1));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "UPDATE article SET tags = JSON_ARRAY_APPEND(tags, '$', ?), history = JSON_ARRAY_APPEND(history, '$', ?) WHERE id = ?"
+        );
+    }
+
+    #[test]
+    fn test_order_by_field() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1492));
+            })
+            .order_by_field("title", vec!["urgent", "high", "low"])
+            .order_by("id", Order::Asc)
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? ORDER BY FIELD(title, ?, ?, ?), id ASC"
+        );
+    }
+
+    #[test]
+    fn test_ilike() {
+        let qb = Select::<Article>::table()
+            .filter(|b| push_ilike(b, "title", DataKind::from("%rust%")))
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE LOWER(title) LIKE LOWER(?)"
+        );
+    }
+
+    #[test]
+    fn test_delete_join() {
+        let qb = Delete::<Article>::using("article_tag", |b| {
+                b.push("article.id = article_tag.article_id AND article_tag.tag = ").push_bind(DataKind::from("spam"));
+            })
+            .filter(|b| {
+                b.push("article.tenant_id = ").push_bind(DataKind::from(1));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "DELETE article FROM article JOIN article_tag ON article.id = article_tag.article_id AND article_tag.tag = ? WHERE article.tenant_id = ?"
+        );
+    }
+
+    #[test]
+    fn test_not_like() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                push_not_like(b, "title", DataKind::from("%draft%"));
+                b.push(" AND ");
+                push_not_ilike(b, "content", DataKind::from("%spam%"));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE title NOT LIKE ? AND LOWER(content) NOT LIKE LOWER(?)"
+        );
+    }
+
+    #[test]
+    fn test_lock_mode() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1));
+            })
+            .lock(LockMode::UpdateNoWait)
+            .order_by("id", Order::Asc)
+            .paginate(2, 10).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? ORDER BY id ASC LIMIT ? OFFSET ? FOR UPDATE NOWAIT"
+        );
+
+        let qb = Select::<Article>::table()
+            .lock(LockMode::Update)
+            .cursor("id", Order::Asc, None, 5).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article ORDER BY id ASC LIMIT ? FOR UPDATE"
+        );
+    }
+
+    #[test]
+    fn test_update_many_case() {
+        let mut entities = [Article::new(1506, "one", None), Article::new(1506, "two", None)];
+        entities[0].id = 1;
+        entities[1].id = 2;
+        let qb = Update::many(&entities, &ARTICLE_KEY).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "UPDATE article SET tenant_id = CASE id WHEN ? THEN ? WHEN ? THEN ? ELSE tenant_id END, \
+            title = CASE id WHEN ? THEN ? WHEN ? THEN ? ELSE title END, \
+            content = CASE id WHEN ? THEN ? WHEN ? THEN ? ELSE content END, \
+            views = CASE id WHEN ? THEN ? WHEN ? THEN ? ELSE views END, \
+            deleted = CASE id WHEN ? THEN ? WHEN ? THEN ? ELSE deleted END, \
+            created_at = CASE id WHEN ? THEN ? WHEN ? THEN ? ELSE created_at END \
+            WHERE id IN (?, ?)"
+        );
+
+        let tags = [ArticleTag::new("a"), ArticleTag::new("b")];
+        let qb = Update::many(&tags, &PrimaryKey::Composite(&["article_id", "share_seq"])).unwrap();
+        assert!(qb.sql().starts_with("UPDATE article_tag SET tag = CASE WHEN article_id = ? AND share_seq = ? THEN ? WHEN article_id = ? AND share_seq = ? THEN ? ELSE tag END"));
+        assert!(qb.sql().ends_with(" WHERE (article_id = ? AND share_seq = ?) OR (article_id = ? AND share_seq = ?)"));
+    }
+
+    #[test]
+    fn test_data_kind_partial_ord() {
+        use chrono::{Duration, Utc};
+
+        assert!(DataKind::BigInt(1) < DataKind::BigInt(2));
+        assert!(DataKind::BigInt(5) >= DataKind::BigInt(5));
+
+        let now = Utc::now();
+        let earlier = DataKind::Timestamp(now - Duration::seconds(1));
+        let later = DataKind::Timestamp(now);
+        assert!(earlier < later);
+        assert_eq!(later.partial_cmp(&later.clone()), Some(std::cmp::Ordering::Equal));
+
+        assert_eq!(DataKind::BigInt(1).partial_cmp(&DataKind::Text("1".to_string())), None);
+        assert_eq!(DataKind::Null.partial_cmp(&DataKind::Null), Some(std::cmp::Ordering::Equal));
+    }
+
+    #[test]
+    fn test_group_concat() {
+        let qb = Select::<ArticleTag>::table()
+            .columns(|qb| {
+                qb.push("article_id, ");
+                push_group_concat::<_, DataKind>(qb, "tag", "', '", "tags");
+            })
+            .group_by("article_id").unwrap()
+            .finish();
+        assert_eq!(qb.sql(), "SELECT article_id, GROUP_CONCAT(tag SEPARATOR ''', ''') AS tags FROM article_tag GROUP BY article_id");
+    }
+
+    #[test]
+    fn test_window() {
+        use crate::common::types::WindowFunc;
+
+        let qb = Select::<Article>::table()
+            .columns(|b| {
+                b.push("tenant_id, COUNT(*) AS total, ")
+                    .push(WindowFunc::new("SUM(COUNT(*))", "running").order_by("tenant_id", Order::Asc).to_sql());
+            })
+            .group_by("tenant_id").unwrap()
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT tenant_id, COUNT(*) AS total, SUM(COUNT(*)) OVER (ORDER BY tenant_id ASC) AS running FROM article GROUP BY tenant_id"
+        );
+
+        let qb = Select::<Article>::table()
+            .strict()
+            .window(WindowFunc::dense_rank("dr").partition_by("tenant_id; DROP TABLE article")).unwrap()
+            .try_finish();
+        assert!(qb.is_err());
+    }
+
+    #[test]
+    fn test_update_with_join() {
+        let qb = Update::<ArticleTag>::with_join("article", "article.id = article_tag.article_id")
+            .custom(|b| {
+                b.push("article_tag.tenant_id = article.tenant_id");
+            })
+            .filter(|b| {
+                b.push("article.views > ").push_bind(DataKind::from(10));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "UPDATE article_tag JOIN article ON article.id = article_tag.article_id SET article_tag.tenant_id = article.tenant_id WHERE article.views > ?"
+        );
+    }
+
+    #[test]
+    fn test_strict_with_index_hint() {
+        let qb = Select::<Article>::table()
+            .index_hint(IndexHint::Use(vec!["idx_tenant".to_string()]))
+            .strict()
+            .order_by("id", Order::Asc)
+            .try_finish()
+            .unwrap();
+        assert!(qb.sql().contains("FROM article USE INDEX (idx_tenant) ORDER BY id ASC"));
+    }
+
 }
\ No newline at end of file
@@ -12,6 +12,7 @@
 //! 包括数值、字符串、二进制、时间、JSON、UUID 和 IP 地址类型。
 
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::error::Error;
 use std::sync::Arc;
 use std::any::Any;
@@ -23,7 +24,10 @@ use sqlx::{Encode, Type, TypeInfo};
 use sqlx::types::{Decimal, Uuid};
 use serde_json::Value;
 
-use crate::common::conversion::{unwrap_option, ValueConvert};
+use crate::common::conversion::{unwrap_option, DbEnumInt, ValueConvert};
+#[cfg(feature = "json-fallback")]
+use crate::common::conversion::json_fallback;
+use crate::common::error::QueryError;
 
 /// Enum representing PostgreSQL data types, supporting the main PostgreSQL type system
 #[derive(Default, Debug, Clone, PartialEq)]
@@ -52,7 +56,8 @@ pub enum DataKind {
     Text(String),   // VARCHAR, CHAR, TEXT
 
     // Binary types
-    Blob(Arc<[u8]>),  // VARBINARY, BINARY, BLOB
+    Blob(Arc<[u8]>),  // VARBINARY, BLOB
+    Binary(Arc<[u8]>), // BINARY(n), fixed length, e.g. UUID-as-bytes in BINARY(16)
 
     // Time types
     Date(NaiveDate),      // DATE
@@ -101,6 +106,7 @@ impl Encode<'_, MySql> for DataKind {
 
             // Binary types
             DataKind::Blob(blob) => <Vec<u8> as Encode<'_, MySql>>::encode(blob.to_vec(), buf),
+            DataKind::Binary(bytes) => <&[u8] as Encode<'_, MySql>>::encode(bytes, buf),
 
             // Time types
             DataKind::Date(d) => <NaiveDate as Encode<'_, MySql>>::encode(*d, buf),
@@ -178,6 +184,9 @@ impl DataKind {
 
             // Binary types
             DataKind::Blob(_) => <Vec<u8> as Type<MySql>>::type_info(),
+            // MySQL parameter types carry no length, so this is the same binary byte type as
+            // Blob; the fixed length is checked by `DataKind::binary` instead
+            DataKind::Binary(_) => <[u8] as Type<MySql>>::type_info(),
 
             // Time types
             DataKind::Date(_) => <NaiveDate as Type<MySql>>::type_info(),
@@ -197,6 +206,39 @@ impl DataKind {
     }
 }
 
+impl DataKind {
+    /// Create a fixed-length binary value for a `BINARY(n)` column
+    /// 
+    /// MySQL silently right-pads shorter values with `0x00` and rejects or truncates longer
+    /// ones, so the length is checked up front instead of leaving it to the server.
+    /// 
+    /// # Arguments
+    /// * `bytes` - The raw bytes to bind
+    /// * `len` - The declared column length `n`
+    /// 
+    /// # Returns
+    /// A `DataKind::Binary` value, or `QueryError::ValueInvalid` if the length does not match
+    /// 
+    /// 为 `BINARY(n)` 列创建定长二进制值
+    /// 
+    /// MySQL 会用 `0x00` 静默填充较短的值，并拒绝或截断较长的值，
+    /// 因此在绑定前先校验长度，而不是交给服务器处理。
+    /// 
+    /// # 参数
+    /// * `bytes` - 要绑定的原始字节
+    /// * `len` - 列声明的长度 `n`
+    /// 
+    /// # 返回值
+    /// `DataKind::Binary` 值；长度不匹配时返回 `QueryError::ValueInvalid`
+    pub fn binary(bytes: impl AsRef<[u8]>, len: usize) -> Result<Self, QueryError> {
+        let bytes = bytes.as_ref();
+        if bytes.len() != len {
+            return Err(QueryError::ValueInvalid(format!("BINARY({}) (got {} bytes)", len, bytes.len())));
+        }
+        Ok(DataKind::Binary(Arc::from(bytes)))
+    }
+}
+
 impl ValueConvert for DataKind {
     fn convert(value: &dyn Any) -> Self {
         macro_rules! try_convert {
@@ -204,6 +246,10 @@ impl ValueConvert for DataKind {
                 $(if let Some(v) = unwrap_option::<$type>(value) {
                     return $variant(v);
                 })*
+                #[cfg(feature = "json-fallback")]
+                if let Some(json) = json_fallback(value) {
+                    return DataKind::Json(Arc::new(json));
+                }
                 return DataKind::Null;
             };
         }
@@ -227,6 +273,7 @@ impl ValueConvert for DataKind {
             DateTime<Utc> => |v: &DateTime<Utc>| DataKind::Timestamp(*v),
             Vec<u8> => |v: &Vec<u8>| DataKind::Blob(Arc::from(v.as_slice())),
             &[u8] => |v: &&[u8]| DataKind::Blob(Arc::from(*v)),
+            [u8; 16] => |v: &[u8; 16]| DataKind::Binary(Arc::from(v.as_slice())),
             bool => |v: &bool| DataKind::Bool(*v),
             Value => |v: &Value| DataKind::Json(Arc::new(v.clone())),
             Uuid => |v: &Uuid| DataKind::Uuid(*v),
@@ -250,6 +297,42 @@ impl ValueConvert for DataKind {
     }
 }
 
+/// Values are only comparable within the same variant, e.g. two cursor values of the same column;
+/// different variants (and unordered types such as JSON) yield `None`.
+///
+/// 仅同一变体内的值可比较，例如同一列的两个游标值；不同变体（以及 JSON 等无序类型）返回 `None`。
+impl PartialOrd for DataKind {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (DataKind::Bool(a), DataKind::Bool(b)) => a.partial_cmp(b),
+            (DataKind::TinyInt(a), DataKind::TinyInt(b)) => a.partial_cmp(b),
+            (DataKind::SmallInt(a), DataKind::SmallInt(b)) => a.partial_cmp(b),
+            (DataKind::Int(a), DataKind::Int(b)) => a.partial_cmp(b),
+            (DataKind::BigInt(a), DataKind::BigInt(b)) => a.partial_cmp(b),
+            (DataKind::UnsignedTinyInt(a), DataKind::UnsignedTinyInt(b)) => a.partial_cmp(b),
+            (DataKind::UnsignedSmallInt(a), DataKind::UnsignedSmallInt(b)) => a.partial_cmp(b),
+            (DataKind::UnsignedInt(a), DataKind::UnsignedInt(b)) => a.partial_cmp(b),
+            (DataKind::UnsignedBigInt(a), DataKind::UnsignedBigInt(b)) => a.partial_cmp(b),
+            (DataKind::Float(a), DataKind::Float(b)) => a.partial_cmp(b),
+            (DataKind::Double(a), DataKind::Double(b)) => a.partial_cmp(b),
+            (DataKind::Decimal(a), DataKind::Decimal(b)) => a.partial_cmp(b),
+            (DataKind::Text(a), DataKind::Text(b)) => a.partial_cmp(b),
+            (DataKind::Blob(a), DataKind::Blob(b)) => a.partial_cmp(b),
+            (DataKind::Binary(a), DataKind::Binary(b)) => a.partial_cmp(b),
+            (DataKind::Date(a), DataKind::Date(b)) => a.partial_cmp(b),
+            (DataKind::Time(a), DataKind::Time(b)) => a.partial_cmp(b),
+            (DataKind::DateTime(a), DataKind::DateTime(b)) => a.partial_cmp(b),
+            (DataKind::Timestamp(a), DataKind::Timestamp(b)) => a.partial_cmp(b),
+            (DataKind::Uuid(a), DataKind::Uuid(b)) => a.partial_cmp(b),
+            (DataKind::IpAddr(a), DataKind::IpAddr(b)) => a.partial_cmp(b),
+            (DataKind::Ipv4Addr(a), DataKind::Ipv4Addr(b)) => a.partial_cmp(b),
+            (DataKind::Ipv6Addr(a), DataKind::Ipv6Addr(b)) => a.partial_cmp(b),
+            _ if self == other => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
 // Implement From trait for common types
 macro_rules! impl_from {
     ($type:ty, $variant:expr) => {
@@ -266,6 +349,7 @@ impl_from!(String, |value: String| DataKind::Text(value));
 impl_from!(&str, |value: &str| DataKind::Text(value.to_string()));
 impl_from!(Vec<u8>, |value: Vec<u8>| DataKind::Blob(Arc::from(value)));
 impl_from!(&[u8], |value: &[u8]| DataKind::Blob(Arc::from(value)));
+impl_from!([u8; 16], |value: [u8; 16]| DataKind::Binary(Arc::from(value.as_slice())));
 impl_from!(i8, DataKind::TinyInt);
 impl_from!(i16, DataKind::SmallInt);
 impl_from!(i32, DataKind::Int);
@@ -292,6 +376,13 @@ impl_from!(Ipv4Addr, DataKind::Ipv4Addr);
 impl_from!(Ipv6Addr, DataKind::Ipv6Addr);
 
 
+// Integer-backed enums are bound as their discriminant
+impl<E: DbEnumInt> From<E> for DataKind {
+    fn from(value: E) -> Self {
+        DataKind::Int(value.to_i32())
+    }
+}
+
 impl<'a> From<DataKind> for Cow<'a, DataKind> {
     fn from(value: DataKind) -> Self {
         Cow::Owned(value)
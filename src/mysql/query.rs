@@ -11,9 +11,18 @@
 //! 它包括执行查询、获取单行或多行数据以及处理事务的函数。
 //! 所有函数都设计为与 MySQL 特定的 sqlx 类型配合使用。
 
-use sqlx::{mysql::{MySqlQueryResult, MySqlRow}, Acquire, Error, FromRow, QueryBuilder, MySql};
+use std::{io::{self, Write}, panic::AssertUnwindSafe};
 
-use crate::mysql::connection;
+use field_access::FieldAccess;
+use futures_util::{future::try_join_all, FutureExt, stream, Stream, TryStreamExt};
+use serde::Serialize;
+use sqlx::{mysql::{MySqlConnection, MySqlDatabaseError, MySqlQueryResult, MySqlRow}, Acquire, Database, Decode, Error, Execute, FromRow, QueryBuilder, MySql, Type};
+use tokio::sync::{mpsc, Semaphore};
+
+use crate::{common::{error::QueryError, helper::apply_interceptors, fields::{get_value, get_values, is_default_pk}, types::{BatchMode, ChildSpec, CursorPaginatedResult, EncodedCursorPage, Order, PaginatedResult, PrimaryKey, TreeNode, UpdateOutcome}}, mysql::{builder::{Delete, Insert, Select, Update}, connection, kind::DataKind}};
+
+/// `fetch_stream` 中等待消费的行数上限
+const STREAM_BUFFER_SIZE: usize = 64;
 
 /// Execute a query and return the result
 /// 
@@ -31,9 +40,10 @@ use crate::mysql::connection;
 /// # 返回值
 /// 成功时返回 MySqlQueryResult，失败时返回 Error
 pub async fn execute<'a>(
-    mut builder: QueryBuilder<'a, MySql>,
+    builder: QueryBuilder<'a, MySql>,
 ) -> Result<MySqlQueryResult, Error>
 {
+    let mut builder = intercept(builder)?;
     #[cfg(debug_assertions)]
     {
         let sql = builder.sql();
@@ -74,13 +84,15 @@ pub async fn execute_with_trans<'a>(
     let mut tx = conn.begin().await?;
     let mut results = Vec::new();
 
-    for mut builder in builders {
+    for builder in builders {
+        let mut builder = intercept(builder)?;
         match builder.build().execute(&mut *tx).await {
             Ok(result) => {
                 results.push(result);
             }
             Err(e) => {
-                tx.rollback().await?;
+                // 回滚失败时仍返回执行错误，未完成的事务会在连接释放时回滚
+                let _ = tx.rollback().await;
                 return Err(e);
             }
         }
@@ -90,6 +102,117 @@ pub async fn execute_with_trans<'a>(
     Ok(results)
 }
 
+/// Execute several independent queries and collect their results
+/// 
+/// With `BatchMode::Transaction` the queries run in order inside one transaction, which is
+/// rolled back if any of them fails. With `BatchMode::Concurrent(n)` they run concurrently
+/// on separate pool connections in autocommit mode, at most `n` at a time, so queries that
+/// succeeded stay committed even if another one fails. Results keep the order of `builders`.
+/// 
+/// # Arguments
+/// * `builders` - Independent queries to execute
+/// * `mode` - Transactional or concurrent execution
+/// 
+/// # Returns
+/// The result of every query on success, or the first Error
+/// 
+/// 执行多条独立查询并收集结果
+/// 
+/// 使用 `BatchMode::Transaction` 时，查询在同一事务中依次执行，任一失败则整体回滚。
+/// 使用 `BatchMode::Concurrent(n)` 时，查询在不同的连接池连接上以自动提交模式并发执行，
+/// 同时最多执行 `n` 条，因此即使某条失败，已成功的查询仍会保留。结果顺序与 `builders` 一致。
+/// 
+/// # 参数
+/// * `builders` - 要执行的独立查询
+/// * `mode` - 事务执行或并发执行
+/// 
+/// # 返回值
+/// 成功时返回每条查询的结果，否则返回第一个 Error
+pub async fn execute_all<'a>(
+    builders: Vec<QueryBuilder<'a, MySql>>,
+    mode: BatchMode,
+) -> Result<Vec<MySqlQueryResult>, Error>
+{
+    #[cfg(debug_assertions)]
+    {
+        for builder in builders.iter() {
+            dbg!(builder.sql());
+        }
+    }
+    let pool = connection::get_db_pool()?;
+
+    match mode {
+        BatchMode::Transaction => {
+            let mut tx = pool.begin().await?;
+            let mut results = Vec::with_capacity(builders.len());
+            for builder in builders {
+                let mut builder = intercept(builder)?;
+                results.push(builder.build().execute(&mut *tx).await?);
+            }
+            tx.commit().await?;
+            Ok(results)
+        }
+        BatchMode::Concurrent(max_concurrency) => {
+            let semaphore = Semaphore::new(max_concurrency.max(1));
+            let tasks = builders.into_iter().map(|builder| {
+                let pool = &pool;
+                let semaphore = &semaphore;
+                async move {
+                    let _permit = semaphore.acquire().await.map_err(|_| Error::PoolClosed)?;
+                    let mut builder = intercept(builder)?;
+                    builder.build().execute(&**pool).await
+                }
+            });
+            try_join_all(tasks).await
+        }
+    }
+}
+
+/// Run a closure inside a transaction
+/// 
+/// The closure receives the transaction's connection; execute builders on it with
+/// `builder.build().execute(&mut *conn)`. The transaction commits when the closure returns
+/// `Ok` and rolls back when it returns `Err`, so all writes made through the connection
+/// succeed or fail as one unit of work. If the rollback itself fails, the closure's error is
+/// still the one returned.
+/// 
+/// # Arguments
+/// * `f` - Async closure performing the unit of work
+/// 
+/// # Returns
+/// The closure's result on commit, or the closure's / transaction's Error
+/// 
+/// 在事务中执行闭包
+/// 
+/// 闭包接收事务连接，可通过 `builder.build().execute(&mut *conn)` 在其上执行构建器。
+/// 闭包返回 `Ok` 时提交事务，返回 `Err` 时回滚，因此通过该连接进行的所有写入
+/// 作为一个工作单元同时成功或失败。回滚本身失败时，返回的仍是闭包的错误。
+/// 
+/// # 参数
+/// * `f` - 执行工作单元的异步闭包
+/// 
+/// # 返回值
+/// 提交后返回闭包的结果，否则返回闭包或事务的 Error
+pub async fn transaction<R, F>(f: F) -> Result<R, Error>
+where
+    F: AsyncFnOnce(&mut MySqlConnection) -> Result<R, Error>,
+{
+    let pool = connection::get_db_pool()?;
+    let mut tx = pool.begin().await?;
+
+    match f(&mut tx).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(result)
+        }
+        Err(e) => {
+            // 回滚失败时仍返回闭包的错误，未完成的事务会在连接释放时回滚
+            let _ = tx.rollback().await;
+            Err(e)
+        }
+    }
+}
+
 /// Fetch an optional single row and map it to a type
 /// 
 /// # Type Parameters
@@ -112,11 +235,12 @@ pub async fn execute_with_trans<'a>(
 /// # 返回值
 /// 成功时返回可选的映射类型，失败时返回 Error
 pub async fn fetch_optional<'a, T>(
-    mut builder: QueryBuilder<'a, MySql>,
+    builder: QueryBuilder<'a, MySql>,
 ) -> Result<Option<T>, Error>
 where
     T: for<'r> FromRow<'r, MySqlRow> + Unpin + Send + 'a,
 {
+    let mut builder = intercept(builder)?;
     #[cfg(debug_assertions)]
     {
         let sql = builder.sql();
@@ -148,11 +272,12 @@ where
 /// # 返回值
 /// 成功时返回映射类型，失败时返回 Error
 pub async fn fetch_one<'a, T>(
-    mut builder: QueryBuilder<'a, MySql>,
+    builder: QueryBuilder<'a, MySql>,
 ) -> Result<T, Error>
 where
     T: for<'r> FromRow<'r, MySqlRow> + Unpin + Send + 'a,
 {
+    let mut builder = intercept(builder)?;
     #[cfg(debug_assertions)]
     {
         let sql = builder.sql();
@@ -162,6 +287,64 @@ where
     builder.build_query_as::<T>().fetch_one(&*pool).await
 }
 
+/// Fetch an optional row locked with `FOR UPDATE NOWAIT` inside a transaction
+/// 
+/// Use with a builder from `Select::for_update_nowait` on a transaction connection. When the
+/// row is already locked by another transaction, MySQL error 3572 (ER_LOCK_NOWAIT) is returned as
+/// `QueryError::LockNotAvailable`, which can be checked with `QueryError::matches`.
+/// 
+/// # Type Parameters
+/// * `T` - Type to map the row to, must implement FromRow trait
+/// 
+/// # Arguments
+/// * `conn` - Connection of the transaction holding the lock
+/// * `builder` - QueryBuilder containing the locking query
+/// 
+/// # Returns
+/// Optional mapped row on success or an Error
+/// 
+/// 在事务中获取以 `FOR UPDATE NOWAIT` 锁定的可选单行
+/// 
+/// 在事务连接上配合 `Select::for_update_nowait` 生成的构建器使用。当目标行已被其他事务锁定时，
+/// MySQL 错误 3572（ER_LOCK_NOWAIT）会以 `QueryError::LockNotAvailable` 返回，可通过 `QueryError::matches` 判断。
+/// 
+/// # 类型参数
+/// * `T` - 要映射到的类型，必须实现 FromRow trait
+/// 
+/// # 参数
+/// * `conn` - 持有锁的事务连接
+/// * `builder` - 包含加锁查询的 QueryBuilder
+/// 
+/// # 返回值
+/// 成功时返回可选的映射行，失败时返回 Error
+pub async fn fetch_optional_nowait<'a, T>(
+    conn: &mut MySqlConnection,
+    builder: QueryBuilder<'a, MySql>,
+) -> Result<Option<T>, Error>
+where
+    T: for<'r> FromRow<'r, MySqlRow> + Unpin + Send + 'a,
+{
+    let mut builder = intercept(builder)?;
+    #[cfg(debug_assertions)]
+    {
+        let sql = builder.sql();
+        dbg!(sql);
+    }
+    builder.build_query_as::<T>()
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|err| {
+            let lock_not_available = err.as_database_error()
+                .and_then(|e| e.try_downcast_ref::<MySqlDatabaseError>())
+                .is_some_and(|e| e.number() == 3572);
+            if lock_not_available {
+                QueryError::LockNotAvailable.into()
+            } else {
+                err
+            }
+        })
+}
+
 /// Fetch all rows and map them to a vector of types
 /// 
 /// # Type Parameters
@@ -184,11 +367,12 @@ where
 /// # 返回值
 /// 成功时返回映射类型的向量，失败时返回 Error
 pub async fn fetch_all<'a, T>(
-    mut builder: QueryBuilder<'a, MySql>,
+    builder: QueryBuilder<'a, MySql>,
 ) -> Result<Vec<T>, Error>
 where
     T: for<'r> FromRow<'r, MySqlRow> + Unpin + Send + 'a,
 {
+    let mut builder = intercept(builder)?;
     #[cfg(debug_assertions)]
     {
         let sql = builder.sql();
@@ -198,6 +382,391 @@ where
     builder.build_query_as::<T>().fetch_all(&*pool).await
 }
 
+/// Fetch the first column of every row, e.g. the values of a `SELECT DISTINCT column` query
+/// 
+/// # Type Parameters
+/// * `V` - Type to decode the column to
+/// 
+/// # Arguments
+/// * `builder` - QueryBuilder selecting a single column (see `Select::distinct`)
+/// 
+/// # Returns
+/// Vector of column values on success or an Error
+/// 
+/// 获取每一行的第一列，例如 `SELECT DISTINCT column` 查询的值
+/// 
+/// # 类型参数
+/// * `V` - 列解码后的类型
+/// 
+/// # 参数
+/// * `builder` - 只选择一列的 QueryBuilder（参见 `Select::distinct`）
+/// 
+/// # 返回值
+/// 成功时返回列值的向量，失败时返回 Error
+pub async fn fetch_column<'a, V>(
+    builder: QueryBuilder<'a, MySql>,
+) -> Result<Vec<V>, Error>
+where
+    V: for<'r> Decode<'r, MySql> + Type<MySql> + Send + Unpin + 'a,
+{
+    let mut builder = intercept(builder)?;
+    #[cfg(debug_assertions)]
+    {
+        let sql = builder.sql();
+        dbg!(sql);
+    }
+    let pool = connection::get_db_pool()?;
+    builder.build_query_scalar::<V>().fetch_all(&*pool).await
+}
+
+/// Fetch adjacency-list rows and assemble them into a tree
+/// 
+/// Rows whose parent does not match another fetched row become roots, see `TreeNode::build`.
+/// 
+/// # Type Parameters
+/// * `T` - Row type, must implement FromRow and FieldAccess
+/// 
+/// # Arguments
+/// * `parent_column` - Column holding the parent id, e.g. `parent_id`
+/// * `id_column` - Column holding the row id, e.g. `id`
+/// * `builder` - QueryBuilder selecting the rows, including any condition and ordering
+/// 
+/// # Returns
+/// The root nodes with their children nested, or an Error
+/// 
+/// 获取邻接表行并组装为树
+/// 
+/// 父值与其他已获取行都不匹配的行成为根节点，参见 `TreeNode::build`。
+/// 
+/// # 类型参数
+/// * `T` - 行类型，必须实现 FromRow 和 FieldAccess
+/// 
+/// # 参数
+/// * `parent_column` - 保存父 id 的列，例如 `parent_id`
+/// * `id_column` - 保存行 id 的列，例如 `id`
+/// * `builder` - 选择行的 QueryBuilder，包含条件和排序
+/// 
+/// # 返回值
+/// 嵌套了子节点的根节点，失败时返回 Error
+pub async fn fetch_tree<'a, T>(
+    parent_column: &str,
+    id_column: &str,
+    builder: QueryBuilder<'a, MySql>,
+) -> Result<Vec<TreeNode<T>>, Error>
+where
+    T: for<'r> FromRow<'r, MySqlRow> + FieldAccess + Unpin + Send + 'a,
+{
+    let rows: Vec<T> = fetch_all(builder).await?;
+    Ok(TreeNode::build::<DataKind>(rows, parent_column, id_column))
+}
+
+/// Fetch the rows changed after a timestamp, oldest first (delta fetch for incremental sync)
+/// 
+/// Builds `SELECT ... WHERE timestamp_column > ? ... ORDER BY timestamp_column ASC`. The
+/// `query_condition` closure is called right after `timestamp_column > ?` and adds any extra
+/// conditions, each starting with ` AND `; pass `|_| {}` for none.
+/// 
+/// # Type Parameters
+/// * `T` - Entity type to map the rows to, must implement FromRow and FieldAccess
+/// 
+/// # Arguments
+/// * `timestamp_column` - Column recording the last change, e.g. `updated_at`
+/// * `since` - Only rows with a later timestamp are returned
+/// * `query_condition` - Function adding extra conditions
+/// 
+/// # Returns
+/// The changed rows ordered by the timestamp column, or an Error
+/// 
+/// 获取某时间戳之后变更的行，按时间从早到晚排列（增量同步的差量获取）
+/// 
+/// 生成 `SELECT ... WHERE timestamp_column > ? ... ORDER BY timestamp_column ASC`。
+/// `query_condition` 闭包在 `timestamp_column > ?` 之后调用，用于添加额外条件，
+/// 每个条件以 ` AND ` 开头；没有额外条件时传入 `|_| {}`。
+/// 
+/// # 类型参数
+/// * `T` - 要映射到的实体类型，必须实现 FromRow 和 FieldAccess
+/// 
+/// # 参数
+/// * `timestamp_column` - 记录最后变更时间的列，例如 `updated_at`
+/// * `since` - 只返回时间戳晚于该值的行
+/// * `query_condition` - 添加额外条件的函数
+/// 
+/// # 返回值
+/// 按时间戳列排序的变更行，失败时返回 Error
+pub async fn get_changed_since<T>(
+    timestamp_column: &str,
+    since: DataKind,
+    query_condition: impl FnOnce(&mut QueryBuilder<'_, MySql>),
+) -> Result<Vec<T>, Error>
+where
+    T: for<'r> FromRow<'r, MySqlRow> + FieldAccess + Default + Unpin + Send,
+{
+    let builder = Select::<T>::table()
+        .filter(|b| {
+            b.push(timestamp_column).push(" > ").push_bind(since);
+            query_condition(b);
+        })
+        .order_by(timestamp_column, Order::Asc)
+        .finish();
+    fetch_all(builder).await
+}
+
+/// Run the same query on every named pool concurrently and concatenate the rows (scatter-gather)
+/// 
+/// A QueryBuilder cannot be cloned, so `builder_factory` is called once per pool to build the
+/// query. Rows are concatenated in pool name order; the first failing pool aborts the call.
+/// 
+/// # Type Parameters
+/// * `T` - Type to map the rows to, must implement FromRow trait
+/// 
+/// # Arguments
+/// * `builder_factory` - Function building the query, called once per named pool
+/// 
+/// # Returns
+/// The rows of all pools, or an Error (`QueryError::DBPoolNotInitialized` if no named pool is registered)
+/// 
+/// 在所有命名连接池上并发执行同一查询并拼接结果行（分散-聚合）
+/// 
+/// QueryBuilder 无法克隆，因此每个连接池调用一次 `builder_factory` 构建查询。
+/// 结果按连接池名称顺序拼接；任一连接池失败则整个调用失败。
+/// 
+/// # 类型参数
+/// * `T` - 要映射到的类型，必须实现 FromRow trait
+/// 
+/// # 参数
+/// * `builder_factory` - 构建查询的函数，每个命名连接池调用一次
+/// 
+/// # 返回值
+/// 所有连接池的结果行，失败时返回 Error（未注册任何命名连接池时为 `QueryError::DBPoolNotInitialized`）
+pub async fn fetch_all_sharded<'a, T, F>(
+    builder_factory: F,
+) -> Result<Vec<T>, Error>
+where
+    T: for<'r> FromRow<'r, MySqlRow> + Unpin + Send + 'a,
+    F: Fn() -> QueryBuilder<'a, MySql>,
+{
+    let pools = connection::named_pools();
+    if pools.is_empty() {
+        return Err(QueryError::DBPoolNotInitialized.into());
+    }
+
+    let mut builders = pools.iter()
+        .map(|_| intercept(builder_factory()))
+        .collect::<Result<Vec<_>, Error>>()?;
+    #[cfg(debug_assertions)]
+    {
+        let sql = builders[0].sql();
+        dbg!(sql);
+    }
+    let tasks = pools.iter()
+        .zip(builders.iter_mut())
+        .map(|((_, pool), builder)| builder.build_query_as::<T>().fetch_all(&**pool));
+    let rows = try_join_all(tasks).await?;
+
+    Ok(rows.into_iter().flatten().collect())
+}
+
+/// Stream query results to a writer as newline-delimited JSON (one object per row)
+/// 
+/// Rows are decoded and written one at a time while the query streams, so the whole result
+/// is never held in memory. Write and serialization failures are returned as `Error::Io`.
+/// 
+/// # Type Parameters
+/// * `T` - Row type, must implement FromRow and Serialize
+/// * `W` - Destination, e.g. a file or `Vec<u8>`
+/// 
+/// # Arguments
+/// * `builder` - QueryBuilder containing the query to execute
+/// * `writer` - Destination of the JSON lines
+/// 
+/// # Returns
+/// The number of rows written, or an Error
+/// 
+/// 以换行分隔的 JSON（每行一个对象）将查询结果流式写入 writer
+/// 
+/// 查询以流的方式执行，每行解码后立即写出，因此不会在内存中保留整个结果集。
+/// 写入和序列化失败以 `Error::Io` 返回。
+/// 
+/// # 类型参数
+/// * `T` - 行类型，必须实现 FromRow 和 Serialize
+/// * `W` - 写入目标，例如文件或 `Vec<u8>`
+/// 
+/// # 参数
+/// * `builder` - 包含要执行查询的 QueryBuilder
+/// * `writer` - JSON 行的写入目标
+/// 
+/// # 返回值
+/// 写入的行数，失败时返回 Error
+pub async fn export_ndjson<'a, T, W>(
+    builder: QueryBuilder<'a, MySql>,
+    writer: &mut W,
+) -> Result<u64, Error>
+where
+    T: for<'r> FromRow<'r, MySqlRow> + Serialize + Unpin + Send + 'a,
+    W: Write,
+{
+    let mut builder = intercept(builder)?;
+    #[cfg(debug_assertions)]
+    {
+        let sql = builder.sql();
+        dbg!(sql);
+    }
+    let pool = connection::get_db_pool()?;
+    let mut rows = builder.build_query_as::<T>().fetch(&*pool);
+    let mut count = 0;
+    while let Some(row) = rows.try_next().await? {
+        serde_json::to_writer(&mut *writer, &row).map_err(io::Error::from)?;
+        writer.write_all(b"\n")?;
+        count += 1;
+    }
+    writer.flush()?;
+
+    Ok(count)
+}
+
+/// Stream query results row by row instead of collecting them into a `Vec`
+/// 
+/// The query runs on a background task that decodes rows as they arrive and hands them over
+/// through a bounded channel, so memory stays bounded however many rows match. Query
+/// interceptors are applied as for `fetch_all`. Dropping the stream stops the query and
+/// releases its connection.
+/// 
+/// # Type Parameters
+/// * `T` - Type to map the rows to, must implement FromRow trait
+/// 
+/// # Arguments
+/// * `builder` - QueryBuilder containing the query to execute
+/// 
+/// # Returns
+/// A stream of mapped rows; a failing query yields a single Error item, and a panic on the
+/// background task (e.g. in a `FromRow` implementation) yields `Error::WorkerCrashed`
+/// 
+/// 以流的方式逐行返回查询结果，而不是收集到 `Vec` 中
+/// 
+/// 查询在后台任务中执行，每行到达后立即解码并通过有界通道传递，因此无论匹配多少行，内存占用都是有界的。
+/// 与 `fetch_all` 一样会应用查询拦截器。丢弃流会停止查询并释放其连接。
+/// 
+/// # 类型参数
+/// * `T` - 要映射到的类型，必须实现 FromRow trait
+/// 
+/// # 参数
+/// * `builder` - 包含要执行查询的 QueryBuilder
+/// 
+/// # 返回值
+/// 映射后的行组成的流；查询失败时产生一个 Error 项，后台任务 panic（例如在 `FromRow` 实现中）
+/// 时产生 `Error::WorkerCrashed`
+pub fn fetch_stream<T>(
+    builder: QueryBuilder<'static, MySql>,
+) -> impl Stream<Item = Result<T, Error>> + Send + 'static
+where
+    T: for<'r> FromRow<'r, MySqlRow> + Unpin + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel(STREAM_BUFFER_SIZE);
+    tokio::spawn(async move {
+        // 后台任务 panic 时也要向流发送错误项，否则流会像正常结束一样悄然终止
+        let err = match AssertUnwindSafe(stream_rows(builder, &sender)).catch_unwind().await {
+            Ok(Ok(())) => return,
+            Ok(Err(err)) => err,
+            Err(_) => Error::WorkerCrashed,
+        };
+        let _ = sender.send(Err(err)).await;
+    });
+    stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|row| (row, receiver))
+    })
+}
+
+/// Fetch one page of rows and report whether another page follows, without a COUNT query
+/// 
+/// The builder is expected to request `page_size + 1` rows (see `Select::paginate_plus_one`).
+/// If the extra row comes back it is dropped and `has_next` is `true`.
+/// 
+/// # Type Parameters
+/// * `T` - Type to map the rows to, must implement FromRow trait
+/// 
+/// # Arguments
+/// * `builder` - QueryBuilder fetching up to `page_size + 1` rows
+/// * `page_size` - Number of records per page
+/// 
+/// # Returns
+/// The page rows and the `has_next` flag on success or an Error
+/// 
+/// 获取一页数据并判断是否还有下一页，无需执行 COUNT 查询
+/// 
+/// 构建器应请求 `page_size + 1` 行（参见 `Select::paginate_plus_one`）。
+/// 如果多取的一行存在，则将其丢弃并返回 `has_next` 为 `true`。
+/// 
+/// # 类型参数
+/// * `T` - 要映射到的类型，必须实现 FromRow trait
+/// 
+/// # 参数
+/// * `builder` - 最多获取 `page_size + 1` 行的 QueryBuilder
+/// * `page_size` - 每页记录数
+/// 
+/// # 返回值
+/// 成功时返回当前页数据和 `has_next` 标志，失败时返回 Error
+pub async fn fetch_page_plus_one<'a, T>(
+    builder: QueryBuilder<'a, MySql>,
+    page_size: u64,
+) -> Result<(Vec<T>, bool), Error>
+where
+    T: for<'r> FromRow<'r, MySqlRow> + Unpin + Send + 'a,
+{
+    let mut rows = fetch_all::<T>(builder).await?;
+    let has_next = rows.len() as u64 > page_size;
+    rows.truncate(page_size as usize);
+    Ok((rows, has_next))
+}
+
+/// Fetch one cursor page whose rows carry opaque cursor tokens
+/// 
+/// Runs a cursor query (see `Select::cursor` and `Select::cursor_multi`) and encodes each row's
+/// `key_fields` into a token, see `CursorPaginatedResult::into_encoded`. The page's `next`/`prev`
+/// tokens are set only when a full page of `limit` rows came back.
+/// 
+/// # Type Parameters
+/// * `T` - Type to map the rows to, must implement FromRow and Serialize
+/// 
+/// # Arguments
+/// * `builder` - Cursor QueryBuilder fetching up to `limit` rows
+/// * `limit` - Page size passed to the cursor method
+/// * `sort_order` - Sort order of the cursor query
+/// * `key_fields` - Fields that identify a row's position, e.g. `&["created_at", "id"]`
+/// 
+/// # Returns
+/// An EncodedCursorPage on success, or an Error if the query fails or a row cannot be serialized
+/// 
+/// 获取一页游标分页数据，每条记录都带有不透明游标令牌
+/// 
+/// 执行游标查询（参见 `Select::cursor` 和 `Select::cursor_multi`），并将每行的 `key_fields`
+/// 编码为令牌，参见 `CursorPaginatedResult::into_encoded`。只有返回满页 `limit` 行时才设置
+/// 页面的 `next`/`prev` 令牌。
+/// 
+/// # 类型参数
+/// * `T` - 要映射到的类型，必须实现 FromRow 和 Serialize
+/// 
+/// # 参数
+/// * `builder` - 最多获取 `limit` 行的游标 QueryBuilder
+/// * `limit` - 传给游标方法的每页记录数
+/// * `sort_order` - 游标查询的排序方向
+/// * `key_fields` - 确定记录位置的字段，例如 `&["created_at", "id"]`
+/// 
+/// # 返回值
+/// 成功时返回 EncodedCursorPage，查询失败或记录无法序列化时返回 Error
+pub async fn fetch_cursor_encoded<'a, T>(
+    builder: QueryBuilder<'a, MySql>,
+    limit: u64,
+    sort_order: Order,
+    key_fields: &[&str],
+) -> Result<EncodedCursorPage<T>, Error>
+where
+    T: for<'r> FromRow<'r, MySqlRow> + Serialize + Unpin + Send + 'a,
+{
+    let rows = fetch_all::<T>(builder).await?;
+    let page = CursorPaginatedResult::<T, DataKind>::new(rows, limit, sort_order);
+    Ok(page.into_encoded(key_fields)?)
+}
+
 /// Fetch a scalar value (typically a count or id)
 /// 
 /// # Arguments
@@ -214,9 +783,10 @@ where
 /// # 返回值
 /// 成功时返回 u64 标量值，失败时返回 Error
 pub async fn fetch_scalar<'a>(
-    mut builder: QueryBuilder<'a, MySql>
+    builder: QueryBuilder<'a, MySql>
 ) -> Result<i64, Error>
 {
+    let mut builder = intercept(builder)?;
     #[cfg(debug_assertions)]
     {
         let sql = builder.sql();
@@ -228,28 +798,600 @@ pub async fn fetch_scalar<'a>(
 
 /// Fetch an optional scalar value (typically a count or id)
 /// 
+/// The `i64` form of `fetch_scalar_opt`: no row and a `NULL` value both give `None`.
+/// Use `fetch_scalar_opt` to decode other types.
+/// 
 /// # Arguments
 /// * `builder` - QueryBuilder containing the query to execute
 /// 
 /// # Returns
-/// Optional u64 scalar value on success or an Error
+/// Optional i64 scalar value on success or an Error
 /// 
 /// 获取可选的标量值（通常是计数或ID）
 /// 
+/// `fetch_scalar_opt` 的 `i64` 形式：没有行或值为 `NULL` 时都返回 `None`。
+/// 解码为其他类型时使用 `fetch_scalar_opt`。
+/// 
 /// # 参数
 /// * `builder` - 包含要执行查询的 QueryBuilder
 /// 
 /// # 返回值
-/// 成功时返回可选的 u64 标量值，失败时返回 Error
+/// 成功时返回可选的 i64 标量值，失败时返回 Error
 pub async fn fetch_scalar_optional<'a>(
-    mut builder: QueryBuilder<'a, MySql>,
+    builder: QueryBuilder<'a, MySql>,
 ) -> Result<Option<i64>, Error>
 {
+    fetch_scalar_opt::<i64>(builder).await
+}
+
+/// Fetch an optional scalar of any type, e.g. `SELECT MAX(id) FROM table`
+/// 
+/// A `NULL` value (as returned by aggregates over no rows) is reported as `None` instead of
+/// failing to decode. `fetch_scalar_optional` is the `i64` form of this function.
+/// 
+/// # Type Parameters
+/// * `V` - Type to decode the scalar to
+/// 
+/// # Arguments
+/// * `builder` - QueryBuilder selecting a single column
+/// 
+/// # Returns
+/// The scalar value, `None` when there is no row or the value is `NULL`, or an Error
+/// 
+/// 获取任意类型的可选标量值，例如 `SELECT MAX(id) FROM table`
+/// 
+/// `NULL` 值（例如聚合函数在没有行时的返回值）同样返回 `None`，而不会解码失败。
+/// `fetch_scalar_optional` 是该函数的 `i64` 形式。
+/// 
+/// # 类型参数
+/// * `V` - 标量解码后的类型
+/// 
+/// # 参数
+/// * `builder` - 只选择一列的 QueryBuilder
+/// 
+/// # 返回值
+/// 标量值，没有行或值为 `NULL` 时返回 `None`，失败时返回 Error
+pub async fn fetch_scalar_opt<'a, V>(
+    builder: QueryBuilder<'a, MySql>,
+) -> Result<Option<V>, Error>
+where
+    V: for<'r> Decode<'r, MySql> + Type<MySql> + Send + Unpin + 'a,
+{
+    let mut builder = intercept(builder)?;
+    #[cfg(debug_assertions)]
+    {
+        let sql = builder.sql();
+        dbg!(sql);
+    }
+    let pool = connection::get_db_pool()?;
+    let value = builder.build_query_scalar::<Option<V>>().fetch_optional(&*pool).await?;
+    Ok(value.flatten())
+}
+
+/// Fetch the result of an existence query
+/// 
+/// # Arguments
+/// * `builder` - QueryBuilder producing a single EXISTS value, e.g. from `Subquery::into_exists`
+/// 
+/// # Returns
+/// true if the subquery matched any row, or an Error
+/// 
+/// 获取存在性查询的结果
+/// 
+/// # 参数
+/// * `builder` - 生成单个 EXISTS 值的 QueryBuilder，例如来自 `Subquery::into_exists`
+/// 
+/// # 返回值
+/// 子查询匹配到任意行时返回 true，失败时返回 Error
+pub async fn fetch_exists<'a>(
+    builder: QueryBuilder<'a, MySql>,
+) -> Result<bool, Error>
+{
+    let mut builder = intercept(builder)?;
+    #[cfg(debug_assertions)]
+    {
+        let sql = builder.sql();
+        dbg!(sql);
+    }
+    let pool = connection::get_db_pool()?;
+    let exists = builder.build_query_scalar::<i64>().fetch_one(&*pool).await?;
+    Ok(exists != 0)
+}
+
+/// Fetch a page of rows together with the total count from one consistent snapshot
+/// 
+/// Both queries run inside a single `READ ONLY` transaction at REPEATABLE READ isolation, so the
+/// total always matches the data even while other sessions are writing.
+/// 
+/// # Type Parameters
+/// * `T` - Type to map the rows to, must implement FromRow trait
+/// 
+/// # Arguments
+/// * `count_builder` - QueryBuilder returning the total count as a single scalar
+/// * `list_builder` - QueryBuilder returning the rows of the requested page
+/// * `page_number` - Current page number
+/// * `page_size` - Number of records per page
+/// 
+/// # Returns
+/// PaginatedResult on success or an Error
+/// 
+/// 在同一个一致性快照中获取分页数据和总数
+/// 
+/// 两个查询在同一个只读事务中执行，隔离级别为 REPEATABLE READ，因此即使其他会话正在写入，
+/// 总数也始终与数据保持一致。
+/// 
+/// # 类型参数
+/// * `T` - 要映射到的类型，必须实现 FromRow trait
+/// 
+/// # 参数
+/// * `count_builder` - 返回单个总数标量的 QueryBuilder
+/// * `list_builder` - 返回当前页数据的 QueryBuilder
+/// * `page_number` - 当前页码
+/// * `page_size` - 每页记录数
+/// 
+/// # 返回值
+/// 成功时返回 PaginatedResult，失败时返回 Error
+pub async fn fetch_page_consistent<'a, T>(
+    count_builder: QueryBuilder<'a, MySql>,
+    list_builder: QueryBuilder<'a, MySql>,
+    page_number: u64,
+    page_size: u64,
+) -> Result<PaginatedResult<T>, Error>
+where
+    T: for<'r> FromRow<'r, MySqlRow> + Unpin + Send + 'a,
+{
+    #[cfg(debug_assertions)]
+    {
+        dbg!(count_builder.sql());
+        dbg!(list_builder.sql());
+    }
+    let pool = connection::get_db_pool()?;
+    let mut conn = pool.acquire().await?;
+    // 不带 SESSION 的 SET TRANSACTION 只作用于该连接上的下一个事务
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY").execute(&mut *conn).await?;
+    let mut tx = conn.begin().await?;
+    let mut count_builder = intercept(count_builder)?;
+    let mut list_builder = intercept(list_builder)?;
+    let total = count_builder.build_query_scalar::<i64>().fetch_one(&mut *tx).await?;
+    let data = list_builder.build_query_as::<T>().fetch_all(&mut *tx).await?;
+    tx.commit().await?;
+
+    Ok(PaginatedResult::new(data, total as u64, page_number, page_size))
+}
+
+/// Check whether a table exists
+/// 
+/// Looks the table up in `information_schema.tables` of the current database.
+/// 
+/// # Arguments
+/// * `name` - Table name
+/// 
+/// # Returns
+/// True if the table exists, or an Error
+/// 
+/// 检查表是否存在
+/// 
+/// 通过 当前数据库的 `information_schema.tables` 查找表。
+/// 
+/// # 参数
+/// * `name` - 表名
+/// 
+/// # 返回值
+/// 表存在时返回 true，失败时返回 Error
+pub async fn table_exists(name: &str) -> Result<bool, Error> {
+    let pool = connection::get_db_pool()?;
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = DATABASE() AND table_name = ?"
+    )
+        .bind(name)
+        .fetch_one(&*pool)
+        .await?;
+    Ok(count > 0)
+}
+
+/// Insert or update a single entity depending on its primary key
+/// 
+/// When every primary key value is default (see `is_default_pk`) the entity is inserted,
+/// otherwise it is updated by primary key. No conflict clause is emitted, so this also
+/// works for tables without a unique constraint beyond the primary key.
+/// 
+/// # Arguments
+/// * `entity` - Entity to save
+/// * `primary_key` - Primary key definition
+/// 
+/// # Returns
+/// True if the entity was inserted, false if it was updated, or an Error
+/// 
+/// 根据主键插入或更新单个实体
+/// 
+/// 所有主键值均为默认值时（参见 `is_default_pk`）插入实体，否则按主键更新。
+/// 不会生成冲突子句，因此也适用于除主键外没有唯一约束的表。
+/// 
+/// # 参数
+/// * `entity` - 要保存的实体
+/// * `primary_key` - 主键定义
+/// 
+/// # 返回值
+/// 插入时返回 true，更新时返回 false，失败时返回 Error
+pub async fn save<'a, ET>(
+    entity: &'a ET,
+    primary_key: &PrimaryKey<'a>,
+) -> Result<bool, Error>
+where
+    ET: FieldAccess + Default,
+{
+    if is_default_pk::<ET, DataKind>(entity, primary_key) {
+        execute(Insert::one(entity, primary_key)?).await?;
+        Ok(true)
+    } else {
+        execute(Update::one(entity, primary_key, false)?).await?;
+        Ok(false)
+    }
+}
+
+/// Insert a single entity and return the stored row, including generated values
+/// 
+/// MySQL has no `RETURNING` clause (MariaDB 10.5+ does, but MySQL 8 does not), so the row is
+/// read back on the same connection after the insert: by `LAST_INSERT_ID()` for an auto
+/// generated single key, otherwise by the entity's own key values.
+/// 
+/// # Arguments
+/// * `entity` - Entity to insert
+/// * `primary_key` - Primary key definition
+/// 
+/// # Returns
+/// The inserted row, or an Error
+/// 
+/// 插入单个实体并返回存储的行，包括生成的值
+/// 
+/// MySQL 没有 `RETURNING` 子句（MariaDB 10.5+ 支持，MySQL 8 不支持），因此插入后在同一连接上读回该行：
+/// 自动生成的单列主键按 `LAST_INSERT_ID()` 查询，否则按实体自身的主键值查询。
+/// 
+/// # 参数
+/// * `entity` - 要插入的实体
+/// * `primary_key` - 主键定义
+/// 
+/// # 返回值
+/// 插入的行，失败时返回 Error
+pub async fn insert_one_returning<'a, ET>(
+    entity: &'a ET,
+    primary_key: &PrimaryKey<'a>,
+) -> Result<ET, Error>
+where
+    ET: FieldAccess + Default + for<'r> FromRow<'r, MySqlRow> + Unpin + Send,
+{
+    let builder = Insert::one(entity, primary_key)?;
+    let mut builder = intercept(builder)?;
+    #[cfg(debug_assertions)]
+    {
+        let sql = builder.sql();
+        dbg!(sql);
+    }
+    let pool = connection::get_db_pool()?;
+    let mut conn = pool.acquire().await?;
+    let result = builder.build().execute(&mut *conn).await?;
+
+    let keys = primary_key.get_keys();
+    let values = if primary_key.auto_generate() {
+        vec![DataKind::from(result.last_insert_id())]
+    } else {
+        get_values::<ET, DataKind>(entity, &keys)
+    };
+    let select = Select::<ET>::table()
+        .filter(|b| {
+            for (i, (key, value)) in keys.iter().zip(values).enumerate() {
+                if i > 0 {
+                    b.push(" AND ");
+                }
+                b.push(*key).push(" = ").push_bind(value);
+            }
+        })
+        .finish();
+    let mut select = intercept(select)?;
+    select.build_query_as().fetch_one(&mut *conn).await
+}
+
+/// Update the row matching a natural key column, or insert the entity if there is none
+/// 
+/// Runs `UPDATE ... SET <other columns> WHERE column = ?` with the entity's value for `column`
+/// and, when no row was updated, inserts the entity, both in one transaction. Unlike `save`,
+/// the row is found by a unique column other than the primary key, and unlike an upsert no
+/// conflict target is needed. If a concurrent insert of the same key wins the race, the insert
+/// fails with a unique violation and the whole step is retried once, now updating that row;
+/// this relies on a unique constraint on `column`. The primary key columns are never updated.
+/// 
+/// # Arguments
+/// * `column` - Natural key column identifying the row
+/// * `entity` - Entity to update or insert
+/// * `primary_key` - Primary key definition
+/// 
+/// # Returns
+/// True if the entity was inserted, false if an existing row was updated, or an Error
+/// 
+/// 按自然键列更新匹配的行，不存在时插入实体
+/// 
+/// 以实体在 `column` 上的值执行 `UPDATE ... SET <其他列> WHERE column = ?`，未更新任何行时插入实体，
+/// 两者在同一事务中执行。与 `save` 不同，该方法按主键以外的唯一列定位行；与更新插入不同，它不需要冲突目标。
+/// 若并发插入了相同的键，插入会因唯一约束冲突而失败，此时整个步骤重试一次并改为更新该行；
+/// 这依赖于 `column` 上的唯一约束。主键列不会被更新。
+/// 
+/// # 参数
+/// * `column` - 用于定位行的自然键列
+/// * `entity` - 要更新或插入的实体
+/// * `primary_key` - 主键定义
+/// 
+/// # 返回值
+/// 插入时返回 true，更新已有行时返回 false，失败时返回 Error
+pub async fn update_or_insert_by<'a, ET>(
+    column: &'a str,
+    entity: &'a ET,
+    primary_key: &PrimaryKey<'a>,
+) -> Result<bool, Error>
+where
+    ET: FieldAccess + Default,
+{
+    let keys = primary_key.get_keys();
+    let columns: Vec<&str> = entity.field_names().iter()
+        .copied()
+        .filter(|name| *name != column && !keys.contains(name))
+        .collect();
+
+    let mut retried = false;
+    loop {
+        let result = transaction(async |conn: &mut MySqlConnection| {
+            let builder = Update::<ET>::table()
+                .set_from_entity(entity, &columns)?
+                .filter(|b| {
+                    b.push(column).push(" = ").push_bind(get_value::<ET, DataKind>(entity, column));
+                })
+                .finish();
+            let mut builder = intercept(builder)?;
+            #[cfg(debug_assertions)]
+            {
+                let sql = builder.sql();
+                dbg!(sql);
+            }
+            if builder.build().execute(&mut *conn).await?.rows_affected() > 0 {
+                return Ok(false);
+            }
+
+            let builder = Insert::one(entity, primary_key)?;
+            let mut builder = intercept(builder)?;
+            #[cfg(debug_assertions)]
+            {
+                let sql = builder.sql();
+                dbg!(sql);
+            }
+            builder.build().execute(&mut *conn).await?;
+            Ok(true)
+        }).await;
+
+        match result {
+            Err(Error::Database(e)) if !retried && e.is_unique_violation() => retried = true,
+            result => return result,
+        }
+    }
+}
+
+/// Fetch the row matching a condition, or insert the entity and return it if there is none
+/// 
+/// Looks the row up with `find`; when nothing matches, inserts the entity and fetches it again
+/// with the same condition, all in one transaction. If a concurrent insert wins the race, the
+/// insert fails with a unique violation and the whole step is retried once, now finding that
+/// row; this relies on a unique constraint covering the condition.
+/// 
+/// # Arguments
+/// * `find` - Function building the WHERE conditions that identify the row
+/// * `entity` - Entity to insert when no row matches
+/// * `primary_key` - Primary key definition
+/// 
+/// # Returns
+/// The existing or newly created row, or an Error
+/// 
+/// 获取匹配条件的行，不存在时插入实体并返回
+/// 
+/// 先通过 `find` 查找行；没有匹配时插入实体并以相同条件重新查询，全部在同一事务中执行。
+/// 若并发插入抢先完成，插入会因唯一约束冲突而失败，此时整个步骤重试一次并改为查到该行；
+/// 这依赖于覆盖该条件的唯一约束。
+/// 
+/// # 参数
+/// * `find` - 构建定位该行的 WHERE 条件的函数
+/// * `entity` - 没有匹配行时要插入的实体
+/// * `primary_key` - 主键定义
+/// 
+/// # 返回值
+/// 已存在或新创建的行，失败时返回 Error
+pub async fn find_or_create<'a, ET, F>(
+    find: F,
+    entity: &'a ET,
+    primary_key: &PrimaryKey<'a>,
+) -> Result<ET, Error>
+where
+    ET: FieldAccess + Default + for<'r> FromRow<'r, MySqlRow> + Unpin + Send,
+    F: Fn(&mut QueryBuilder<'_, MySql>),
+{
+    let mut retried = false;
+    loop {
+        let result = transaction(async |conn: &mut MySqlConnection| {
+            if let Some(row) = fetch_optional_by(&mut *conn, &find).await? {
+                return Ok(row);
+            }
+
+            let builder = Insert::one(entity, primary_key)?;
+            let mut builder = intercept(builder)?;
+            #[cfg(debug_assertions)]
+            {
+                let sql = builder.sql();
+                dbg!(sql);
+            }
+            builder.build().execute(&mut *conn).await?;
+            fetch_optional_by(&mut *conn, &find).await?.ok_or(Error::RowNotFound)
+        }).await;
+
+        match result {
+            Err(Error::Database(e)) if !retried && e.is_unique_violation() => retried = true,
+            result => return result,
+        }
+    }
+}
+
+/// Delete a row and its child rows within a transaction
+/// 
+/// Runs the queries of `Delete::cascade_by_key`, emulating `ON DELETE CASCADE`: the child
+/// rows referencing the key are deleted first, then the parent row. If any statement fails
+/// the transaction is rolled back, so no orphaned or partly deleted rows are left.
+/// 
+/// # Arguments
+/// * `key_column` - Parent key column name
+/// * `key_value` - Parent key value
+/// * `children` - Child tables referencing the parent key
+/// 
+/// # Returns
+/// The number of parent rows deleted, or an Error
+/// 
+/// 在事务中删除记录及其子表记录
+/// 
+/// 执行 `Delete::cascade_by_key` 生成的查询，模拟 `ON DELETE CASCADE`：先删除引用该键的子表记录，
+/// 再删除父记录。任一语句失败时回滚事务，不会留下孤立或部分删除的记录。
+/// 
+/// # 参数
+/// * `key_column` - 父表键列名
+/// * `key_value` - 父表键值
+/// * `children` - 引用父表键的子表
+/// 
+/// # 返回值
+/// 删除的父记录行数，失败时返回 Error
+pub async fn cascade_by_key<ET>(
+    key_column: &str,
+    key_value: DataKind,
+    children: &[ChildSpec<'_>],
+) -> Result<u64, Error>
+where
+    ET: FieldAccess,
+{
+    let builders = Delete::<ET>::cascade_by_key(key_column, key_value, children);
+    let results = execute_with_trans(builders).await?;
+    Ok(results.last().map_or(0, |result| result.rows_affected()))
+}
+
+/// Archive a row and soft-delete it within a transaction
+/// 
+/// Runs the queries of `Delete::archive_by_key`: the row is copied into `archive_table`
+/// unless it is already soft-deleted, then flagged as deleted. Both statements commit or
+/// roll back together.
+/// 
+/// # Arguments
+/// * `key_column` - Key column name
+/// * `key_value` - Key value of the row
+/// * `archive_table` - Table receiving the copy, with the same columns as the entity
+/// * `soft_delete_column` - Boolean column flagging the row as deleted
+/// 
+/// # Returns
+/// The number of rows archived, 0 if the row is missing or already soft-deleted, or an Error
+/// 
+/// 在事务中归档记录并将其软删除
+/// 
+/// 执行 `Delete::archive_by_key` 生成的查询：记录未被软删除时复制到 `archive_table`，
+/// 随后标记为已删除。两条语句同时提交或回滚。
+/// 
+/// # 参数
+/// * `key_column` - 键列名
+/// * `key_value` - 记录的键值
+/// * `archive_table` - 接收副本的表，列与实体相同
+/// * `soft_delete_column` - 标记记录已删除的布尔列
+/// 
+/// # 返回值
+/// 归档的行数，记录不存在或已被软删除时为 0，失败时返回 Error
+pub async fn archive_by_key<ET>(
+    key_column: &str,
+    key_value: DataKind,
+    archive_table: &str,
+    soft_delete_column: &str,
+) -> Result<u64, Error>
+where
+    ET: FieldAccess + Default,
+{
+    let builders = Delete::<ET>::archive_by_key(key_column, key_value, archive_table, soft_delete_column);
+    let results = execute_with_trans(builders).await?;
+    Ok(results.first().map_or(0, |result| result.rows_affected()))
+}
+
+/// Update a single entity by primary key, reporting whether the row exists
+/// 
+/// # Arguments
+/// * `entity` - Entity to update
+/// * `primary_key` - Primary key definition
+/// 
+/// # Returns
+/// `UpdateOutcome::Updated` if a row was affected, `UpdateOutcome::NotFound` otherwise, or an Error
+/// 
+/// 按主键更新单个实体，并报告该行是否存在
+/// 
+/// # 参数
+/// * `entity` - 要更新的实体
+/// * `primary_key` - 主键定义
+/// 
+/// # 返回值
+/// 有行受影响时返回 `UpdateOutcome::Updated`，否则返回 `UpdateOutcome::NotFound`，失败时返回 Error
+pub async fn update_one_checked<'a, ET>(
+    entity: &'a ET,
+    primary_key: &PrimaryKey<'a>,
+) -> Result<UpdateOutcome, Error>
+where
+    ET: FieldAccess + Default,
+{
+    let result = execute(Update::one(entity, primary_key, false)?).await?;
+    Ok(UpdateOutcome::from_rows_affected(result.rows_affected()))
+}
+
+/// 在给定连接上按条件查询实体的可选单行
+async fn fetch_optional_by<ET, F>(conn: &mut MySqlConnection, find: &F) -> Result<Option<ET>, Error>
+where
+    ET: FieldAccess + Default + for<'r> FromRow<'r, MySqlRow> + Unpin + Send,
+    F: Fn(&mut QueryBuilder<'_, MySql>),
+{
+    let builder = Select::<ET>::table()
+        .filter(|b| find(b))
+        .finish();
+    let mut builder = intercept(builder)?;
+    #[cfg(debug_assertions)]
+    {
+        let sql = builder.sql();
+        dbg!(sql);
+    }
+    builder.build_query_as::<ET>().fetch_optional(&mut *conn).await
+}
+
+/// 执行查询并将每行发送到通道，接收端被丢弃时提前结束
+async fn stream_rows<T>(
+    builder: QueryBuilder<'static, MySql>,
+    sender: &mpsc::Sender<Result<T, Error>>,
+) -> Result<(), Error>
+where
+    T: for<'r> FromRow<'r, MySqlRow> + Unpin + Send + 'static,
+{
+    let mut builder = intercept(builder)?;
     #[cfg(debug_assertions)]
     {
         let sql = builder.sql();
         dbg!(sql);
     }
     let pool = connection::get_db_pool()?;
-    builder.build_query_scalar::<i64>().fetch_optional(&*pool).await
-}
\ No newline at end of file
+    let mut rows = builder.build_query_as::<T>().fetch(&*pool);
+    while let Some(row) = rows.try_next().await? {
+        if sender.send(Ok(row)).await.is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// 应用查询拦截器：以改写后的 SQL 和原绑定参数重建 QueryBuilder，参见 `QueryInterceptor`；
+/// 未注册拦截器时直接返回原构建器
+fn intercept(mut builder: QueryBuilder<'_, MySql>) -> Result<QueryBuilder<'_, MySql>, Error> {
+    let Some(sql) = apply_interceptors(builder.sql(), MySql::NAME) else {
+        return Ok(builder);
+    };
+    let arguments = builder.build().take_arguments().map_err(Error::Encode)?.unwrap_or_default();
+    Ok(QueryBuilder::with_arguments(sql, arguments))
+}
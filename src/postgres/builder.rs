@@ -13,7 +13,7 @@
 
 use sqlx::{QueryBuilder, Postgres};
 
-use crate::{internal::{delete_builder, insert_builder, select_builder, subquery, update_builder, upsert_postgres}, postgres::kind::DataKind};
+use crate::{internal::{delete_builder, insert_builder, merge_postgres, select_builder, subquery, update_builder, upsert_postgres}, postgres::kind::DataKind};
 
 /// QueryBuilder type alias for PostgreSQL  
 /// PostgreSQL 的 QueryBuilder 类型别名
@@ -95,6 +95,12 @@ pub type SQB<'a> = subquery::SubqueryBuilder<'a, DataKind>;
 /// * `group_by` -  Add GROUP BY clause to the subquery
 /// * `having` - Add HAVING clause to the subquery
 /// * `append_to` - Embed the subquery into a parent query builder
+/// * `append_in_to` - Embed the subquery as `column IN (subquery)`, rejecting projections other than one column
+/// * `projected_columns` - Number of columns the subquery projects
+/// * `check_placeholders` - Check that raw `?` fragments match the bound values
+/// * `try_append_to` - Embed the subquery after checking its placeholders
+/// * `into_count` - Build a `SELECT COUNT(*)` query over the subquery as a derived table
+/// * `into_exists` - Build a `SELECT EXISTS (...)` query over the subquery, run with `fetch_exists`
 /// 
 /// # 公共方法
 /// 
@@ -106,6 +112,12 @@ pub type SQB<'a> = subquery::SubqueryBuilder<'a, DataKind>;
 /// * `group_by` - 向子查询中添加 GROUP BY 子句
 /// * `having` -  向子查询中添加 HAVING 子句
 /// * `append_to` - 将子查询嵌入到父查询构建器中
+/// * `append_in_to` - 以 `column IN (subquery)` 形式嵌入子查询，投影列数不为一时报错
+/// * `projected_columns` - 子查询投影的列数
+/// * `check_placeholders` - 检查原始 `?` 片段与绑定值数量是否一致
+/// * `try_append_to` - 检查占位符后再将子查询嵌入父查询
+/// * `into_count` - 基于子查询（作为派生表）构建 `SELECT COUNT(*)` 查询
+/// * `into_exists` - 基于子查询构建 `SELECT EXISTS (...)` 查询，配合 `fetch_exists` 执行
 /// 
 /// # Examples
 /// 
@@ -139,24 +151,44 @@ pub type Subquery<'a, ET> = subquery::Subquery<'a, ET, DataKind>;
 /// 
 /// * `one` - Create single record insert operation
 /// * `many` - Create multiple records insert operation
+/// * `many_with_casts` - Create multiple records insert operation, casting the placeholders of the given columns
+/// * `many_or_ignore` - Create multiple records insert operation skipping conflicting rows (ON CONFLICT DO NOTHING)
+/// * `one_with_columns` - Create single record insert with only the given columns
+/// * `many_with_explicit_id` - Create an insert with explicit identity values (OVERRIDING SYSTEM VALUE)
+/// * `one_with_explicit_id` - Create a single record insert with an explicit identity value
 /// * `table` - Create custom table and columns
 /// * `with_table` - Create a insert with a custom table name
 /// * `from_query` - Create an Insert instance from a query
 /// * `from_query_with_table` - Create an Insert instance from a query with a custom table name
 /// * `custom` - Custom VALUES or value-related query statements
+/// * `on_conflict_do_nothing` - Add ON CONFLICT DO NOTHING, with an optional conflict target
+/// * `on_conflict_on_constraint` - Add ON CONFLICT ON CONSTRAINT name DO UPDATE / DO NOTHING
+/// * `overriding_system_value` - Add OVERRIDING SYSTEM VALUE after the column list
 /// * `returning` - Add RETURNING clause to the insert statement
+/// * `try_returning` - Add RETURNING with the columns checked against the entity's fields
+/// * `returning_none` - Explicitly add no RETURNING clause (a marker, does not remove an earlier `returning`)
 /// * `finish` - Finish building, get the internal QueryBuilder
 /// 
 /// # 公共方法
 /// 
 /// * `one` - 创建单条记录插入操作
 /// * `many` - 创建多条记录插入操作
+/// * `many_with_casts` - 创建多条记录插入操作，并对指定列的占位符做类型转换
+/// * `many_or_ignore` - 创建跳过冲突行的多条记录插入操作（ON CONFLICT DO NOTHING）
+/// * `one_with_columns` - 创建只写入指定列的单条记录插入操作
+/// * `many_with_explicit_id` - 创建带显式标识值的插入操作（OVERRIDING SYSTEM VALUE）
+/// * `one_with_explicit_id` - 创建带显式标识值的单条记录插入操作
 /// * `table` - 创建默认表名的插入操作
 /// * `with_table` - 创建带有自定义表名的插入操作
 /// * `from_query` - 从外部查询中创建 Insert 实例
 /// * `from_query_with_table` - 从外部查询中创建 Insert 实例，可以自定义表名
 /// * `custom` - 自定义 VALUES 或值相关的查询语句
+/// * `on_conflict_do_nothing` - 添加 ON CONFLICT DO NOTHING，冲突目标可选
+/// * `on_conflict_on_constraint` - 添加 ON CONFLICT ON CONSTRAINT name DO UPDATE / DO NOTHING
+/// * `overriding_system_value` - 在列列表之后添加 OVERRIDING SYSTEM VALUE
 /// * `returning` - 添加 RETURNING 子句到插入语句
+/// * `try_returning` - 添加 RETURNING 子句，并校验各列均为实体字段
+/// * `returning_none` - 明确不添加 RETURNING 子句（仅作标记，不会移除之前的 `returning`）
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
 ///  
 /// # Examples
@@ -185,25 +217,43 @@ pub type Insert<'a, ET> = insert_builder::Insert<'a, ET, Postgres, DataKind>;
 /// # Public Methods
 /// 
 /// * `one` - Create a single entity update operation
+/// * `many` - Create a batch update of several entities as one statement with a CASE per column
+/// * `set_where` - Create an update assigning one value to a column on rows matching a condition
 /// * `table` - Create an Update instance with the default table name
 /// * `with_table` - Create an Update instance with a custom table name
 /// * `from_query` - Create an Update instance from a query
 /// * `from_query_with_table` - Create an Update instance from a query with a custom table name
+/// * `with_cte` - Create an UPDATE preceded by a WITH common table expression
+/// * `with_join` - Create an UPDATE reading from a joined table (JOIN on MySQL, FROM on PostgreSQL/SQLite)
+/// * `set_from_entity` - Add `col = ?` assignments using the entity's values for the given columns
+/// * `json_array_append` - Add an assignment appending one element to a JSON array column
+/// * `set_cast` - Add a `col = ?` assignment with the placeholder cast to a SQL type
 /// * `custom` - Custom SET columns or other query statements
 /// * `filter` - Add WHERE condition to the update statement
 /// * `returning` - Add RETURNING clause to the update statement 
+/// * `try_returning` - Add RETURNING with the columns checked against the entity's fields
+/// * `returning_none` - Explicitly add no RETURNING clause (a marker, does not remove an earlier `returning`)
 /// * `finish` - Finish building, get the internal QueryBuilder
 /// 
 /// # 公共方法
 /// 
 /// * `one` - 创建单个实体更新操作
+/// * `many` - 以单条语句创建多个实体的批量更新操作，每列一个 CASE
+/// * `set_where` - 创建将同一个值赋给匹配条件记录某一列的更新操作
 /// * `table` - 创建使用默认表名的 Update 实例
 /// * `with_table` - 创建使用自定义表名的 Update 实例
 /// * `from_query` - 从外部查询中创建 Update 实例
 /// * `from_query_with_table` - 从外部查询中创建 Update 实例，可以自定义表名
+/// * `with_cte` - 创建前置 WITH 公用表表达式的 UPDATE
+/// * `with_join` - 创建从关联表读取数据的 UPDATE（MySQL 使用 JOIN，PostgreSQL/SQLite 使用 FROM）
+/// * `set_from_entity` - 添加 `col = ?` 赋值，值取自实体的指定列
+/// * `json_array_append` - 添加向 JSON 数组列追加一个元素的赋值
+/// * `set_cast` - 添加占位符转换为 SQL 类型的 `col = ?` 赋值
 /// * `custom` - 自定义 SET 列或其他查询语句
 /// * `filter` - 向查询中添加过滤查询部分
 /// * `returning` - 添加 RETURNING 子句到更新语句
+/// * `try_returning` - 添加 RETURNING 子句，并校验各列均为实体字段
+/// * `returning_none` - 明确不添加 RETURNING 子句（仅作标记，不会移除之前的 `returning`）
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
 /// 
 /// # Examples
@@ -235,9 +285,19 @@ pub type Update<'a, ET> = update_builder::Update<'a, ET, Postgres, DataKind>;
 /// * `with_table` - Create a Delete instance with a custom table name
 /// * `from_query` - Create an Delete instance from a query
 /// * `from_query_with_table` - Create an Delete instance from a query with a custom table name
+/// * `with_cte` - Create a DELETE preceded by a WITH common table expression
+/// * `soft` - Create a soft delete `UPDATE table SET column = ?` from a `SoftDelete` configuration
 /// * `by_primary_key` - Create a DELETE query by primary key
+/// * `by_keys` - Create a DELETE query matching a key column against a list of keys
+/// * `cascade_by_key` - Create cascading DELETE queries for child tables and the parent row
+/// * `archive_by_key` - Create queries copying a row into an archive table and soft-deleting it
+/// * `dedupe_keep_latest` - Create a DELETE removing duplicates, keeping the latest row per group
+/// * `using` - Create a DELETE matching rows of another table (USING / JOIN / EXISTS per database)
+/// * `by_keys_any` - Create a DELETE query matching a key column with `= ANY(array)` (PostgreSQL only)
 /// * `filter` - Create a DELETE query with custom WHERE conditions
 /// * `returning` - Add RETURNING clause to the DELETE statement 
+/// * `try_returning` - Add RETURNING with the columns checked against the entity's fields
+/// * `returning_none` - Explicitly add no RETURNING clause (a marker, does not remove an earlier `returning`)
 /// * `finish` - Finish building, get the internal QueryBuilder
 /// 
 /// # 公共方法
@@ -246,9 +306,19 @@ pub type Update<'a, ET> = update_builder::Update<'a, ET, Postgres, DataKind>;
 /// * `with_table` - 使用自定义表名创建 Delete 实例
 /// * `from_query` - 从外部查询中创建 Delete 实例
 /// * `from_query_with_table` - 从外部查询中创建 Delete 实例，可以自定义表名
+/// * `with_cte` - 创建前置 WITH 公用表表达式的 DELETE
+/// * `soft` - 根据 `SoftDelete` 配置创建软删除 `UPDATE table SET column = ?`
 /// * `by_primary_key` - 通过主键创建 DELETE 查询
+/// * `by_keys` - 创建按键列表匹配键列的 DELETE 查询
+/// * `cascade_by_key` - 创建子表与父记录的级联 DELETE 查询
+/// * `archive_by_key` - 创建将记录复制到归档表并软删除的查询
+/// * `dedupe_keep_latest` - 创建删除重复记录的 DELETE，每组保留最新的一条
+/// * `using` - 创建按另一张表的行匹配删除的 DELETE（按数据库使用 USING / JOIN / EXISTS）
+/// * `by_keys_any` - 创建使用 `= ANY(数组)` 匹配键列的 DELETE 查询（仅 PostgreSQL）
 /// * `filter` - 创建带有自定义 WHERE 条件的 DELETE 查询
 /// * `returning` - 添加 RETURNING 子句到删除语句
+/// * `try_returning` - 添加 RETURNING 子句，并校验各列均为实体字段
+/// * `returning_none` - 明确不添加 RETURNING 子句（仅作标记，不会移除之前的 `returning`）
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
 /// 
 /// # Examples
@@ -280,15 +350,39 @@ pub type Delete<'a, ET> = delete_builder::Delete<'a, ET, Postgres, DataKind>;
 /// * `with_table` - Create a Select instance with a custom table name
 /// * `from_query` - Create an Select instance from a query
 /// * `from_query_with_table` - Create an Select instance from a query with a custom table name
+/// * `from_subquery` - Create a Select instance reading from a subquery (derived table)
 /// * `columns` - Create a custom column query statement
+/// * `star` - Select every table column with `*` instead of the entity field list
+/// * `columns_coalesce` - Select the entity fields with COALESCE defaults for nullable columns
+/// * `window` - Select all entity fields plus a window function column
+/// * `count_distinct` - Count distinct combinations of one or more columns
+/// * `distinct` - Select the distinct values of one column
+/// * `distinct_on` - Keep the first row per group with `DISTINCT ON (...)`
+/// * `column_exists_subquery` - Select all entity columns plus an `EXISTS (subquery) AS alias` flag
 /// * `filter` - Create a SELECT query with custom WHERE conditions
 /// * `join` - Create a JOIN query statement
+/// * `index_hint` - Add an index hint after the table name (MySQL only, no-op elsewhere)
+/// * `tablesample` - Add a TABLESAMPLE clause after the table name (PostgreSQL only, no-op elsewhere)
 /// * `group_by` - Create a GROUP BY query statement
 /// * `having` - Create a HAVING clause
+/// * `having_bind` - Add a HAVING condition with one bound value
 /// * `by_primary_key` - Create a SELECT query by primary key
+/// * `by_keys` - Create a SELECT query matching a key column against a list of keys
+/// * `by_keys_any` - Create a SELECT query matching a key column with `= ANY(array)` (PostgreSQL only)
 /// * `order_by` - Create an ORDER BY clause
+/// * `order_by_many` - Create several ORDER BY terms in the given order
+/// * `order_by_field` - Order by an explicit value list (FIELD on MySQL, CASE elsewhere)
+/// * `strict` - Validate table and column names as plain identifiers
+/// * `try_finish` - Build the final query, reporting identifiers rejected in strict mode
 /// * `paginate` - Create a pagination query statement
+/// * `paginate_plus_one` - Create a pagination query fetching one extra row to detect the next page
+/// * `limit_with_ties` - Add `FETCH FIRST n ROWS WITH TIES` (PostgreSQL only, requires ORDER BY)
+/// * `for_update_nowait` - Add `FOR UPDATE NOWAIT` (MySQL 8.0+ / PostgreSQL, no-op on SQLite)
+/// * `lock` - Set the row lock mode (`LockMode`), appended after ORDER BY / LIMIT (no-op on SQLite)
+/// * `random_one` - Fetch one random matching row (ORDER BY RANDOM()/RAND() LIMIT 1)
 /// * `cursor` - Create a cursor pagination query statement
+/// * `cursor_nullable` - Create a cursor pagination query over a nullable sort column
+/// * `cursor_multi` - Create a keyset cursor pagination query over several sort columns
 /// * `finish` - Finish building, get the internal QueryBuilder
 /// 
 /// # 公共方法
@@ -297,15 +391,39 @@ pub type Delete<'a, ET> = delete_builder::Delete<'a, ET, Postgres, DataKind>;
 /// * `with_table` - 创建使用自定义表名的 Select 实例
 /// * `from_query` - 从外部查询中创建 Select 实例
 /// * `from_query_with_table` - 从外部查询中创建 Select 实例，可以自定义表名
+/// * `from_subquery` - 创建以子查询（派生表）为数据源的 Select 实例
 /// * `columns` - 创建自定义列的查询语句
+/// * `star` - 使用 `*` 代替实体字段列表选择表的所有列
+/// * `columns_coalesce` - 以实体字段为查询列，并为可空列提供 COALESCE 默认值
+/// * `window` - 查询实体全部字段及一个窗口函数列
+/// * `count_distinct` - 统计一列或多列组合的去重数量
+/// * `distinct` - 选择某一列的不同取值
+/// * `distinct_on` - 使用 `DISTINCT ON (...)` 每组保留第一行
+/// * `column_exists_subquery` - 查询实体所有列并附加 `EXISTS (subquery) AS alias` 标志列
 /// * `filter` - 创建带有自定义 WHERE 条件的查询语句
 /// * `join` - 创建 JOIN 查询语句
+/// * `index_hint` - 在表名后添加索引提示（仅 MySQL，其它数据库忽略）
+/// * `tablesample` - 在表名后添加 TABLESAMPLE 采样子句（仅 PostgreSQL，其它数据库忽略）
 /// * `group_by` - 创建 GROUP BY 查询语句 
 /// * `having` - 创建 HAVING 子句
+/// * `having_bind` - 添加带一个绑定值的 HAVING 条件
 /// * `by_primary_key` - 创建按主键条件查询语句
+/// * `by_keys` - 创建按键列表匹配键列的查询语句
+/// * `by_keys_any` - 创建使用 `= ANY(数组)` 匹配键列的查询语句（仅 PostgreSQL）
 /// * `order_by` - 创建排序子句
+/// * `order_by_many` - 按给定顺序创建多个排序项
+/// * `order_by_field` - 按给定值列表的顺序排序（MySQL 使用 FIELD，其他使用 CASE）
+/// * `strict` - 校验表名和列名为普通标识符
+/// * `try_finish` - 构建最终查询，并报告严格模式下被拒绝的标识符
 /// * `paginate` - 创建分页查询语句
+/// * `paginate_plus_one` - 创建多取一行以判断是否存在下一页的分页查询语句
+/// * `limit_with_ties` - 添加 `FETCH FIRST n ROWS WITH TIES`（仅 PostgreSQL，需要 ORDER BY）
+/// * `for_update_nowait` - 添加 `FOR UPDATE NOWAIT`（MySQL 8.0+ / PostgreSQL，SQLite 上无效果）
+/// * `lock` - 设置行锁模式（`LockMode`），追加在 ORDER BY / LIMIT 之后（SQLite 上无效果）
+/// * `random_one` - 随机取一条匹配的行（ORDER BY RANDOM()/RAND() LIMIT 1）
 /// * `cursor` - 创建游标分页查询语句
+/// * `cursor_nullable` - 创建基于可空排序列的游标分页查询语句
+/// * `cursor_multi` - 创建基于多列排序键的键集游标分页查询语句
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
 /// 
 /// # Examples
@@ -351,14 +469,60 @@ pub type Select<'a, ET> = select_builder::Select<'a, ET, Postgres, DataKind>;
 /// ```
 pub type Upsert<'a, ET> = upsert_postgres::Upsert<'a, ET, Postgres, DataKind>;
 
+/// Merge builder type alias for PostgreSQL (PostgreSQL 15+)
+/// PostgreSQL 的 Merge 构建器类型别名（PostgreSQL 15+）
+/// 
+/// Used to build standard `MERGE INTO ... USING ... ON ... WHEN [NOT] MATCHED THEN ...` statements.
+/// Unlike `Upsert`, each branch can update, delete or insert, and the match columns need no unique constraint.
+/// 
+/// 用于构建标准的 `MERGE INTO ... USING ... ON ... WHEN [NOT] MATCHED THEN ...` 语句。
+/// 与 `Upsert` 不同，每个分支都可以更新、删除或插入，且匹配列上不需要唯一约束。
+/// 
+/// # Type Parameters
+/// 
+/// * `ET` - The entity type that this merge builder operates on
+/// * `ET` - 此合并构建器操作的实体类型
+/// 
+/// # Public Methods
+/// 
+/// * `using_entities` - Create a MERGE using entities as a VALUES source, matched on the primary key
+/// * `using` - Create a MERGE from a custom source (aliased `src`) and match condition
+/// * `when_matched_update` - Add `WHEN MATCHED THEN UPDATE SET col = src.col`
+/// * `when_matched_delete` - Add `WHEN MATCHED THEN DELETE`
+/// * `when_not_matched_insert` - Add `WHEN NOT MATCHED THEN INSERT (...) VALUES (src...)`
+/// * `custom` - Add custom query parts, e.g. conditional branches
+/// * `finish` - Finish building, get the internal QueryBuilder
+/// 
+/// # 公共方法
+/// 
+/// * `using_entities` - 以实体作为 VALUES 数据源创建 MERGE，按主键匹配
+/// * `using` - 以自定义数据源（别名 `src`）和匹配条件创建 MERGE
+/// * `when_matched_update` - 添加 `WHEN MATCHED THEN UPDATE SET col = src.col`
+/// * `when_matched_delete` - 添加 `WHEN MATCHED THEN DELETE`
+/// * `when_not_matched_insert` - 添加 `WHEN NOT MATCHED THEN INSERT (...) VALUES (src...)`
+/// * `custom` - 添加自定义查询部分，例如带条件的分支
+/// * `finish` - 结束构建，获取内部的 QueryBuilder
+/// 
+/// # Examples
+/// 
+/// ```
+/// use kitx::postgres::builder::Merge;
+/// 
+/// let merge_query = Merge::using_entities(&entities, &PRIMARY_KEY)?
+///     .when_matched_update(&[])?
+///     .when_not_matched_insert(&[])?
+///     .finish();
+/// ```
+pub type Merge<'a, ET> = merge_postgres::Merge<'a, ET, Postgres, DataKind>;
+
 #[cfg(test)]
 mod tests {
 
     use crate::{
-        common::types::{CursorPaginatedResult, PaginatedResult, PrimaryKey, Order}, 
-        postgres::{builder::{Delete, Insert, Select, Subquery, Update, Upsert, QB}, 
+        common::{error::QueryError, filter::{push_between, push_between_expr, push_count_filter, push_eq_cast, push_group_concat, push_ilike, push_not_ilike, push_not_like, push_raw_bind}, types::{CursorPaginatedResult, LockMode, PaginatedResult, PrimaryKey, Order, SampleMethod}}, 
+        postgres::{builder::{Delete, Insert, Merge, Select, Subquery, Update, Upsert, QB}, 
         connection, kind::DataKind, 
-        query::{execute, fetch_all, fetch_one, fetch_scalar}}, 
+        query::{execute, fetch_all, fetch_one, fetch_optional_nowait, fetch_scalar, insert_many_on_conflict_nothing_returning, insert_one_returning, insert_or_ignore, transaction_with_settings, upsert_many_detailed}}, 
         test_utils::{article::Article, init::get_database_url}
     };
     //use super::*;
@@ -516,6 +680,29 @@ mod tests {
         //assert_eq!(result[0].views, 150);
     }
 
+    #[test]
+    fn test_subquery_jsonb_operators() {
+        let tagged = || Subquery::<Article>::table()
+            .columns(|b| {
+                b.push("id");
+            })
+            .filter(|b| {
+                b.push("content::jsonb ? 'draft' AND content::jsonb ?| ARRAY['a'] AND tenant_id = ")
+                    .push_bind(DataKind::from(1485));
+            });
+
+        let mut qb = QB::new("SELECT id FROM article WHERE ");
+        tagged().append_in_to(&mut qb, "id").unwrap();
+        assert_eq!(
+            qb.sql().trim_end(),
+            "SELECT id FROM article WHERE id IN (SELECT id FROM article WHERE content::jsonb ? 'draft' AND content::jsonb ?| ARRAY['a'] AND tenant_id = $1)"
+        );
+
+        let mut qb = QB::new("SELECT EXISTS");
+        tagged().try_append_to(&mut qb).unwrap();
+        assert!(qb.sql().contains("?| ARRAY['a'] AND tenant_id = $1"));
+    }
+
     #[tokio::test]
     async fn test_find_list_paginated() {
         let filter_build_fn = |qb: &mut QB| {
@@ -609,4 +796,825 @@ mod tests {
         dbg!(&result);
     }
 
+    #[tokio::test]
+    async fn test_delete_by_keys_any() {
+        let ids: Vec<i32> = (10_000..11_000).collect();
+
+        let select_qb = Select::<Article>::table()
+            .by_keys_any("id", DataKind::Int4Array(ids.clone()))
+            .finish();
+        assert_eq!(
+            select_qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE id = ANY($1)"
+        );
+
+        let qb = Delete::<Article>::table()
+            .by_keys_any("id", DataKind::Int4Array(ids))
+            .finish();
+        assert_eq!(qb.sql(), "DELETE FROM article WHERE id = ANY($1)");
+
+        init_pool().await;
+        let result = execute(qb).await.unwrap();
+        println!("Deleted {} rows.", result.rows_affected());
+        let remaining = fetch_all::<Article>(select_qb).await.unwrap();
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn test_by_keys_binds_array() {
+        let ids: Vec<i32> = (10_000..11_000).collect();
+        let qb = Select::<Article>::table()
+            .by_keys("id", ids.clone())
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE id = ANY($1)"
+        );
+
+        let qb = Delete::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1438));
+            })
+            .by_keys("id", ids)
+            .finish();
+        assert_eq!(qb.sql(), "DELETE FROM article WHERE tenant_id = $1 AND id = ANY($2)");
+    }
+
+    #[test]
+    fn test_raw_fragment_numbering() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1).push(" AND ");
+                push_raw_bind(b, "title ILIKE ? OR views > ?", vec![DataKind::from("%rust%"), DataKind::from(10)]).unwrap();
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = $1 AND title ILIKE $2 OR views > $3"
+        );
+    }
+
+    #[test]
+    fn test_count_distinct() {
+        let qb = Select::<Article>::table()
+            .count_distinct(&["tenant_id", "title"]).unwrap()
+            .finish();
+        assert_eq!(qb.sql(), "SELECT COUNT(DISTINCT (tenant_id, title)) FROM article");
+
+        let qb = Select::<Article>::table()
+            .count_distinct(&["tenant_id"]).unwrap()
+            .finish();
+        assert_eq!(qb.sql(), "SELECT COUNT(DISTINCT tenant_id) FROM article");
+    }
+
+    #[test]
+    fn test_tablesample() {
+        let qb = Select::<Article>::with_table("article a")
+            .tablesample(SampleMethod::System, 10.0)
+            .filter(|b| {
+                b.push("a.views > ").push_bind(100);
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article a TABLESAMPLE SYSTEM (10) WHERE a.views > $1"
+        );
+    }
+
+    #[test]
+    fn test_limit_with_ties() {
+        let qb = Select::<Article>::table()
+            .order_by("views", Order::Desc)
+            .limit_with_ties(3)
+            .unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article ORDER BY views DESC FETCH FIRST 3 ROWS WITH TIES"
+        );
+
+        let result = Select::<Article>::table().limit_with_ties(3);
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_insert_with_explicit_id() {
+        let mut entity = Article::new(1457, "explicit id", None);
+        entity.id = 14570;
+
+        let qb = Insert::one_with_explicit_id(&entity).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "INSERT INTO article (id, tenant_id, title, content, views, deleted, created_at) OVERRIDING SYSTEM VALUE VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        );
+
+        init_pool().await;
+        let delete_qb = Delete::<Article>::table()
+            .filter(|b| {
+                b.push("id = ").push_bind(14570);
+            })
+            .finish();
+        execute(delete_qb).await.unwrap();
+        let result = execute(qb).await.unwrap();
+        assert_eq!(result.rows_affected(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_interval_filter() {
+        let max_age = chrono::Duration::hours(2);
+        assert_eq!(DataKind::from(max_age), DataKind::Interval(max_age));
+
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("NOW() - created_at < ").push_bind(DataKind::from(max_age));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE NOW() - created_at < $1"
+        );
+
+        init_pool().await;
+        let tenant = |b: &mut QB| {
+            b.push("tenant_id = ").push_bind(DataKind::from(1459));
+        };
+        execute(Delete::<Article>::table().filter(tenant).finish()).await.unwrap();
+        for (title, age) in [("just inside", "1 hour 59 minutes"), ("just outside", "2 hours 1 minute")] {
+            execute(Insert::one(&Article::new(1459, title, None), &ARTICLE_KEY).unwrap()).await.unwrap();
+            let mut qb = QB::new(format!("UPDATE article SET created_at = LOCALTIMESTAMP - INTERVAL '{}' WHERE ", age));
+            tenant(&mut qb);
+            qb.push(" AND title = ").push_bind(DataKind::from(title));
+            execute(qb).await.unwrap();
+        }
+
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                tenant(b);
+                b.push(" AND NOW() - created_at < ").push_bind(DataKind::from(max_age));
+            })
+            .finish();
+        let list = fetch_all::<Article>(qb).await.unwrap();
+        let titles: Vec<_> = list.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, vec!["just inside"]);
+    }
+
+    #[tokio::test]
+    async fn test_for_update_nowait() {
+        let locking_select = || {
+            Select::<Article>::table()
+                .filter(|b| {
+                    b.push("tenant_id = ").push_bind(1464);
+                })
+                .for_update_nowait()
+        };
+        assert_eq!(
+            locking_select().sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = $1 FOR UPDATE NOWAIT"
+        );
+
+        init_pool().await;
+        execute(Delete::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1464));
+            })
+            .finish()).await.unwrap();
+        execute(Insert::one(&Article::new(1464, "locked", None), &ARTICLE_KEY).unwrap()).await.unwrap();
+
+        let pool = connection::get_db_pool().unwrap();
+        let mut tx1 = pool.begin().await.unwrap();
+        let mut tx2 = pool.begin().await.unwrap();
+        let locked = fetch_optional_nowait::<Article>(&mut tx1, locking_select()).await.unwrap();
+        assert!(locked.is_some());
+
+        let err = fetch_optional_nowait::<Article>(&mut tx2, locking_select()).await.unwrap_err();
+        assert!(QueryError::LockNotAvailable.matches(&err));
+
+        tx2.rollback().await.unwrap();
+        tx1.rollback().await.unwrap();
+    }
+
+    #[test]
+    fn test_count_filter() {
+        let qb = Select::<Article>::table()
+            .columns(|b| {
+                push_count_filter(b, "views > ?", vec![DataKind::Int4(10)], "popular").unwrap();
+            })
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1465);
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT COUNT(*) FILTER (WHERE views > $1) AS popular FROM article WHERE tenant_id = $2"
+        );
+    }
+
+    #[test]
+    fn test_in_large() {
+        use crate::common::filter::push_in_large;
+
+        let ids: Vec<i64> = (1..=2000).collect();
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                push_in_large::<_, _, DataKind>(b, "id", ids);
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE id = ANY($1)"
+        );
+
+        let flags: Vec<bool> = vec![true; 2000];
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                push_in_large::<_, _, DataKind>(b, "deleted", flags);
+            })
+            .finish();
+        assert_eq!(qb.sql().matches("deleted IN (").count(), 4);
+        assert!(qb.sql().ends_with("$2000))"));
+    }
+
+    #[test]
+    fn test_upsert_generated_column() {
+        use field_access::FieldAccess;
+        use crate::common::helper::register_generated_columns;
+
+        #[derive(Debug, Default, FieldAccess)]
+        struct InvoiceLine {
+            id: i32,
+            net: i32,
+            tax: i32,
+            total: i32,
+        }
+
+        register_generated_columns("invoice_line", &["total"]);
+        let key = PrimaryKey::Single("id", true);
+        let line = InvoiceLine { id: 7, net: 100, tax: 20, total: 120 };
+
+        let qb = Upsert::one(&line, &key).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "INSERT INTO invoice_line (id, net, tax) VALUES ($1, $2, $3) ON CONFLICT (id) DO UPDATE SET id = EXCLUDED.id, net = EXCLUDED.net, tax = EXCLUDED.tax"
+        );
+
+        let qb = Update::one(&line, &key, false).unwrap();
+        assert_eq!(qb.sql(), "UPDATE invoice_line SET net = $1, tax = $2 WHERE id = $3");
+    }
+
+    #[test]
+    fn test_geometry_wkt() {
+        use field_access::FieldAccess;
+        use crate::common::filter::push_dwithin;
+        use crate::postgres::kind::GeometryWkt;
+
+        #[derive(Debug, Default, FieldAccess)]
+        struct Place {
+            id: i32,
+            name: String,
+            location: GeometryWkt,
+        }
+
+        let place = Place {
+            id: 0,
+            name: "office".to_string(),
+            location: GeometryWkt { wkt: "POINT(116.39 39.91)".to_string(), srid: 4326 },
+        };
+        let key = PrimaryKey::Single("id", true);
+        let qb = Insert::one(&place, &key).unwrap();
+        assert_eq!(qb.sql(), "INSERT INTO place (name, location) VALUES ($1, ST_GeomFromText($2, 4326))");
+
+        let qb = Update::<Place>::set_where(
+            "location",
+            DataKind::geometry_wkt("POINT(0 0)", 3857),
+            |b| {
+                b.push("name = ").push_bind("office");
+            },
+        ).unwrap();
+        assert_eq!(qb.sql(), "UPDATE place SET location = ST_GeomFromText($1, 3857) WHERE name = $2");
+
+        let qb = Select::<Place>::table()
+            .columns(|b| {
+                b.push("id, name, ST_AsText(location) AS location");
+            })
+            .filter(|b| {
+                push_dwithin::<_, DataKind>(b, "location", "POINT(116.40 39.90)", 4326, 1500.0);
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, name, ST_AsText(location) AS location FROM place WHERE ST_DWithin(location::geography, ST_GeomFromText($1, 4326)::geography, $2)"
+        );
+    }
+
+    #[test]
+    fn test_json_array_append() {
+        let qb = Update::<Article>::table()
+            .json_array_append("tags", DataKind::from(r#"["b"]"#))
+            .filter(|b| {
+                b.push("id = ").push_bind(DataKind::from(1));
+            })
+            .finish();
+        assert_eq!(qb.sql(), "UPDATE article SET tags = tags || $1::jsonb WHERE id = $2");
+    }
+
+    #[test]
+    fn test_order_by_field() {
+        let qb = Select::<Article>::table()
+            .order_by_field("views", vec![30, 10, 20])
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article ORDER BY CASE views WHEN $1 THEN 1 WHEN $2 THEN 2 WHEN $3 THEN 3 ELSE 0 END"
+        );
+    }
+
+    #[test]
+    fn test_columns_coalesce() {
+        let qb = Select::<Article>::table()
+            .columns_coalesce(&[("content", "'n/a'", "summary"), ("content", "title", "content")]).unwrap()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1494));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, views, deleted, created_at, COALESCE(content, $1) AS summary, COALESCE(content, title) AS content FROM article WHERE tenant_id = $2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upsert_many_detailed() {
+        init_pool().await;
+        execute(Delete::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1495));
+            })
+            .finish()).await.unwrap();
+
+        let mut existing = Article::new(1495, "existing", None);
+        let mut qb = Insert::one(&existing, &ARTICLE_KEY).unwrap();
+        qb.push(" RETURNING *");
+        existing.id = fetch_one::<Article>(qb).await.unwrap().id;
+        existing.title = "existing, renamed".to_string();
+        let fresh = [Article::new(1495, "new one", None), Article::new(1495, "new two", None)];
+
+        let entities = [&existing, &fresh[0], &fresh[1]];
+        let (inserted, updated) = upsert_many_detailed(entities, &ARTICLE_KEY).await.unwrap();
+        assert_eq!((inserted, updated), (2, 1));
+
+        let qb = Select::<Article>::table()
+            .columns(|b| {
+                b.push("COUNT(*)");
+            })
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1495));
+            })
+            .finish();
+        assert_eq!(fetch_scalar(qb).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_advisory_lock() {
+        init_pool().await;
+        let pool = connection::get_db_pool().unwrap();
+        let mut holder = pool.acquire().await.unwrap();
+        let mut other = pool.acquire().await.unwrap();
+        let key = 1496;
+
+        connection::advisory_lock(&mut holder, key).await.unwrap();
+        assert!(!connection::try_advisory_lock(&mut other, key).await.unwrap());
+        assert!(!connection::advisory_unlock(&mut other, key).await.unwrap());
+
+        assert!(connection::advisory_unlock(&mut holder, key).await.unwrap());
+        assert!(connection::try_advisory_lock(&mut other, key).await.unwrap());
+        assert!(connection::advisory_unlock(&mut other, key).await.unwrap());
+    }
+
+    #[test]
+    fn test_merge() {
+        use crate::common::error::QueryError;
+
+        let mut existing = Article::new(1497, "existing", None);
+        existing.id = 7;
+        let fresh = Article::new(1497, "fresh", None);
+
+        let qb = Merge::using_entities([&existing, &fresh], &ARTICLE_KEY).unwrap()
+            .when_matched_update(&["title", "content"]).unwrap()
+            .when_not_matched_insert(&[]).unwrap()
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "MERGE INTO article USING (VALUES ($1, $2, $3, $4, $5, $6, $7), ($8, $9, $10, $11, $12, $13, $14)) \
+             AS src (id, tenant_id, title, content, views, deleted, created_at) ON article.id = src.id \
+             WHEN MATCHED THEN UPDATE SET title = src.title, content = src.content \
+             WHEN NOT MATCHED THEN INSERT (tenant_id, title, content, views, deleted, created_at) \
+             VALUES (src.tenant_id, src.title, src.content, src.views, src.deleted, src.created_at)"
+        );
+
+        let qb = Merge::<Article>::using("staging_article AS src", "article.id = src.id")
+            .custom(|b| {
+                b.push(" WHEN MATCHED AND src.deleted THEN DELETE");
+            })
+            .when_matched_update(&["views"]).unwrap()
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "MERGE INTO article USING staging_article AS src ON article.id = src.id \
+             WHEN MATCHED AND src.deleted THEN DELETE WHEN MATCHED THEN UPDATE SET views = src.views"
+        );
+
+        let err = Merge::<Article>::using("staging_article AS src", "article.id = src.id")
+            .when_not_matched_insert(&[])
+            .err().unwrap();
+        assert!(QueryError::ColumnsListEmpty.matches(&err));
+    }
+
+    #[test]
+    fn test_ilike() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1)).push(" AND ");
+                push_ilike(b, "title", DataKind::from("%rust%"));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = $1 AND title ILIKE $2"
+        );
+    }
+
+    #[test]
+    fn test_delete_using() {
+        let qb = Delete::<Article>::using("article_tag", |b| {
+                b.push("article.id = article_tag.article_id AND article_tag.tag = ").push_bind(DataKind::from("spam"));
+            })
+            .filter(|b| {
+                b.push("article.tenant_id = ").push_bind(DataKind::from(1));
+            })
+            .returning(["article.id"])
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "DELETE FROM article USING article_tag WHERE article.id = article_tag.article_id AND article_tag.tag = $1 AND article.tenant_id = $2 RETURNING article.id"
+        );
+    }
+
+    #[test]
+    fn test_not_like() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                push_not_like(b, "title", DataKind::from("%draft%"));
+                b.push(" AND ");
+                push_not_ilike(b, "content", DataKind::from("%spam%"));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE title NOT LIKE $1 AND content NOT ILIKE $2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transaction_with_settings() {
+        init_pool().await;
+        let name = transaction_with_settings(&[("application_name", "kitx-billing")], async |conn: &mut sqlx::PgConnection| {
+            sqlx::query_scalar::<_, String>("SELECT current_setting('application_name')")
+                .fetch_one(&mut *conn)
+                .await
+        }).await.unwrap();
+        assert_eq!(name, "kitx-billing");
+    }
+
+    #[test]
+    fn test_lock_mode() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1));
+            })
+            .lock(LockMode::UpdateSkipLocked)
+            .order_by("id", Order::Asc)
+            .paginate(1, 10).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = $1 ORDER BY id ASC LIMIT $2 OFFSET $3 FOR UPDATE SKIP LOCKED"
+        );
+
+        let qb = Select::<Article>::table()
+            .order_by("id", Order::Desc)
+            .lock(LockMode::Share)
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article ORDER BY id DESC FOR SHARE"
+        );
+    }
+
+    #[test]
+    fn test_cursor_multi() {
+        let qb = Select::<Article>::table()
+            .cursor_multi(
+                &[("created_at", Order::Asc), ("id", Order::Asc)],
+                Some(vec![DataKind::from("2024-01-01 00:00:00"), DataKind::from(7)]),
+                10,
+            ).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE (created_at, id) > ($1, $2) ORDER BY created_at ASC, id ASC LIMIT $3"
+        );
+
+        let qb = Select::<Article>::table()
+            .cursor_multi(
+                &[("views", Order::Desc), ("id", Order::Asc)],
+                Some(vec![DataKind::from(5), DataKind::from(7)]),
+                10,
+            ).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE ((views < $1) OR (views = $2 AND id > $3)) ORDER BY views DESC, id ASC LIMIT $4"
+        );
+    }
+
+    #[test]
+    fn test_distinct_on() {
+        let qb = Select::<Article>::table()
+            .distinct_on(&["tenant_id"]).unwrap()
+            .filter(|b| {
+                b.push("deleted = ").push_bind(DataKind::from(false));
+            })
+            .order_by("tenant_id", Order::Asc)
+            .order_by("created_at", Order::Desc)
+            .try_finish().unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT DISTINCT ON (tenant_id) id, tenant_id, title, content, views, deleted, created_at FROM article WHERE deleted = $1 ORDER BY tenant_id ASC, created_at DESC"
+        );
+
+        let err = Select::<Article>::table()
+            .distinct_on(&["tenant_id"]).unwrap()
+            .order_by("created_at", Order::Desc)
+            .order_by("tenant_id", Order::Asc)
+            .paginate(1, 10)
+            .err().unwrap();
+        assert!(QueryError::DistinctOnOrder("created_at".to_string()).matches(&err));
+
+        assert!(Select::<Article>::table().distinct_on(&[]).is_err());
+
+        let err = Select::<Article>::table()
+            .filter(|b| {
+                b.push("deleted = ").push_bind(DataKind::from(false));
+            })
+            .distinct_on(&["tenant_id"])
+            .err().unwrap();
+        assert!(QueryError::ClauseAfterFrom("distinct_on".to_string()).matches(&err));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "DISTINCT ON columns must lead the ORDER BY")]
+    fn test_distinct_on_finish_asserts_order() {
+        let _ = Select::<Article>::table()
+            .distinct_on(&["tenant_id"]).unwrap()
+            .order_by("created_at", Order::Desc)
+            .finish();
+    }
+
+    #[tokio::test]
+    async fn test_insert_many_on_conflict_nothing_returning() {
+        init_pool().await;
+        execute(Delete::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1505));
+            })
+            .finish()).await.unwrap();
+
+        let entities: Vec<Article> = (15050..15053)
+            .map(|id| {
+                let mut entity = Article::new(1505, &format!("article {}", id), None);
+                entity.id = id;
+                entity
+            })
+            .collect();
+        execute(Insert::one_with_explicit_id(&entities[1]).unwrap()).await.unwrap();
+
+        let skipped = insert_many_on_conflict_nothing_returning(&entities, &["id"]).await.unwrap();
+        assert_eq!(skipped, vec![vec![DataKind::from(15051)]]);
+
+        let skipped = insert_many_on_conflict_nothing_returning(&entities, &["id"]).await.unwrap();
+        assert_eq!(skipped.len(), 3);
+    }
+
+    #[test]
+    fn test_eq_cast() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                push_eq_cast(b, "external_id", DataKind::from("8f14e45f-ceea-467f-a0e5-4b8b3f3b2c1d"), "uuid");
+                b.push(" AND tenant_id = ").push_bind(DataKind::from(1));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE external_id = $1::uuid AND tenant_id = $2"
+        );
+
+        let qb = Update::<Article>::table()
+            .set_cast("meta", DataKind::from(r#"{"pinned":true}"#), "jsonb")
+            .filter(|b| {
+                b.push("id = ").push_bind(DataKind::from(1));
+            })
+            .finish();
+        assert_eq!(qb.sql(), "UPDATE article SET meta = $1::jsonb WHERE id = $2");
+
+        let entities = [Article::new(1506, "one", None), Article::new(1506, "two", None)];
+        let qb = Insert::many_with_casts(&entities, &ARTICLE_KEY, &[("title", "text")]).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "INSERT INTO article (tenant_id, title, content, views, deleted, created_at) VALUES ($1, $2::text, $3, $4, $5, $6), ($7, $8::text, $9, $10, $11, $12)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_insert_one_returning() {
+        init_pool().await;
+        execute(Delete::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1507));
+            })
+            .finish()).await.unwrap();
+
+        let entity = Article::new(1507, "returning", None);
+        let inserted = insert_one_returning(&entity, &ARTICLE_KEY).await.unwrap();
+        assert!(inserted.id > 0);
+        assert_eq!(inserted.title, "returning");
+
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("id = ").push_bind(DataKind::from(inserted.id));
+            })
+            .finish();
+        let stored: Article = fetch_one(qb).await.unwrap();
+        assert_eq!(stored.tenant_id, 1507);
+    }
+
+    #[test]
+    fn test_update_with_cte() {
+        let stale = Subquery::<Article>::table()
+            .columns(|b| {
+                b.push("id");
+            })
+            .filter(|b| {
+                b.push("views < ").push_bind(DataKind::from(5));
+            });
+        let qb = Update::<Article>::with_cte("stale", stale)
+            .custom(|b| {
+                b.push("deleted = ").push_bind(DataKind::from(true));
+            })
+            .filter(|b| {
+                b.push("id IN (SELECT id FROM stale)");
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "WITH stale AS (SELECT id FROM article WHERE views < $1) UPDATE article SET deleted = $2 WHERE id IN (SELECT id FROM stale)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_insert_or_ignore() {
+        let mut entity = Article::new(1509, "ignored duplicate", None);
+        entity.id = 15090;
+        let qb = Insert::many_or_ignore([&entity], &PrimaryKey::Single("id", false), &["id"]).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "INSERT INTO article (id, tenant_id, title, content, views, deleted, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT (id) DO NOTHING"
+        );
+        let qb = Insert::<Article>::table()
+            .columns(["id", "title"])
+            .custom(|b| {
+                b.push(" VALUES (").push_bind(DataKind::from(15090)).push(", ").push_bind(DataKind::from("raw")).push(")");
+            })
+            .on_conflict_do_nothing(&[])
+            .finish();
+        assert_eq!(qb.sql(), "INSERT INTO article (id, title) VALUES ($1, $2) ON CONFLICT DO NOTHING");
+
+        init_pool().await;
+        execute(Delete::<Article>::table()
+            .filter(|b| {
+                b.push("id = ").push_bind(DataKind::from(15090));
+            })
+            .finish()).await.unwrap();
+        let key = PrimaryKey::Single("id", false);
+        let result = insert_or_ignore([&entity], &key, &["id"]).await.unwrap();
+        assert_eq!(result.rows_affected(), 1);
+        let result = insert_or_ignore([&entity], &key, &[]).await.unwrap();
+        assert_eq!(result.rows_affected(), 0);
+    }
+
+    #[test]
+    fn test_between_expr() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                push_between(b, "views", DataKind::from(10), DataKind::from(20));
+                b.push(" AND tenant_id = ").push_bind(DataKind::from(1));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE views BETWEEN $1 AND $2 AND tenant_id = $3"
+        );
+
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                push_between_expr(b, "views", "tenant_id", "tenant_id * 10");
+                b.push(" AND tenant_id = ").push_bind(DataKind::from(1));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE views BETWEEN tenant_id AND tenant_id * 10 AND tenant_id = $1"
+        );
+    }
+
+    #[test]
+    fn test_data_kind_partial_ord() {
+        use chrono::{Duration, Utc};
+
+        assert!(DataKind::Int8(1) < DataKind::Int8(2));
+        assert!(DataKind::Int8(5) >= DataKind::Int8(5));
+
+        let now = Utc::now();
+        let earlier = DataKind::Timestamptz(now - Duration::seconds(1));
+        let later = DataKind::Timestamptz(now);
+        assert!(earlier < later);
+        assert_eq!(later.partial_cmp(&later.clone()), Some(std::cmp::Ordering::Equal));
+
+        assert_eq!(DataKind::Int8(1).partial_cmp(&DataKind::Text("1".to_string())), None);
+        assert_eq!(DataKind::Null.partial_cmp(&DataKind::Null), Some(std::cmp::Ordering::Equal));
+    }
+
+    #[test]
+    fn test_string_agg() {
+        let qb = Select::<Article>::table()
+            .columns(|qb| {
+                qb.push("tenant_id, ");
+                push_group_concat::<_, DataKind>(qb, "title", ", ", "titles");
+            })
+            .group_by("tenant_id").unwrap()
+            .finish();
+        assert_eq!(qb.sql(), "SELECT tenant_id, STRING_AGG(title, $1) AS titles FROM article GROUP BY tenant_id");
+    }
+
+    #[test]
+    fn test_on_conflict_on_constraint() {
+        let qb = Insert::<Article>::with_table("account")
+            .columns(["email", "name"])
+            .custom(|b| {
+                b.push(" VALUES (").push_bind(DataKind::from("a@example.com")).push(", ").push_bind(DataKind::from("Ann")).push(")");
+            })
+            .on_conflict_on_constraint("uq_email", &["name"])
+            .returning(["id"])
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "INSERT INTO account (email, name) VALUES ($1, $2) ON CONFLICT ON CONSTRAINT uq_email DO UPDATE SET name = EXCLUDED.name RETURNING id"
+        );
+
+        let qb = Insert::<Article>::table()
+            .columns(["id", "title"])
+            .custom(|b| {
+                b.push(" VALUES (").push_bind(DataKind::from(1514)).push(", ").push_bind(DataKind::from("raw")).push(")");
+            })
+            .on_conflict_on_constraint("article_pkey", &[])
+            .finish();
+        assert_eq!(qb.sql(), "INSERT INTO article (id, title) VALUES ($1, $2) ON CONFLICT ON CONSTRAINT article_pkey DO NOTHING");
+    }
+
+    #[test]
+    fn test_update_with_join() {
+        use crate::test_utils::article::ArticleTag;
+
+        let qb = Update::<ArticleTag>::with_join("article", "article.id = article_tag.article_id")
+            .custom(|b| {
+                b.push("tenant_id = article.tenant_id");
+            })
+            .filter(|b| {
+                b.push("article.views > ").push_bind(DataKind::from(10));
+            })
+            .returning(["article_id", "tenant_id"])
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "UPDATE article_tag SET tenant_id = article.tenant_id FROM article WHERE article.id = article_tag.article_id AND (article.views > $1) RETURNING article_id, tenant_id"
+        );
+    }
+
+    #[test]
+    fn test_strict_with_tablesample() {
+        let qb = Select::<Article>::table()
+            .tablesample(SampleMethod::Bernoulli, 5.0)
+            .strict()
+            .order_by("id", Order::Asc)
+            .try_finish()
+            .unwrap();
+        assert!(qb.sql().contains("FROM article TABLESAMPLE BERNOULLI (5) ORDER BY id ASC"));
+    }
+
 }
\ No newline at end of file
@@ -12,6 +12,7 @@
 //! 包括数值、字符串、二进制、日期/时间、网络、UUID 和 JSON 类型。
 
 use std::any::Any;
+use std::cmp::Ordering;
 use std::error::Error;
 use std::sync::Arc;
 use std::net::IpAddr;
@@ -25,7 +26,9 @@ use sqlx::types::{Decimal, ipnetwork::IpNetwork};
 use serde_json::Value;
 use uuid::Uuid;
 
-use crate::common::conversion::{unwrap_option, ValueConvert};
+use crate::common::conversion::{unwrap_option, DbEnumInt, ValueConvert};
+#[cfg(feature = "json-fallback")]
+use crate::common::conversion::json_fallback;
 
 /// Enum representing PostgreSQL data types, supporting the main PostgreSQL type system
 #[derive(Default, Debug, Clone, PartialEq)]
@@ -66,6 +69,33 @@ pub enum DataKind {
 
     // JSON types
     Json(Arc<Value>),    // JSON, JSONB
+
+    // Array types, bound as a single parameter (e.g. `id = ANY($1)`)
+    Int4Array(Vec<i32>),    // INT4[]
+    Int8Array(Vec<i64>),    // INT8[]
+    TextArray(Vec<String>), // TEXT[]
+    UuidArray(Vec<Uuid>),   // UUID[]
+
+    // PostGIS geometry, bound as WKT text inside `ST_GeomFromText(?, srid)`
+    Geometry(Arc<GeometryWkt>), // GEOMETRY
+}
+
+/// PostGIS geometry given as WKT text and an SRID
+/// 
+/// Use it as an entity field type (or build a value with `DataKind::geometry_wkt`). When
+/// written by INSERT, UPDATE or UPSERT builders it renders as `ST_GeomFromText(?, srid)`
+/// with the WKT bound as text. Reading geometry columns back is not supported; select
+/// `ST_AsText(column)` instead.
+/// 
+/// 以 WKT 文本和 SRID 表示的 PostGIS 几何值
+/// 
+/// 可作为实体字段类型使用（或通过 `DataKind::geometry_wkt` 构造值）。由 INSERT、UPDATE 或
+/// UPSERT 构建器写入时生成 `ST_GeomFromText(?, srid)`，WKT 以文本绑定。不支持直接读取几何列，
+/// 请改为选择 `ST_AsText(column)`。
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct GeometryWkt {
+    pub wkt: String,
+    pub srid: i32,
 }
 
 impl Encode<'_, Postgres> for DataKind {
@@ -91,6 +121,11 @@ impl Encode<'_, Postgres> for DataKind {
             DataKind::MacAddr(mac) => <[u8; 6] as Encode<'_, Postgres>>::encode(mac.bytes(), buf),
             DataKind::Uuid(uuid) => <Uuid as Encode<'_, Postgres>>::encode(*uuid, buf),
             DataKind::Json(j) => <&Value as Encode<'_, Postgres>>::encode(j, buf),            
+            DataKind::Int4Array(a) => <&[i32] as Encode<'_, Postgres>>::encode(a, buf),
+            DataKind::Int8Array(a) => <&[i64] as Encode<'_, Postgres>>::encode(a, buf),
+            DataKind::TextArray(a) => <&[String] as Encode<'_, Postgres>>::encode(a, buf),
+            DataKind::UuidArray(a) => <&[Uuid] as Encode<'_, Postgres>>::encode(a, buf),
+            DataKind::Geometry(g) => <&str as Encode<'_, Postgres>>::encode(g.wkt.as_str(), buf),
         }
     }
 
@@ -112,11 +147,19 @@ impl Type<Postgres> for DataKind {
             | "DATE" | "TIME" | "TIMESTAMP" | "TIMESTAMPTZ" | "INTERVAL"
             | "INET" | "CIDR" | "MACADDR" | "UUID"
             | "JSON" | "JSONB" | "NULL"
+            | "INT4[]" | "INT8[]" | "TEXT[]" | "UUID[]"
         )
     }
 }
 
 impl DataKind {
+    /// Create a PostGIS geometry value from WKT text and an SRID
+    /// 
+    /// 由 WKT 文本和 SRID 创建 PostGIS 几何值
+    pub fn geometry_wkt(wkt: impl Into<String>, srid: i32) -> Self {
+        DataKind::Geometry(Arc::new(GeometryWkt { wkt: wkt.into(), srid }))
+    }
+
     pub fn get_type_info(&self) -> PgTypeInfo {
         match self {
             DataKind::Bool(_) => <bool as Type<Postgres>>::type_info(),
@@ -138,6 +181,11 @@ impl DataKind {
             DataKind::MacAddr(_) => <[u8; 6] as Type<Postgres>>::type_info(),
             DataKind::Uuid(_) => <Uuid as Type<Postgres>>::type_info(),
             DataKind::Json(_) => <Value as Type<Postgres>>::type_info(),
+            DataKind::Int4Array(_) => <Vec<i32> as Type<Postgres>>::type_info(),
+            DataKind::Int8Array(_) => <Vec<i64> as Type<Postgres>>::type_info(),
+            DataKind::TextArray(_) => <Vec<String> as Type<Postgres>>::type_info(),
+            DataKind::UuidArray(_) => <Vec<Uuid> as Type<Postgres>>::type_info(),
+            DataKind::Geometry(_) => <str as Type<Postgres>>::type_info(),
             DataKind::Null => <str as Type<Postgres>>::type_info(),
         }
     }
@@ -156,6 +204,10 @@ impl ValueConvert for DataKind {
                 $(if let Some(v) = unwrap_option::<$type>(value) {
                     return $variant(v);
                 })*
+                #[cfg(feature = "json-fallback")]
+                if let Some(json) = json_fallback(value) {
+                    return DataKind::Json(Arc::new(json));
+                }
                 return DataKind::Null;
             };
         }
@@ -182,7 +234,12 @@ impl ValueConvert for DataKind {
             Value => |v: &Value| DataKind::Json(Arc::new(v.clone())),
             IpAddr => |v: &IpAddr| DataKind::Inet(*v),
             IpNetwork => |v: &IpNetwork| DataKind::Cidr(*v),
-            MacAddress => |v: &MacAddress| DataKind::MacAddr(*v)
+            MacAddress => |v: &MacAddress| DataKind::MacAddr(*v),
+            Vec<i32> => |v: &Vec<i32>| DataKind::Int4Array(v.clone()),
+            Vec<i64> => |v: &Vec<i64>| DataKind::Int8Array(v.clone()),
+            Vec<String> => |v: &Vec<String>| DataKind::TextArray(v.clone()),
+            Vec<Uuid> => |v: &Vec<Uuid>| DataKind::UuidArray(v.clone()),
+            GeometryWkt => |v: &GeometryWkt| DataKind::Geometry(Arc::new(v.clone()))
         );
     }
 
@@ -196,6 +253,42 @@ impl ValueConvert for DataKind {
             _ => false,
         }
     }
+
+    fn placeholder_wrap(value: &Self) -> Option<(String, String)> {
+        match value {
+            DataKind::Geometry(g) => Some(("ST_GeomFromText(".to_string(), format!(", {})", g.srid))),
+            _ => None,
+        }
+    }
+}
+
+/// Values are only comparable within the same variant, e.g. two cursor values of the same column;
+/// different variants (and unordered types such as JSON) yield `None`.
+///
+/// 仅同一变体内的值可比较，例如同一列的两个游标值；不同变体（以及 JSON 等无序类型）返回 `None`。
+impl PartialOrd for DataKind {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (DataKind::Bool(a), DataKind::Bool(b)) => a.partial_cmp(b),
+            (DataKind::Int2(a), DataKind::Int2(b)) => a.partial_cmp(b),
+            (DataKind::Int4(a), DataKind::Int4(b)) => a.partial_cmp(b),
+            (DataKind::Int8(a), DataKind::Int8(b)) => a.partial_cmp(b),
+            (DataKind::Float4(a), DataKind::Float4(b)) => a.partial_cmp(b),
+            (DataKind::Float8(a), DataKind::Float8(b)) => a.partial_cmp(b),
+            (DataKind::Numeric(a), DataKind::Numeric(b)) => a.partial_cmp(b),
+            (DataKind::Text(a), DataKind::Text(b)) => a.partial_cmp(b),
+            (DataKind::Bytea(a), DataKind::Bytea(b)) => a.partial_cmp(b),
+            (DataKind::Date(a), DataKind::Date(b)) => a.partial_cmp(b),
+            (DataKind::Time(a), DataKind::Time(b)) => a.partial_cmp(b),
+            (DataKind::Timestamp(a), DataKind::Timestamp(b)) => a.partial_cmp(b),
+            (DataKind::Timestamptz(a), DataKind::Timestamptz(b)) => a.partial_cmp(b),
+            (DataKind::Interval(a), DataKind::Interval(b)) => a.partial_cmp(b),
+            (DataKind::Inet(a), DataKind::Inet(b)) => a.partial_cmp(b),
+            (DataKind::Uuid(a), DataKind::Uuid(b)) => a.partial_cmp(b),
+            _ if self == other => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
 }
 
 macro_rules! impl_from {
@@ -234,3 +327,15 @@ impl_from!(Decimal, DataKind::Numeric);
 impl_from!(IpAddr, DataKind::Inet);
 impl_from!(IpNetwork, DataKind::Cidr);
 impl_from!(MacAddress, DataKind::MacAddr);
+impl_from!(Vec<i32>, DataKind::Int4Array);
+impl_from!(Vec<i64>, DataKind::Int8Array);
+impl_from!(Vec<String>, DataKind::TextArray);
+impl_from!(Vec<Uuid>, DataKind::UuidArray);
+impl_from!(GeometryWkt, |value: GeometryWkt| DataKind::Geometry(Arc::new(value)));
+
+// Integer-backed enums are bound as their discriminant
+impl<E: DbEnumInt> From<E> for DataKind {
+    fn from(value: E) -> Self {
+        DataKind::Int4(value.to_i32())
+    }
+}
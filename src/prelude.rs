@@ -1,36 +1,38 @@
-pub use crate::common::types::{Order, PrimaryKey, CursorPaginatedResult, PaginatedResult};
+pub use crate::common::types::{BatchMode, IndexHint, LockMode, Order, PrimaryKey, SampleMethod, ChildSpec, FilterTree, SoftDelete, TreeNode, WindowFunc, UpdateOutcome, CursorPaginatedResult, CursorEdge, EncodedCursorPage, PaginatedResult, decode_cursor, encode_cursor};
+pub use crate::common::conversion::{clear_column_transformers, clear_text_normalizer, decode_columns, enable_text_trim, register_column_transformer, set_text_normalizer, with_text_normalizer, ColumnTransformer, DbEnumInt};
 pub use crate::common::error::{KitxError, QueryError, RelationError};
-pub use crate::common::fields::{batch_extract, extract_all, extract_with_bind, extract_with_filter, get_value, get_values};
-pub use crate::common::filter::{push_primary_key_bind, push_primary_key_conditions};
-pub use crate::common::helper::{get_table_name, QueryCondition};
+pub use crate::common::fields::{batch_extract, extract_all, extract_with_bind, extract_with_filter, get_value, get_values, is_default_pk};
+pub use crate::common::filter::{push_bit_and, push_bit_or, push_count_filter, push_dwithin, push_example_conditions, push_filter_tree, push_has_flag, push_ilike, push_in_large, push_not_ilike, push_not_like, push_primary_key_bind, push_primary_key_conditions, push_raw_bind};
+pub use crate::common::helper::{clear_generated_columns, clear_interceptors, get_table_name, is_valid_identifier, register_generated_columns, register_interceptor, QueryCondition, QueryInterceptor};
 pub use crate::common::relation::EntitiesRelation;
+pub use crate::common::loader::BatchLoader;
 
 #[cfg(feature = "sqlite")]
 pub mod sqlite {
     pub use crate::sqlite::{
-        connection::{create_db_pool, setup_db_pool},
+        connection::{create_db_pool, execute_ddl, get_named_pool, named_pools, register_named_pool, setup_db_pool, unregister_named_pool},
         kind::DataKind,
-        query::{execute, execute_with_trans, fetch_all, fetch_one, fetch_optional, fetch_scalar, fetch_scalar_optional},
-        builder::{Insert, Select, Update, Delete, Upsert, Subquery, QB, SQB},
+        query::{archive_by_key, cascade_by_key, execute, execute_all, execute_with_trans, export_ndjson, fetch_all, fetch_all_sharded, fetch_column, fetch_cursor_encoded, fetch_tree, fetch_one, fetch_exists, fetch_stream, find_or_create, get_changed_since, fetch_optional, fetch_page_consistent, fetch_page_plus_one, fetch_scalar, fetch_scalar_opt, fetch_scalar_optional, insert_one_returning, save, table_exists, transaction, update_one_checked, update_or_insert_by, supports_native_upsert, upsert_many, upsert_with_fallback},
+        builder::{Insert, Replace, Select, Update, Delete, Upsert, Subquery, QB, SQB},
     };
 }
 
 #[cfg(feature = "mysql")]
 pub mod mysql {
     pub use crate::mysql::{
-        connection::{create_db_pool, setup_db_pool},
+        connection::{advisory_lock, advisory_unlock, create_db_pool, execute_ddl, get_named_pool, named_pools, register_named_pool, setup_db_pool, try_advisory_lock, unregister_named_pool},
         kind::DataKind,
-        query::{execute, execute_with_trans, fetch_all, fetch_one, fetch_optional, fetch_scalar, fetch_scalar_optional},
-        builder::{Insert, Select, Update, Delete, Upsert, Subquery, QB, SQB},
+        query::{archive_by_key, cascade_by_key, execute, execute_all, execute_with_trans, export_ndjson, fetch_all, fetch_all_sharded, fetch_column, fetch_cursor_encoded, fetch_tree, fetch_one, fetch_exists, fetch_stream, find_or_create, get_changed_since, fetch_optional, fetch_optional_nowait, fetch_page_consistent, fetch_page_plus_one, fetch_scalar, fetch_scalar_opt, fetch_scalar_optional, insert_one_returning, save, table_exists, transaction, update_one_checked, update_or_insert_by},
+        builder::{Insert, Replace, Select, Update, Delete, Upsert, Subquery, QB, SQB},
     };
 }
 
 #[cfg(feature = "postgres")]
 pub mod postgres {
     pub use crate::postgres::{
-        connection::{create_db_pool, setup_db_pool},
-        kind::DataKind,
-        query::{execute, execute_with_trans, fetch_all, fetch_one, fetch_optional, fetch_scalar, fetch_scalar_optional},
-        builder::{Insert, Select, Update, Delete, Upsert, Subquery, QB, SQB},
+        connection::{advisory_lock, advisory_unlock, create_db_pool, execute_ddl, get_named_pool, named_pools, register_named_pool, setup_db_pool, try_advisory_lock, unregister_named_pool},
+        kind::{DataKind, GeometryWkt},
+        query::{archive_by_key, cascade_by_key, execute, execute_all, execute_with_trans, export_ndjson, fetch_all, fetch_all_sharded, fetch_column, fetch_cursor_encoded, fetch_tree, fetch_one, fetch_exists, fetch_stream, find_or_create, get_changed_since, fetch_optional, fetch_optional_nowait, fetch_page_consistent, fetch_page_plus_one, fetch_scalar, fetch_scalar_opt, fetch_scalar_optional, insert_many_on_conflict_nothing_returning, insert_one_returning, insert_or_ignore, save, table_exists, transaction, transaction_with_settings, update_one_checked, update_or_insert_by, upsert_many_detailed},
+        builder::{Insert, Merge, Select, Update, Delete, Upsert, Subquery, QB, SQB},
     };
 }
\ No newline at end of file
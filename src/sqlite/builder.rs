@@ -13,7 +13,7 @@
 
 use sqlx::{QueryBuilder, Sqlite};
 
-use crate::{internal::{delete_builder, insert_builder, select_builder, subquery, update_builder, upsert_sqlite}, sqlite::kind::DataKind};
+use crate::{internal::{delete_builder, insert_builder, replace_builder, select_builder, subquery, update_builder, upsert_sqlite}, sqlite::kind::DataKind};
 
 /// QueryBuilder type alias for SQLite  
 /// SQLite 的 QueryBuilder 类型别名
@@ -95,6 +95,12 @@ pub type SQB<'a> = subquery::SubqueryBuilder<'a, DataKind>;
 /// * `group_by` -  Add GROUP BY clause to the subquery
 /// * `having` - Add HAVING clause to the subquery
 /// * `append_to` - Embed the subquery into a parent query builder
+/// * `append_in_to` - Embed the subquery as `column IN (subquery)`, rejecting projections other than one column
+/// * `projected_columns` - Number of columns the subquery projects
+/// * `check_placeholders` - Check that raw `?` fragments match the bound values
+/// * `try_append_to` - Embed the subquery after checking its placeholders
+/// * `into_count` - Build a `SELECT COUNT(*)` query over the subquery as a derived table
+/// * `into_exists` - Build a `SELECT EXISTS (...)` query over the subquery, run with `fetch_exists`
 /// 
 /// # 公共方法
 /// 
@@ -106,6 +112,12 @@ pub type SQB<'a> = subquery::SubqueryBuilder<'a, DataKind>;
 /// * `group_by` - 向子查询中添加 GROUP BY 子句
 /// * `having` -  向子查询中添加 HAVING 子句
 /// * `append_to` - 将子查询嵌入到父查询构建器中
+/// * `append_in_to` - 以 `column IN (subquery)` 形式嵌入子查询，投影列数不为一时报错
+/// * `projected_columns` - 子查询投影的列数
+/// * `check_placeholders` - 检查原始 `?` 片段与绑定值数量是否一致
+/// * `try_append_to` - 检查占位符后再将子查询嵌入父查询
+/// * `into_count` - 基于子查询（作为派生表）构建 `SELECT COUNT(*)` 查询
+/// * `into_exists` - 基于子查询构建 `SELECT EXISTS (...)` 查询，配合 `fetch_exists` 执行
 /// 
 /// # Examples
 /// 
@@ -139,24 +151,36 @@ pub type Subquery<'a, ET> = subquery::Subquery<'a, ET, DataKind>;
 /// 
 /// * `one` - Create single record insert operation
 /// * `many` - Create multiple records insert operation
+/// * `many_with_casts` - Create multiple records insert operation, casting the placeholders of the given columns
+/// * `many_or_ignore` - Create multiple records insert operation skipping conflicting rows (ON CONFLICT DO NOTHING)
+/// * `one_with_columns` - Create single record insert with only the given columns
 /// * `table` - Create custom table and columns
 /// * `with_table` - Create a insert with a custom table name
 /// * `from_query` - Create an Insert instance from a query
 /// * `from_query_with_table` - Create an Insert instance from a query with a custom table name
 /// * `custom` - Custom VALUES or value-related query statements
+/// * `on_conflict_do_nothing` - Add ON CONFLICT DO NOTHING, with an optional conflict target
 /// * `returning` - Add RETURNING clause to the insert statement
+/// * `try_returning` - Add RETURNING with the columns checked against the entity's fields
+/// * `returning_none` - Explicitly add no RETURNING clause (a marker, does not remove an earlier `returning`)
 /// * `finish` - Finish building, get the internal QueryBuilder
 /// 
 /// # 公共方法
 /// 
 /// * `one` - 创建单条记录插入操作
 /// * `many` - 创建多条记录插入操作
+/// * `many_with_casts` - 创建多条记录插入操作，并对指定列的占位符做类型转换
+/// * `many_or_ignore` - 创建跳过冲突行的多条记录插入操作（ON CONFLICT DO NOTHING）
+/// * `one_with_columns` - 创建只写入指定列的单条记录插入操作
 /// * `table` - 创建默认表名的插入操作
 /// * `with_table` - 创建带有自定义表名的插入操作
 /// * `from_query` - 从外部查询中创建 Insert 实例
 /// * `from_query_with_table` - 从外部查询中创建 Insert 实例，可以自定义表名
 /// * `custom` - 自定义 VALUES 或值相关的查询语句
+/// * `on_conflict_do_nothing` - 添加 ON CONFLICT DO NOTHING，冲突目标可选
 /// * `returning` - 添加 RETURNING 子句到插入语句
+/// * `try_returning` - 添加 RETURNING 子句，并校验各列均为实体字段
+/// * `returning_none` - 明确不添加 RETURNING 子句（仅作标记，不会移除之前的 `returning`）
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
 /// 
 /// # Examples
@@ -185,25 +209,43 @@ pub type Insert<'a, ET> = insert_builder::Insert<'a, ET, Sqlite, DataKind>;
 /// # Public Methods
 /// 
 /// * `one` - Create a single entity update operation
+/// * `many` - Create a batch update of several entities as one statement with a CASE per column
+/// * `set_where` - Create an update assigning one value to a column on rows matching a condition
 /// * `table` - Create an Update instance with the default table name
 /// * `with_table` - Create an Update instance with a custom table name
 /// * `from_query` - Create an Update instance from a query
 /// * `from_query_with_table` - Create an Update instance from a query with a custom table name
+/// * `with_cte` - Create an UPDATE preceded by a WITH common table expression
+/// * `with_join` - Create an UPDATE reading from a joined table (JOIN on MySQL, FROM on PostgreSQL/SQLite)
+/// * `set_from_entity` - Add `col = ?` assignments using the entity's values for the given columns
+/// * `json_array_append` - Add an assignment appending one element to a JSON array column
+/// * `set_cast` - Add a `col = ?` assignment with the placeholder cast to a SQL type
 /// * `custom` - Custom SET columns or other query statements
 /// * `filter` - Add WHERE condition to the update statement
 /// * `returning` - Add RETURNING clause to the update statement 
+/// * `try_returning` - Add RETURNING with the columns checked against the entity's fields
+/// * `returning_none` - Explicitly add no RETURNING clause (a marker, does not remove an earlier `returning`)
 /// * `finish` - Finish building, get the internal QueryBuilder
 /// 
 /// # 公共方法
 /// 
 /// * `one` - 创建单个实体更新操作
+/// * `many` - 以单条语句创建多个实体的批量更新操作，每列一个 CASE
+/// * `set_where` - 创建将同一个值赋给匹配条件记录某一列的更新操作
 /// * `table` - 创建使用默认表名的 Update 实例
 /// * `with_table` - 创建使用自定义表名的 Update 实例
 /// * `from_query` - 从外部查询中创建 Update 实例
 /// * `from_query_with_table` - 从外部查询中创建 Update 实例，可以自定义表名
+/// * `with_cte` - 创建前置 WITH 公用表表达式的 UPDATE
+/// * `with_join` - 创建从关联表读取数据的 UPDATE（MySQL 使用 JOIN，PostgreSQL/SQLite 使用 FROM）
+/// * `set_from_entity` - 添加 `col = ?` 赋值，值取自实体的指定列
+/// * `json_array_append` - 添加向 JSON 数组列追加一个元素的赋值
+/// * `set_cast` - 添加占位符转换为 SQL 类型的 `col = ?` 赋值
 /// * `custom` - 自定义 SET 列或其他查询语句
 /// * `filter` - 向查询中添加过滤查询部分
 /// * `returning` - 添加 RETURNING 子句到更新语句
+/// * `try_returning` - 添加 RETURNING 子句，并校验各列均为实体字段
+/// * `returning_none` - 明确不添加 RETURNING 子句（仅作标记，不会移除之前的 `returning`）
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
 /// 
 /// # Examples
@@ -235,9 +277,18 @@ pub type Update<'a, ET> = update_builder::Update<'a, ET, Sqlite, DataKind>;
 /// * `with_table` - Create a Delete instance with a custom table name
 /// * `from_query` - Create an Delete instance from a query
 /// * `from_query_with_table` - Create an Delete instance from a query with a custom table name
+/// * `with_cte` - Create a DELETE preceded by a WITH common table expression
+/// * `soft` - Create a soft delete `UPDATE table SET column = ?` from a `SoftDelete` configuration
 /// * `by_primary_key` - Create a DELETE query by primary key
+/// * `by_keys` - Create a DELETE query matching a key column against a list of keys
+/// * `cascade_by_key` - Create cascading DELETE queries for child tables and the parent row
+/// * `archive_by_key` - Create queries copying a row into an archive table and soft-deleting it
+/// * `dedupe_keep_latest` - Create a DELETE removing duplicates, keeping the latest row per group
+/// * `using` - Create a DELETE matching rows of another table (USING / JOIN / EXISTS per database)
 /// * `filter` - Create a DELETE query with custom WHERE conditions
 /// * `returning` - Add RETURNING clause to the DELETE statement 
+/// * `try_returning` - Add RETURNING with the columns checked against the entity's fields
+/// * `returning_none` - Explicitly add no RETURNING clause (a marker, does not remove an earlier `returning`)
 /// * `finish` - Finish building, get the internal QueryBuilder
 /// 
 /// # 公共方法
@@ -246,9 +297,18 @@ pub type Update<'a, ET> = update_builder::Update<'a, ET, Sqlite, DataKind>;
 /// * `with_table` - 使用自定义表名创建 Delete 实例
 /// * `from_query` - 从外部查询中创建 Delete 实例
 /// * `from_query_with_table` - 从外部查询中创建 Delete 实例，可以自定义表名
+/// * `with_cte` - 创建前置 WITH 公用表表达式的 DELETE
+/// * `soft` - 根据 `SoftDelete` 配置创建软删除 `UPDATE table SET column = ?`
 /// * `by_primary_key` - 通过主键创建 Delete 查询
+/// * `by_keys` - 创建按键列表匹配键列的 DELETE 查询
+/// * `cascade_by_key` - 创建子表与父记录的级联 DELETE 查询
+/// * `archive_by_key` - 创建将记录复制到归档表并软删除的查询
+/// * `dedupe_keep_latest` - 创建删除重复记录的 DELETE，每组保留最新的一条
+/// * `using` - 创建按另一张表的行匹配删除的 DELETE（按数据库使用 USING / JOIN / EXISTS）
 /// * `filter` - 创建带有自定义 WHERE 条件的 DELETE 查询
 /// * `returning` - 添加 RETURNING 子句到删除语句
+/// * `try_returning` - 添加 RETURNING 子句，并校验各列均为实体字段
+/// * `returning_none` - 明确不添加 RETURNING 子句（仅作标记，不会移除之前的 `returning`）
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
 /// 
 /// # Examples
@@ -280,15 +340,37 @@ pub type Delete<'a, ET> = delete_builder::Delete<'a, ET, Sqlite, DataKind>;
 /// * `with_table` - Create a Select instance with a custom table name
 /// * `from_query` - Create an Select instance from a query
 /// * `from_query_with_table` - Create an Select instance from a query with a custom table name
+/// * `from_subquery` - Create a Select instance reading from a subquery (derived table)
 /// * `columns` - Create a custom column query statement
+/// * `star` - Select every table column with `*` instead of the entity field list
+/// * `columns_coalesce` - Select the entity fields with COALESCE defaults for nullable columns
+/// * `window` - Select all entity fields plus a window function column
+/// * `count_distinct` - Count distinct combinations of one or more columns
+/// * `distinct` - Select the distinct values of one column
+/// * `column_exists_subquery` - Select all entity columns plus an `EXISTS (subquery) AS alias` flag
 /// * `filter` - Create a SELECT query with custom WHERE conditions
 /// * `join` - Create a JOIN query statement
+/// * `index_hint` - Add an index hint after the table name (MySQL only, no-op elsewhere)
+/// * `tablesample` - Add a TABLESAMPLE clause after the table name (PostgreSQL only, no-op elsewhere)
 /// * `group_by` - Create a GROUP BY query statement
 /// * `having` - Create a HAVING clause
+/// * `having_bind` - Add a HAVING condition with one bound value
 /// * `by_primary_key` - Create a SELECT query by primary key
+/// * `by_keys` - Create a SELECT query matching a key column against a list of keys
 /// * `order_by` - Create an ORDER BY clause
+/// * `order_by_many` - Create several ORDER BY terms in the given order
+/// * `order_by_field` - Order by an explicit value list (FIELD on MySQL, CASE elsewhere)
+/// * `strict` - Validate table and column names as plain identifiers
+/// * `try_finish` - Build the final query, reporting identifiers rejected in strict mode
 /// * `paginate` - Create a pagination query statement
+/// * `paginate_plus_one` - Create a pagination query fetching one extra row to detect the next page
+/// * `limit_with_ties` - Add `FETCH FIRST n ROWS WITH TIES` (PostgreSQL only, requires ORDER BY)
+/// * `for_update_nowait` - Add `FOR UPDATE NOWAIT` (MySQL 8.0+ / PostgreSQL, no-op on SQLite)
+/// * `lock` - Set the row lock mode (`LockMode`), appended after ORDER BY / LIMIT (no-op on SQLite)
+/// * `random_one` - Fetch one random matching row (ORDER BY RANDOM()/RAND() LIMIT 1)
 /// * `cursor` - Create a cursor pagination query statement
+/// * `cursor_nullable` - Create a cursor pagination query over a nullable sort column
+/// * `cursor_multi` - Create a keyset cursor pagination query over several sort columns
 /// * `finish` - Finish building, get the internal QueryBuilder
 /// 
 /// # 公共方法
@@ -297,15 +379,37 @@ pub type Delete<'a, ET> = delete_builder::Delete<'a, ET, Sqlite, DataKind>;
 /// * `with_table` - 创建使用自定义表名的 Select 实例
 /// * `from_query` - 从外部查询中创建 Select 实例
 /// * `from_query_with_table` - 从外部查询中创建 Select 实例，可以自定义表名
+/// * `from_subquery` - 创建以子查询（派生表）为数据源的 Select 实例
 /// * `columns` - 创建自定义列的查询语句
+/// * `star` - 使用 `*` 代替实体字段列表选择表的所有列
+/// * `columns_coalesce` - 以实体字段为查询列，并为可空列提供 COALESCE 默认值
+/// * `window` - 查询实体全部字段及一个窗口函数列
+/// * `count_distinct` - 统计一列或多列组合的去重数量
+/// * `distinct` - 选择某一列的不同取值
+/// * `column_exists_subquery` - 查询实体所有列并附加 `EXISTS (subquery) AS alias` 标志列
 /// * `filter` - 创建带有自定义 WHERE 条件的查询语句
 /// * `join` - 创建 JOIN 查询语句
+/// * `index_hint` - 在表名后添加索引提示（仅 MySQL，其它数据库忽略）
+/// * `tablesample` - 在表名后添加 TABLESAMPLE 采样子句（仅 PostgreSQL，其它数据库忽略）
 /// * `group_by` - 创建 GROUP BY 查询语句 
 /// * `having` - 创建 HAVING 子句
+/// * `having_bind` - 添加带一个绑定值的 HAVING 条件
 /// * `by_primary_key` - 创建按主键条件查询语句
+/// * `by_keys` - 创建按键列表匹配键列的查询语句
 /// * `order_by` - 创建排序子句
+/// * `order_by_many` - 按给定顺序创建多个排序项
+/// * `order_by_field` - 按给定值列表的顺序排序（MySQL 使用 FIELD，其他使用 CASE）
+/// * `strict` - 校验表名和列名为普通标识符
+/// * `try_finish` - 构建最终查询，并报告严格模式下被拒绝的标识符
 /// * `paginate` - 创建分页查询语句
+/// * `paginate_plus_one` - 创建多取一行以判断是否存在下一页的分页查询语句
+/// * `limit_with_ties` - 添加 `FETCH FIRST n ROWS WITH TIES`（仅 PostgreSQL，需要 ORDER BY）
+/// * `for_update_nowait` - 添加 `FOR UPDATE NOWAIT`（MySQL 8.0+ / PostgreSQL，SQLite 上无效果）
+/// * `lock` - 设置行锁模式（`LockMode`），追加在 ORDER BY / LIMIT 之后（SQLite 上无效果）
+/// * `random_one` - 随机取一条匹配的行（ORDER BY RANDOM()/RAND() LIMIT 1）
 /// * `cursor` - 创建游标分页查询语句
+/// * `cursor_nullable` - 创建基于可空排序列的游标分页查询语句
+/// * `cursor_multi` - 创建基于多列排序键的键集游标分页查询语句
 /// * `finish` - 结束构建，获取内部的 QueryBuilder
 /// 
 /// # Examples
@@ -335,11 +439,13 @@ pub type Select<'a, ET> = select_builder::Select<'a, ET, Sqlite, DataKind>;
 /// 
 /// * `one` - Create single record upsert operation
 /// * `many` - Create multiple records upsert operation
+/// * `many_fallback` - Create update/insert statement pairs for SQLite versions without ON CONFLICT
 /// 
 /// # 公共方法
 /// 
 /// * `one` - 创建单条记录更新插入操作
 /// * `many` - 创建多条记录更新插入操作
+/// * `many_fallback` - 为不支持 ON CONFLICT 的 SQLite 版本创建更新/插入语句对
 /// 
 /// # Examples
 /// 
@@ -351,13 +457,47 @@ pub type Select<'a, ET> = select_builder::Select<'a, ET, Sqlite, DataKind>;
 /// ```
 pub type Upsert<'a, ET> = upsert_sqlite::Upsert<'a, ET, Sqlite, DataKind>;
 
+/// Replace builder type alias for SQLite  
+/// SQLite 的 Replace 构建器类型别名
+/// 
+/// Used to build `REPLACE INTO` statements. On a key conflict the existing row is deleted
+/// and the new row inserted, so delete triggers fire and omitted columns fall back to defaults.
+/// 
+/// 用于构建 `REPLACE INTO` 语句。发生键冲突时会删除已有记录并插入新记录，
+/// 因此会触发删除触发器，未提供的列会回退为默认值。
+/// 
+/// # Type Parameters
+/// 
+/// * `ET` - The entity type that this replace builder operates on
+/// * `ET` - 此替换构建器操作的实体类型
+/// 
+/// # Public Methods
+/// 
+/// * `one` - Create single record replace operation
+/// * `many` - Create multiple records replace operation
+/// 
+/// # 公共方法
+/// 
+/// * `one` - 创建单条记录替换操作
+/// * `many` - 创建多条记录替换操作
+/// 
+/// # Examples
+/// 
+/// ```
+/// use kitx::sqlite::builder::Replace;
+/// 
+/// let entity = User { id: 1, name: "Charlie".to_string() };
+/// let replace_query = Replace::one(&entity).unwrap();
+/// ```
+pub type Replace<'a, ET> = replace_builder::Replace<'a, ET, Sqlite, DataKind>;
+
 #[cfg(test)]
 mod tests {
 
     use crate::{
-        common::types::{CursorPaginatedResult, Order, PaginatedResult, PrimaryKey}, sqlite::{builder::{Delete, Insert, Select, Subquery, Update, Upsert, QB}, 
+        common::{filter::{push_count_filter, push_group_concat, push_eq_cast, push_example_conditions, push_filter_tree, push_has_flag, push_ilike, push_not_ilike, push_not_like, push_raw_bind}, types::{CursorPaginatedResult, FilterTree, LockMode, TreeNode, IndexHint, Order, PaginatedResult, PrimaryKey, SampleMethod}}, sqlite::{builder::{Delete, Insert, Replace, Select, Subquery, Update, Upsert, QB}, 
         connection, kind::DataKind, 
-        query::{execute, fetch_all, fetch_one, fetch_scalar}}, test_utils::{article::Article, init::get_database_url}
+        query::{archive_by_key, cascade_by_key, execute, execute_all, export_ndjson, fetch_all, fetch_all_sharded, fetch_column, fetch_tree, fetch_exists, fetch_one, fetch_stream, find_or_create, get_changed_since, fetch_page_consistent, insert_one_returning, fetch_page_plus_one, fetch_scalar, fetch_scalar_opt, fetch_scalar_optional, save, table_exists, transaction, update_one_checked, update_or_insert_by, upsert_many, upsert_with_fallback}}, test_utils::{article::{Article, ArticleTag}, init::{create_memory_pool, get_database_url}}
     };
     //use super::*;
 
@@ -607,4 +747,2717 @@ mod tests {
         dbg!(&result);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_query_by_example() {
+        let example = Article {
+            tenant_id: 7,
+            title: "hello".to_string(),
+            content: None,
+            ..Default::default()
+        };
+
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                let count = push_example_conditions::<_, _, DataKind>(b, &example, &["id", "views", "deleted", "created_at"]);
+                assert_eq!(count, 2);
+            })
+            .finish();
+
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? AND title = ?"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replace_one() {
+        let mut entity = Article::new(100, "replace-1", None);
+        entity.id = 9001;
+        let qb = Replace::one(&entity).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "REPLACE INTO article (id, tenant_id, title, content, views, deleted, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)"
+        );
+
+        init_pool().await;
+        execute(qb).await.unwrap();
+
+        let mut replaced = Article::new(100, "replace-2", None);
+        replaced.id = 9001;
+        execute(Replace::one(&replaced).unwrap()).await.unwrap();
+
+        let binding = vec![9001.into()];
+        let qb = Select::<Article>::table()
+            .by_primary_key(&ARTICLE_KEY, &binding)
+            .finish();
+        let article = fetch_one::<Article>(qb).await.unwrap();
+        assert_eq!(article.title, "replace-2");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_page_consistent() {
+        init_pool().await;
+
+        let filter_build_fn = |qb: &mut QB| {
+            qb.push("tenant_id = ").push_bind(1435);
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        let writer = tokio::spawn(async {
+            for i in 0..5 {
+                let entity = Article::new(1435, &format!("consistent-{}", i), None);
+                let qb = Insert::one(&entity, &ARTICLE_KEY).unwrap();
+                execute(qb).await.unwrap();
+            }
+        });
+
+        for _ in 0..5 {
+            let count_qb = Select::<Article>::table()
+                .columns(|qb| { qb.push("COUNT(*)"); })
+                .filter(filter_build_fn)
+                .finish();
+            let list_qb = Select::<Article>::table()
+                .filter(filter_build_fn)
+                .order_by("id", Order::Asc)
+                .paginate(1, 1000).unwrap();
+
+            let page = fetch_page_consistent::<Article>(count_qb, list_qb, 1, 1000).await.unwrap();
+            assert_eq!(page.total, page.data.len() as u64);
+        }
+
+        writer.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cursor_nullable() {
+        let pool = create_memory_pool().await;
+        let contents = [Some("b"), None, Some("a"), Some("b"), None, Some("c")];
+        for content in contents {
+            let entity = Article::new(1, "nullable", content.map(String::from));
+            Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+        }
+
+        let mut visited = Vec::new();
+        let mut cursor: Option<(Option<DataKind>, DataKind)> = None;
+        loop {
+            let mut qb = Select::<Article>::table()
+                .cursor_nullable("content", "id", Order::Asc, cursor.take(), 2)
+                .unwrap();
+            let page = qb.build_query_as::<Article>().fetch_all(&pool).await.unwrap();
+            if let Some(last) = page.last() {
+                cursor = Some((last.content.clone().map(DataKind::from), DataKind::from(last.id)));
+            }
+            let len = page.len();
+            visited.extend(page.into_iter().map(|a| (a.content, a.id)));
+            if len < 2 {
+                break;
+            }
+        }
+
+        let expected = vec![
+            (Some("a".to_string()), 3),
+            (Some("b".to_string()), 1),
+            (Some("b".to_string()), 4),
+            (Some("c".to_string()), 6),
+            (None, 2),
+            (None, 5),
+        ];
+        assert_eq!(visited, expected);
+    }
+
+    #[test]
+    fn test_index_hint() {
+        let qb = Select::<Article>::table()
+            .index_hint(IndexHint::Force(vec!["idx_tenant".to_string()]))
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1);
+            })
+            .finish();
+
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ?"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_column_exists_subquery() {
+        #[derive(sqlx::FromRow)]
+        struct ArticleFlag {
+            id: i32,
+            has_tags: bool,
+        }
+
+        let pool = create_memory_pool().await;
+        for title in ["tagged", "untagged"] {
+            let entity = Article::new(1, title, None);
+            Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+        }
+        let mut tag = ArticleTag::new("rust");
+        tag.article_id = 1;
+        let tag_key = PrimaryKey::Composite(&["article_id", "share_seq"]);
+        Insert::one(&tag, &tag_key).unwrap().build().execute(&pool).await.unwrap();
+
+        let tags_subquery = Subquery::<ArticleTag>::table()
+            .columns(|b| {
+                b.push("1");
+            })
+            .filter(|b| {
+                b.push("article_id = article.id AND tag = ").push_bind("rust".into());
+            });
+        let mut qb = Select::<Article>::table()
+            .column_exists_subquery("has_tags", tags_subquery)
+            .order_by("id", Order::Asc)
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at, EXISTS (SELECT 1 FROM article_tag WHERE article_id = article.id AND tag = ?) AS has_tags FROM article ORDER BY id ASC"
+        );
+
+        let rows = qb.build_query_as::<ArticleFlag>().fetch_all(&pool).await.unwrap();
+        let flags: Vec<_> = rows.iter().map(|r| (r.id, r.has_tags)).collect();
+        assert_eq!(flags, vec![(1, true), (2, false)]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_page_plus_one() {
+        init_pool().await;
+
+        let filter_build_fn = |qb: &mut QB| {
+            qb.push("tenant_id = ").push_bind(1441);
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        let entities: Vec<_> = (0..4).map(|i| Article::new(1441, &format!("plus-one-{}", i), None)).collect();
+        execute(Insert::many(&entities, &ARTICLE_KEY).unwrap()).await.unwrap();
+
+        let mut pages = Vec::new();
+        for page_number in 1..=3 {
+            let qb = Select::<Article>::table()
+                .filter(filter_build_fn)
+                .order_by("id", Order::Asc)
+                .paginate_plus_one(page_number, 2).unwrap();
+            let (rows, has_next) = fetch_page_plus_one::<Article>(qb, 2).await.unwrap();
+            pages.push((rows.len(), has_next));
+        }
+        assert_eq!(pages, vec![(2, true), (2, false), (0, false)]);
+    }
+
+    #[test]
+    fn test_raw_fragment_with_binds() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1).push(" AND ");
+                push_raw_bind(b, "instr(title, ?) > ? ", vec![DataKind::from("rust"), DataKind::from(0)]).unwrap();
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? AND instr(title, ?) > ? "
+        );
+
+        let mut qb = QB::new("");
+        let err = push_raw_bind(&mut qb, "a = ? AND b = ?", vec![DataKind::from(1)]).unwrap_err();
+        assert!(err.to_string().contains("Expected 2 bound values"));
+        assert_eq!(qb.sql(), "");
+    }
+
+    #[test]
+    fn test_count_distinct() {
+        let qb = Select::<Article>::table()
+            .count_distinct(&["tenant_id", "title"]).unwrap()
+            .finish();
+        assert_eq!(qb.sql(), "SELECT COUNT(DISTINCT tenant_id || '-' || title) FROM article");
+
+        let qb = Select::<Article>::table()
+            .count_distinct(&["tenant_id"]).unwrap()
+            .finish();
+        assert_eq!(qb.sql(), "SELECT COUNT(DISTINCT tenant_id) FROM article");
+    }
+
+    #[test]
+    fn test_tablesample() {
+        let qb = Select::<Article>::with_table("article a")
+            .tablesample(SampleMethod::System, 10.0)
+            .filter(|b| {
+                b.push("a.views > ").push_bind(100);
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article a WHERE a.views > ?"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subquery_count_and_list() {
+        let pool = create_memory_pool().await;
+        for (title, views) in [("a", 10), ("b", 20), ("c", 30)] {
+            let mut entity = Article::new(1, title, None);
+            entity.views = views;
+            Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+        }
+        for article_id in [1, 2, 3] {
+            let mut tag = ArticleTag::new("rust");
+            tag.article_id = article_id;
+            let tag_key = PrimaryKey::Composite(&["article_id", "share_seq"]);
+            Insert::one(&tag, &tag_key).unwrap().build().execute(&pool).await.unwrap();
+        }
+
+        let base = Subquery::<Article>::table()
+            .columns(|b| {
+                b.push("article.*");
+            })
+            .filter(|b| {
+                b.push("article.id IN (SELECT article_id FROM article_tag WHERE tag = ").push_bind("rust".into())
+                    .push(") AND article.views > ").push_bind(15.into());
+            });
+
+        let mut count_qb = base.clone().into_count::<sqlx::Sqlite>();
+        assert_eq!(
+            count_qb.sql(),
+            "SELECT COUNT(*) FROM (SELECT article.* FROM article WHERE article.id IN (SELECT article_id FROM article_tag WHERE tag = ?) AND article.views > ?) AS count_base"
+        );
+        let total: i64 = count_qb.build_query_scalar().fetch_one(&pool).await.unwrap();
+
+        let mut list_qb = Select::<Article>::from_subquery(base, "base")
+            .order_by("id", Order::Desc)
+            .paginate(1, 10).unwrap();
+        let list = list_qb.build_query_as::<Article>().fetch_all(&pool).await.unwrap();
+
+        assert_eq!(total, 2);
+        assert_eq!(list.iter().map(|a| a.id).collect::<Vec<_>>(), vec![3, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_fallback() {
+        use crate::internal::upsert_sqlite::supports_on_conflict;
+        assert!(!supports_on_conflict("3.23.1"));
+        assert!(supports_on_conflict("3.24.0"));
+        assert!(supports_on_conflict("3.45.1"));
+
+        init_pool().await;
+        let filter_build_fn = |qb: &mut QB| {
+            qb.push("tenant_id = ").push_bind(1447);
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        execute(Insert::one(&Article::new(1447, "existing", None), &ARTICLE_KEY).unwrap()).await.unwrap();
+        let mut existing = fetch_one::<Article>(Select::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+
+        existing.title = "existing-updated".to_string();
+        let fresh = Article::new(1447, "fresh", None);
+        let binding = [existing.clone(), fresh];
+        let statements = Upsert::many_fallback(&binding, &ARTICLE_KEY).unwrap();
+        let rows_affected = upsert_with_fallback(statements).await.unwrap();
+        assert_eq!(rows_affected, 2);
+
+        let qb_by_tenant = || Select::<Article>::table()
+            .filter(filter_build_fn)
+            .order_by("id", Order::Asc)
+            .finish();
+        let list = fetch_all::<Article>(qb_by_tenant()).await.unwrap();
+        let titles: Vec<_> = list.iter().map(|a| (a.id == existing.id, a.title.as_str())).collect();
+        assert_eq!(titles, vec![(true, "existing-updated"), (false, "fresh")]);
+
+        existing.title = "existing-auto".to_string();
+        let binding = [existing.clone(), Article::new(1447, "auto", None)];
+        assert_eq!(upsert_many(&binding, &ARTICLE_KEY).await.unwrap(), 2);
+        let list = fetch_all::<Article>(qb_by_tenant()).await.unwrap();
+        let titles: Vec<_> = list.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, vec!["existing-auto", "fresh", "auto"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_set_from_entity() {
+        let pool = create_memory_pool().await;
+        let entity = Article::new(1448, "original", None);
+        Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+
+        let mut changed = Article::new(1448, "changed", Some("new content".to_string()));
+        changed.views = 99;
+        let mut qb = Update::<Article>::table()
+            .set_from_entity(&changed, &["title", "content"])
+            .unwrap()
+            .custom(|qb| {
+                qb.push(", views = views + 1");
+            })
+            .filter(|qb| {
+                qb.push("tenant_id = ").push_bind(1448);
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "UPDATE article SET title = ?, content = ?, views = views + 1 WHERE tenant_id = ?"
+        );
+        qb.build().execute(&pool).await.unwrap();
+
+        let mut qb = Select::<Article>::table()
+            .filter(|qb| {
+                qb.push("tenant_id = ").push_bind(1448);
+            })
+            .finish();
+        let updated: Article = qb.build_query_as().fetch_one(&pool).await.unwrap();
+        assert_eq!(updated.title, "changed");
+        assert_eq!(updated.content.as_deref(), Some("new content"));
+        assert_eq!(updated.views, 1);
+
+        assert!(Update::<Article>::table().set_from_entity(&changed, &[]).is_err());
+        assert!(Update::<Article>::table().set_from_entity(&changed, &["missing"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_enum_int_binding() {
+        use crate::common::conversion::DbEnumInt;
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        enum Status {
+            Draft = 0,
+            Archived = 7,
+        }
+
+        impl DbEnumInt for Status {
+            fn to_i32(&self) -> i32 {
+                *self as i32
+            }
+
+            fn from_i32(value: i32) -> Option<Self> {
+                match value {
+                    0 => Some(Status::Draft),
+                    7 => Some(Status::Archived),
+                    _ => None,
+                }
+            }
+        }
+
+        assert_eq!(DataKind::from(Status::Archived), DataKind::Integer(7));
+
+        let pool = create_memory_pool().await;
+        let mut qb = Insert::<Article>::table()
+            .columns(["tenant_id", "title", "views"])
+            .custom(|qb| {
+                qb.push(" VALUES (").push_bind(1450).push(", ").push_bind("archived").push(", ")
+                    .push_bind(DataKind::from(Status::Archived)).push(")");
+            })
+            .finish();
+        qb.build().execute(&pool).await.unwrap();
+
+        let mut qb = Select::<Article>::table()
+            .columns(|qb| {
+                qb.push("views");
+            })
+            .filter(|qb| {
+                qb.push("views = ").push_bind(DataKind::from(Status::Archived));
+            })
+            .finish();
+        let views: i32 = qb.build_query_scalar().fetch_one(&pool).await.unwrap();
+        assert_eq!(Status::from_i32(views), Some(Status::Archived));
+        assert_eq!(Status::from_i32(Status::Draft.to_i32()), Some(Status::Draft));
+        assert_eq!(Status::from_i32(3), None);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_encoded() {
+        use crate::common::types::decode_cursor;
+
+        let pool = create_memory_pool().await;
+        for title in ["a", "b", "c"] {
+            Insert::one(&Article::new(1452, title, None), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let limit = 2;
+        let mut qb = Select::<Article>::table()
+            .cursor("id", Order::Asc, None, limit).unwrap();
+        let list: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        let page = CursorPaginatedResult::<Article, DataKind>::new(list, limit, Order::Asc)
+            .into_encoded(&["id"])
+            .unwrap();
+
+        assert_eq!(page.edges.len(), 2);
+        for edge in &page.edges {
+            let keys = decode_cursor(&edge.cursor).unwrap();
+            assert_eq!(keys["id"], edge.node.id);
+        }
+        assert_eq!(page.next_cursor.as_ref(), Some(&page.edges[1].cursor));
+        assert_eq!(page.prev_cursor.as_ref(), Some(&page.edges[0].cursor));
+        assert!(decode_cursor("not a cursor").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_cursor_encoded() {
+        use crate::common::types::decode_cursor;
+        use crate::sqlite::query::fetch_cursor_encoded;
+
+        init_pool().await;
+        let tenant = |b: &mut QB| {
+            b.push("tenant_id = ").push_bind(DataKind::from(1452));
+        };
+        execute(Delete::<Article>::table().filter(tenant).finish()).await.unwrap();
+        for title in ["a", "b", "c"] {
+            execute(Insert::one(&Article::new(1452, title, None), &ARTICLE_KEY).unwrap()).await.unwrap();
+        }
+
+        let qb = Select::<Article>::table()
+            .filter(tenant)
+            .cursor("id", Order::Desc, None, 2).unwrap();
+        let page = fetch_cursor_encoded::<Article>(qb, 2, Order::Desc, &["id"]).await.unwrap();
+        let titles: Vec<_> = page.edges.iter().map(|edge| edge.node.title.as_str()).collect();
+        assert_eq!(titles, ["c", "b"]);
+        for edge in &page.edges {
+            assert_eq!(decode_cursor(&edge.cursor).unwrap()["id"], edge.node.id);
+        }
+        assert_eq!(page.next_cursor.as_ref(), Some(&page.edges[0].cursor));
+        assert_eq!(page.prev_cursor.as_ref(), Some(&page.edges[1].cursor));
+    }
+
+    #[tokio::test]
+    async fn test_delete_cascade() {
+        use crate::common::types::ChildSpec;
+
+        let pool = create_memory_pool().await;
+        let result = Insert::one(&Article::new(1453, "parent", None), &ARTICLE_KEY).unwrap()
+            .build().execute(&pool).await.unwrap();
+        let article_id = result.last_insert_rowid();
+        for (seq, tag) in ["rust", "sql"].iter().enumerate() {
+            let mut article_tag = ArticleTag::new(tag);
+            article_tag.article_id = article_id as i32;
+            article_tag.share_seq = seq as i32 + 1;
+            Insert::one(&article_tag, &PrimaryKey::Composite(&["article_id", "share_seq"])).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let children = [ChildSpec::new("article_tag", "article_id")];
+        let builders = Delete::<Article>::cascade_by_key("id", DataKind::Integer(article_id), &children);
+        assert_eq!(builders.len(), 2);
+        assert_eq!(builders[0].sql(), "DELETE FROM article_tag WHERE article_id = ?");
+        assert_eq!(builders[1].sql(), "DELETE FROM article WHERE id = ?");
+
+        let mut tx = pool.begin().await.unwrap();
+        for mut builder in builders {
+            builder.build().execute(&mut *tx).await.unwrap();
+        }
+        tx.commit().await.unwrap();
+
+        let articles: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM article WHERE tenant_id = 1453")
+            .fetch_one(&pool).await.unwrap();
+        let tags: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM article_tag WHERE article_id = ?")
+            .bind(article_id)
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!((articles, tags), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_cascade_by_key_in_transaction() {
+        use crate::common::types::ChildSpec;
+
+        init_pool().await;
+        let filter_build_fn = |qb: &mut QB| {
+            qb.push("tenant_id = ").push_bind(1453);
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        let parent = Article::new(1453, "parent", None);
+        let article_id = insert_one_returning(&parent, &ARTICLE_KEY).await.unwrap().id;
+        execute(Delete::<ArticleTag>::table()
+            .filter(|b| {
+                b.push("article_id = ").push_bind(DataKind::from(article_id));
+            })
+            .finish()).await.unwrap();
+        let mut article_tag = ArticleTag::new("rust");
+        article_tag.article_id = article_id;
+        execute(Insert::one(&article_tag, &PrimaryKey::Composite(&["article_id", "share_seq"])).unwrap()).await.unwrap();
+
+        let count_rows = || async {
+            let articles = fetch_scalar(Select::<Article>::table()
+                .columns(|b| {
+                    b.push("COUNT(*)");
+                })
+                .filter(filter_build_fn)
+                .finish()).await.unwrap();
+            let tags = fetch_scalar(Select::<ArticleTag>::table()
+                .columns(|b| {
+                    b.push("COUNT(*)");
+                })
+                .filter(|b| {
+                    b.push("article_id = ").push_bind(DataKind::from(article_id));
+                })
+                .finish()).await.unwrap();
+            (articles, tags)
+        };
+
+        let broken = [ChildSpec::new("article_tag", "article_id"), ChildSpec::new("no_such_table", "article_id")];
+        assert!(cascade_by_key::<Article>("id", DataKind::from(article_id), &broken).await.is_err());
+        assert_eq!(count_rows().await, (1, 1));
+
+        let children = [ChildSpec::new("article_tag", "article_id")];
+        assert_eq!(cascade_by_key::<Article>("id", DataKind::from(article_id), &children).await.unwrap(), 1);
+        assert_eq!(count_rows().await, (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_exists() {
+        init_pool().await;
+        let filter_build_fn = |qb: &mut QB| {
+            qb.push("tenant_id = ").push_bind(1454);
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        execute(Insert::one(&Article::new(1454, "exists", None), &ARTICLE_KEY).unwrap()).await.unwrap();
+
+        let exists_for = |title: &'static str| {
+            Subquery::<Article>::table()
+                .columns(|b| {
+                    b.push("1");
+                })
+                .filter(move |b| {
+                    b.push("tenant_id = ").push_bind(1454.into());
+                    b.push(" AND title = ").push_bind(title.into());
+                })
+                .into_exists::<sqlx::Sqlite>()
+        };
+
+        let qb = exists_for("exists");
+        assert_eq!(qb.sql(), "SELECT EXISTS (SELECT 1 FROM article WHERE tenant_id = ? AND title = ?) ");
+        assert!(fetch_exists(qb).await.unwrap());
+        assert!(!fetch_exists(exists_for("missing")).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_paginated_result_map() {
+        #[derive(Debug, PartialEq)]
+        struct ArticleDto {
+            id: i32,
+            title: String,
+        }
+
+        let pool = create_memory_pool().await;
+        for title in ["a", "b", "c"] {
+            Insert::one(&Article::new(1455, title, None), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let mut qb = Select::<Article>::table()
+            .order_by("id", Order::Asc)
+            .paginate(2, 2)
+            .unwrap();
+        let list: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        let page = PaginatedResult::new(list, 3, 2, 2);
+
+        let dto_page = page.map(|a| ArticleDto { id: a.id, title: a.title });
+        assert_eq!(dto_page.data, vec![ArticleDto { id: 3, title: "c".to_string() }]);
+        assert_eq!((dto_page.total, dto_page.page_number, dto_page.page_size), (3, 2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_text_trim_on_insert() {
+        use crate::common::conversion::with_text_normalizer;
+
+        let pool = create_memory_pool().await;
+        let entity = Article::new(1456, "  hi  ", Some(" body ".to_string()));
+
+        let qb = with_text_normalizer(|s| s.trim().to_string(), || Insert::one(&entity, &ARTICLE_KEY));
+        qb.unwrap().build().execute(&pool).await.unwrap();
+        Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+
+        let mut qb = Select::<Article>::table()
+            .filter(|qb| {
+                qb.push("tenant_id = ").push_bind(1456);
+            })
+            .order_by("id", Order::Asc)
+            .finish();
+        let stored: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(stored[0].title, "hi");
+        assert_eq!(stored[0].content.as_deref(), Some("body"));
+        assert_eq!(stored[1].title, "  hi  ");
+
+        let example = Article { tenant_id: 1456, title: "  hi  ".to_string(), ..Default::default() };
+        let mut qb = with_text_normalizer(|s| s.trim().to_string(), || Select::<Article>::table()
+            .filter(|b| {
+                push_example_conditions::<_, _, DataKind>(b, &example, &["id", "views", "deleted", "created_at"]);
+            })
+            .finish());
+        let found: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].title, "  hi  ");
+    }
+
+    #[tokio::test]
+    async fn test_table_exists() {
+        init_pool().await;
+        assert!(table_exists("article").await.unwrap());
+        assert!(!table_exists("no_such_table").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_archive_by_key() {
+        let pool = create_memory_pool().await;
+        sqlx::query("CREATE TABLE article_archive AS SELECT * FROM article WHERE 0")
+            .execute(&pool).await.unwrap();
+        let result = Insert::one(&Article::new(1460, "to archive", None), &ARTICLE_KEY).unwrap()
+            .build().execute(&pool).await.unwrap();
+        let id = result.last_insert_rowid();
+
+        let builders = Delete::<Article>::archive_by_key("id", DataKind::Integer(id), "article_archive", "deleted");
+        assert_eq!(
+            builders[0].sql(),
+            "INSERT INTO article_archive (id, tenant_id, title, content, views, deleted, created_at) SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE id = ? AND deleted = FALSE"
+        );
+        assert_eq!(builders[1].sql(), "UPDATE article SET deleted = ? WHERE id = ?");
+
+        let mut tx = pool.begin().await.unwrap();
+        for mut builder in builders {
+            builder.build().execute(&mut *tx).await.unwrap();
+        }
+        tx.commit().await.unwrap();
+
+        let archived: Article = sqlx::query_as("SELECT * FROM article_archive WHERE id = ?")
+            .bind(id)
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(archived.title, "to archive");
+        assert!(!archived.deleted);
+        let source: Article = sqlx::query_as("SELECT * FROM article WHERE id = ?")
+            .bind(id)
+            .fetch_one(&pool).await.unwrap();
+        assert!(source.deleted);
+
+        let mut tx = pool.begin().await.unwrap();
+        for mut builder in Delete::<Article>::archive_by_key("id", DataKind::Integer(id), "article_archive", "deleted") {
+            builder.build().execute(&mut *tx).await.unwrap();
+        }
+        tx.commit().await.unwrap();
+        let copies: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM article_archive WHERE id = ?")
+            .bind(id)
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(copies, 1);
+    }
+
+    #[tokio::test]
+    async fn test_archive_by_key_in_transaction() {
+        init_pool().await;
+        execute(QB::new("CREATE TABLE IF NOT EXISTS article_archive AS SELECT * FROM article WHERE 0")).await.unwrap();
+        let filter_build_fn = |b: &mut QB| {
+            b.push("tenant_id = ").push_bind(DataKind::from(1460));
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        let id = execute(Insert::one(&Article::new(1460, "to archive", None), &ARTICLE_KEY).unwrap()).await.unwrap()
+            .last_insert_rowid();
+        execute(Delete::<Article>::with_table("article_archive").filter(|b| {
+            b.push("id = ").push_bind(DataKind::Integer(id));
+        }).finish()).await.unwrap();
+
+        assert_eq!(archive_by_key::<Article>("id", DataKind::Integer(id), "article_archive", "deleted").await.unwrap(), 1);
+        assert_eq!(archive_by_key::<Article>("id", DataKind::Integer(id), "article_archive", "deleted").await.unwrap(), 0);
+        let source = fetch_one::<Article>(Select::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        assert!(source.deleted);
+
+        let err = archive_by_key::<Article>("id", DataKind::Integer(id), "missing_archive", "deleted").await.err().unwrap();
+        assert!(err.to_string().contains("missing_archive"));
+    }
+
+    #[tokio::test]
+    async fn test_has_flag() {
+        const FLAG_PINNED: i64 = 0b0100;
+
+        let pool = create_memory_pool().await;
+        for (title, flags) in [("pinned", 0b0101), ("plain", 0b0011)] {
+            let mut entity = Article::new(1461, title, None);
+            entity.views = flags;
+            Insert::one(&entity, &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let mut qb = Select::<Article>::table()
+            .filter(|qb| {
+                push_has_flag(qb, "views", DataKind::Integer(FLAG_PINNED));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE (views & ?) = ?"
+        );
+        let list: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(list.iter().map(|a| a.title.as_str()).collect::<Vec<_>>(), vec!["pinned"]);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rollback() {
+        init_pool().await;
+        let filter_build_fn = |qb: &mut QB| {
+            qb.push("tenant_id = ").push_bind(1462);
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+
+        let result = transaction(async |conn| {
+            let article = Article::new(1462, "rolled back", None);
+            Insert::one(&article, &ARTICLE_KEY)?.build().execute(&mut *conn).await?;
+            let mut qb = Update::<Article>::table()
+                .custom(|qb| {
+                    qb.push("views = views + 1");
+                })
+                .filter(filter_build_fn)
+                .finish();
+            qb.build().execute(&mut *conn).await?;
+            Err::<(), _>(sqlx::Error::RowNotFound)
+        }).await;
+        assert!(matches!(result, Err(sqlx::Error::RowNotFound)));
+
+        let count = fetch_scalar(Select::<Article>::table()
+            .columns(|qb| {
+                qb.push("COUNT(*)");
+            })
+            .filter(filter_build_fn)
+            .finish()).await.unwrap();
+        assert_eq!(count, 0);
+
+        let title = transaction(async |conn| {
+            let article = Article::new(1462, "committed", None);
+            Insert::one(&article, &ARTICLE_KEY)?.build().execute(&mut *conn).await?;
+            Ok(article.title)
+        }).await.unwrap();
+        let saved = fetch_one::<Article>(Select::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        assert_eq!(saved.title, title);
+    }
+
+    #[tokio::test]
+    async fn test_insert_with_columns() {
+        let pool = create_memory_pool().await;
+        let mut entity = Article::new(1463, "partial", Some("only these".to_string()));
+        entity.views = 42;
+
+        let mut qb = Insert::one_with_columns(&entity, &["title", "content"]).unwrap();
+        assert_eq!(qb.sql(), "INSERT INTO article (title, content) VALUES (?, ?)");
+        qb.build().execute(&pool).await.unwrap();
+
+        let stored: Article = sqlx::query_as("SELECT * FROM article WHERE title = 'partial'")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(stored.content.as_deref(), Some("only these"));
+        assert_eq!((stored.tenant_id, stored.views), (0, 0));
+
+        assert!(Insert::one_with_columns(&entity, &[]).is_err());
+        assert!(Insert::one_with_columns(&entity, &["missing"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_count_filter() {
+        let pool = create_memory_pool().await;
+        for (title, views) in [("a", 5), ("b", 50), ("c", 500)] {
+            let mut entity = Article::new(1465, title, None);
+            entity.views = views;
+            Insert::one(&entity, &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let mut qb = Select::<Article>::table()
+            .columns(|qb| {
+                push_count_filter(qb, "views > ?", vec![DataKind::Integer(10)], "popular").unwrap();
+                qb.push(", ");
+                push_count_filter(qb, "views BETWEEN ? AND ?", vec![DataKind::Integer(1), DataKind::Integer(100)], "moderate").unwrap();
+            })
+            .filter(|qb| {
+                qb.push("tenant_id = ").push_bind(1465);
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT COUNT(CASE WHEN views > ? THEN 1 END) AS popular, COUNT(CASE WHEN views BETWEEN ? AND ? THEN 1 END) AS moderate FROM article WHERE tenant_id = ?"
+        );
+        let (popular, moderate): (i64, i64) = qb.build_query_as().fetch_one(&pool).await.unwrap();
+        assert_eq!((popular, moderate), (2, 2));
+
+        let mut qb = Select::<Article>::table()
+            .columns(|qb| {
+                push_count_filter(qb, "views > ?", vec![DataKind::Integer(10)], "popular").unwrap();
+            })
+            .filter(|qb| {
+                qb.push("tenant_id = ").push_bind(-1465);
+            })
+            .finish();
+        let popular: i64 = qb.build_query_scalar().fetch_one(&pool).await.unwrap();
+        assert_eq!(popular, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_display_order() {
+        let pool = create_memory_pool().await;
+        for title in ["a", "b", "c", "d", "e"] {
+            Insert::one(&Article::new(1467, title, None), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        // Going back from id 4 queries in descending order
+        let limit = 2;
+        let mut qb = Select::<Article>::table()
+            .cursor("id", Order::Desc, Some(DataKind::Integer(4)), limit).unwrap();
+        let list: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        let mut page = CursorPaginatedResult::<Article, DataKind>::new(list, limit, Order::Desc);
+        page.gen_cursors("id");
+        let (next_cursor, prev_cursor) = (page.next_cursor.clone(), page.prev_cursor.clone());
+
+        page.reorder_for_display(Order::Asc);
+        assert_eq!(page.data.iter().map(|a| a.id).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!((page.next_cursor, page.prev_cursor), (next_cursor, prev_cursor));
+    }
+
+    #[tokio::test]
+    async fn test_update_set_where() {
+        let pool = create_memory_pool().await;
+        let old_date = chrono::NaiveDate::from_ymd_opt(2020, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        for (title, created_at) in [("old", old_date), ("new", chrono::Local::now().naive_local())] {
+            let mut entity = Article::new(1468, title, None);
+            entity.created_at = Some(created_at);
+            Insert::one(&entity, &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let cutoff = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let mut qb = Update::<Article>::set_where("deleted", true.into(), |qb| {
+            qb.push("created_at < ").push_bind(DataKind::from(cutoff));
+        }).unwrap();
+        assert_eq!(qb.sql(), "UPDATE article SET deleted = ? WHERE created_at < ?");
+        let result = qb.build().execute(&pool).await.unwrap();
+        assert_eq!(result.rows_affected(), 1);
+
+        let archived: Vec<String> = sqlx::query_scalar("SELECT title FROM article WHERE deleted = 1")
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(archived, vec!["old"]);
+
+        assert!(Update::<Article>::set_where("deleted", true.into(), |_| {}).is_err());
+    }
+
+    #[cfg(feature = "json-fallback")]
+    #[tokio::test]
+    async fn test_json_fallback() {
+        use field_access::FieldAccess;
+        use serde::{Deserialize, Serialize};
+        use crate::common::conversion::register_json_type;
+
+        #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+        struct Preferences {
+            theme: String,
+            page_size: u32,
+        }
+
+        #[derive(Debug, Default, FieldAccess)]
+        struct Profile {
+            id: i64,
+            prefs: Preferences,
+        }
+
+        register_json_type::<Preferences>();
+        let prefs = Preferences { theme: "dark".to_string(), page_size: 50 };
+        assert_eq!(
+            <DataKind as crate::common::conversion::ValueConvert>::convert(&prefs),
+            DataKind::Json(std::sync::Arc::new(serde_json::to_value(&prefs).unwrap()))
+        );
+
+        let pool = create_memory_pool().await;
+        sqlx::query("CREATE TABLE profile (id INTEGER PRIMARY KEY AUTOINCREMENT, prefs TEXT)")
+            .execute(&pool).await.unwrap();
+        let profile = Profile { id: 0, prefs: prefs.clone() };
+        Insert::one(&profile, &ARTICLE_KEY).unwrap()
+            .build().execute(&pool).await.unwrap();
+
+        let stored: String = sqlx::query_scalar("SELECT prefs FROM profile")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(serde_json::from_str::<Preferences>(&stored).unwrap(), prefs);
+    }
+
+    #[test]
+    fn test_in_subquery_single_column() {
+        use field_access::AnyFieldAccess;
+        use crate::common::error::QueryError;
+
+        let multi = Subquery::<ArticleTag>::table()
+            .columns(|b| {
+                b.push("article_id, tag");
+            });
+        let mut qb = QB::new("SELECT id FROM article WHERE ");
+        let err = multi.append_in_to(&mut qb, "id").unwrap_err();
+        assert!(QueryError::SubqueryColumnCount(2).matches(&err));
+        assert_eq!(qb.sql(), "SELECT id FROM article WHERE ");
+
+        assert_eq!(Subquery::<ArticleTag>::table().projected_columns(), ArticleTag::default().field_names().len());
+
+        let single = Subquery::<ArticleTag>::table()
+            .columns(|b| {
+                b.push("COALESCE(article_id, 0)");
+            })
+            .filter(|b| {
+                b.push("tag = ").push_bind(DataKind::from("rust"));
+            });
+        single.append_in_to(&mut qb, "id").unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id FROM article WHERE id IN (SELECT COALESCE(article_id, 0) FROM article_tag WHERE tag = ?) "
+        );
+    }
+
+    #[tokio::test]
+    async fn test_save() {
+        use crate::common::fields::is_default_pk;
+
+        init_pool().await;
+        let filter_build_fn = |qb: &mut QB| {
+            qb.push("tenant_id = ").push_bind(1471);
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+
+        let mut article = Article::new(1471, "draft", None);
+        assert!(is_default_pk::<_, DataKind>(&article, &ARTICLE_KEY));
+        assert!(save(&article, &ARTICLE_KEY).await.unwrap());
+
+        let saved: Article = fetch_one(Select::<Article>::table()
+            .filter(filter_build_fn)
+            .finish()).await.unwrap();
+        assert_eq!(saved.title, "draft");
+
+        article.id = saved.id;
+        article.title = "published".to_string();
+        assert!(!is_default_pk::<_, DataKind>(&article, &ARTICLE_KEY));
+        assert!(!save(&article, &ARTICLE_KEY).await.unwrap());
+
+        let rows: Vec<Article> = fetch_all(Select::<Article>::table()
+            .filter(filter_build_fn)
+            .finish()).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].title, "published");
+    }
+
+    #[tokio::test]
+    async fn test_column_transformer() {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+        use field_access::FieldAccess;
+        use crate::common::conversion::{decode_columns, register_column_transformer, ColumnTransformer};
+
+        struct Base64;
+
+        impl ColumnTransformer for Base64 {
+            fn encode(&self, value: &str) -> String {
+                STANDARD.encode(value)
+            }
+
+            fn decode(&self, value: &str) -> Option<String> {
+                let bytes = STANDARD.decode(value).ok()?;
+                String::from_utf8(bytes).ok()
+            }
+        }
+
+        #[derive(Debug, Default, FieldAccess, sqlx::FromRow)]
+        struct SecretNote {
+            id: i64,
+            label: String,
+            secret: Option<String>,
+        }
+
+        register_column_transformer("secret_note", "secret", Base64);
+        let pool = create_memory_pool().await;
+        sqlx::query("CREATE TABLE secret_note (id INTEGER PRIMARY KEY AUTOINCREMENT, label TEXT, secret TEXT)")
+            .execute(&pool).await.unwrap();
+        let note = SecretNote { id: 0, label: "pin".to_string(), secret: Some("1234".to_string()) };
+        Insert::one(&note, &ARTICLE_KEY).unwrap()
+            .build().execute(&pool).await.unwrap();
+
+        let (label, stored): (String, String) = sqlx::query_as("SELECT label, secret FROM secret_note")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(label, "pin");
+        assert_eq!(stored, STANDARD.encode("1234"));
+
+        let mut fetched: SecretNote = sqlx::query_as("SELECT * FROM secret_note")
+            .fetch_one(&pool).await.unwrap();
+        decode_columns(&mut fetched);
+        assert_eq!(fetched.secret.as_deref(), Some("1234"));
+        assert_eq!(fetched.label, "pin");
+
+        let update = SecretNote { id: fetched.id, label: "pin".to_string(), secret: Some("5678".to_string()) };
+        Update::one(&update, &ARTICLE_KEY, false).unwrap()
+            .build().execute(&pool).await.unwrap();
+        let stored: String = sqlx::query_scalar("SELECT secret FROM secret_note")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(stored, STANDARD.encode("5678"));
+    }
+
+    #[test]
+    fn test_column_transformer_encodes_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use field_access::FieldAccess;
+        use crate::common::conversion::{register_column_transformer, ColumnTransformer};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Counting;
+
+        impl ColumnTransformer for Counting {
+            fn encode(&self, value: &str) -> String {
+                format!("{}#{}", value, CALLS.fetch_add(1, Ordering::SeqCst))
+            }
+        }
+
+        #[derive(Debug, Default, FieldAccess)]
+        struct CountedNote {
+            id: i64,
+            secret: String,
+        }
+
+        register_column_transformer("counted_note", "secret", Counting);
+        let note = CountedNote { id: 1, secret: "abc".to_string() };
+        let qb = Update::one(&note, &ARTICLE_KEY, false).unwrap();
+        assert_eq!(qb.sql(), "UPDATE counted_note SET secret = ? WHERE id = ?");
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_large() {
+        use crate::common::filter::push_in_large;
+
+        let pool = create_memory_pool().await;
+        for title in ["first", "second", "third"] {
+            Insert::one(&Article::new(1473, title, None), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let ids: Vec<i64> = (2..2002).collect();
+        let mut qb = Select::<Article>::table()
+            .columns(|b| {
+                b.push("COUNT(*)");
+            })
+            .filter(|b| {
+                push_in_large::<_, _, DataKind>(b, "id", ids);
+            })
+            .finish();
+        let sql = qb.sql();
+        assert!(sql.starts_with("SELECT COUNT(*) FROM article WHERE (id IN (?, ?"));
+        assert_eq!(sql.matches("id IN (").count(), 4);
+        assert_eq!(sql.matches(" OR ").count(), 3);
+        assert_eq!(sql.matches('?').count(), 2000);
+
+        let count: i64 = qb.build_query_scalar().fetch_one(&pool).await.unwrap();
+        assert_eq!(count, 2);
+
+        let mut qb = QB::new("SELECT COUNT(*) FROM article WHERE ");
+        push_in_large::<_, i64, DataKind>(&mut qb, "id", vec![]);
+        assert_eq!(qb.sql(), "SELECT COUNT(*) FROM article WHERE 1 = 0");
+        let count: i64 = qb.build_query_scalar().fetch_one(&pool).await.unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_keys() {
+        let pool = create_memory_pool().await;
+        for title in ["first", "second", "third"] {
+            Insert::one(&Article::new(1438, title, None), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let mut qb = Delete::<Article>::table()
+            .by_keys("id", (2..1002).collect::<Vec<i64>>())
+            .finish();
+        let sql = qb.sql();
+        assert!(sql.starts_with("DELETE FROM article WHERE (id IN (?, ?"));
+        assert_eq!(sql.matches("id IN (").count(), 2);
+        assert_eq!(qb.build().execute(&pool).await.unwrap().rows_affected(), 2);
+
+        let mut qb = Select::<Article>::table()
+            .by_keys("id", Vec::<i64>::new())
+            .finish();
+        assert!(qb.sql().ends_with("FROM article WHERE 1 = 0"));
+        let remaining: Vec<Article> = Select::<Article>::table().finish().build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert!(qb.build_query_as::<Article>().fetch_all(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_ddl() {
+        init_pool().await;
+        connection::execute_ddl("
+            DROP TABLE IF EXISTS ddl_note;
+            CREATE TABLE ddl_note (id INTEGER PRIMARY KEY, body TEXT NOT NULL);
+            CREATE INDEX idx_ddl_note_body ON ddl_note (body);
+            INSERT INTO ddl_note (body) VALUES ('hello');
+        ").await.unwrap();
+
+        assert!(table_exists("ddl_note").await.unwrap());
+        let mut qb = QB::new("SELECT COUNT(*) FROM ddl_note WHERE body = ");
+        qb.push_bind(DataKind::from("hello"));
+        assert_eq!(fetch_scalar(qb).await.unwrap(), 1);
+
+        let index: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name = 'idx_ddl_note_body'")
+            .fetch_one(&*connection::get_db_pool().unwrap()).await.unwrap();
+        assert_eq!(index, 1);
+    }
+
+    #[test]
+    fn test_order_by_many() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1475);
+            })
+            .order_by_many(&[("views", Order::Desc), ("created_at", Order::Asc), ("id", Order::Desc)])
+            .order_by("title", Order::Asc)
+            .finish();
+
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? ORDER BY views DESC, created_at ASC, id DESC, title ASC"
+        );
+    }
+
+    #[test]
+    fn test_strict_identifiers() {
+        use crate::common::error::QueryError;
+
+        let qb = Select::<Article>::table()
+            .strict()
+            .group_by("article.tenant_id").unwrap()
+            .order_by("tenant_id", Order::Desc)
+            .try_finish()
+            .unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article GROUP BY article.tenant_id ORDER BY tenant_id DESC"
+        );
+
+        let err = Select::<Article>::table()
+            .strict()
+            .order_by("id; DROP TABLE article; --", Order::Asc)
+            .try_finish()
+            .err()
+            .unwrap();
+        assert!(QueryError::InvalidIdentifier("id; DROP TABLE article; --".to_string()).matches(&err));
+
+        let err = Select::<Article>::with_table("article a")
+            .strict()
+            .paginate(1, 10)
+            .err()
+            .unwrap();
+        assert!(QueryError::InvalidIdentifier("article a".to_string()).matches(&err));
+
+        let err = Select::<Article>::table()
+            .strict()
+            .count_distinct(&["tenant_id", "title || 'x'"])
+            .err()
+            .unwrap();
+        assert!(QueryError::InvalidIdentifier("title || 'x'".to_string()).matches(&err));
+
+        let err = Select::<Article>::table()
+            .strict()
+            .cursor("id) OR (1=1", Order::Asc, None, 10)
+            .err()
+            .unwrap();
+        assert!(QueryError::InvalidIdentifier("id) OR (1=1".to_string()).matches(&err));
+
+        let qb = Select::<Article>::table()
+            .order_by("views DESC, id", Order::Asc)
+            .try_finish()
+            .unwrap();
+        assert!(qb.sql().ends_with("ORDER BY views DESC, id ASC"));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_generated_column() {
+        use field_access::FieldAccess;
+        use crate::common::helper::register_generated_columns;
+
+        #[derive(Debug, Default, FieldAccess)]
+        struct OrderLine {
+            id: i64,
+            qty: i64,
+            price: i64,
+            amount: i64,
+        }
+
+        register_generated_columns("order_line", &["amount"]);
+        let pool = create_memory_pool().await;
+        sqlx::query("CREATE TABLE order_line (id INTEGER PRIMARY KEY, qty INTEGER, price INTEGER, amount INTEGER GENERATED ALWAYS AS (qty * price) STORED)")
+            .execute(&pool).await.unwrap();
+
+        let mut line = OrderLine { id: 1, qty: 2, price: 5, amount: 0 };
+        let mut qb = Upsert::one(&line, &ARTICLE_KEY).unwrap();
+        assert!(!qb.sql().contains("amount"));
+        qb.build().execute(&pool).await.unwrap();
+
+        line.qty = 3;
+        Upsert::one(&line, &ARTICLE_KEY).unwrap()
+            .build().execute(&pool).await.unwrap();
+        let amount: i64 = sqlx::query_scalar("SELECT amount FROM order_line WHERE id = 1")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(amount, 15);
+    }
+
+    #[tokio::test]
+    async fn test_distinct_values() {
+        init_pool().await;
+        let filter_build_fn = |qb: &mut QB| {
+            qb.push("tenant_id = ").push_bind(1478);
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+
+        for (title, deleted) in [("draft", false), ("published", false), ("draft", false), ("archived", true), ("published", false)] {
+            let mut article = Article::new(1478, title, None);
+            article.deleted = deleted;
+            execute(Insert::one(&article, &ARTICLE_KEY).unwrap()).await.unwrap();
+        }
+
+        let qb = Select::<Article>::table()
+            .distinct("title")
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1478).push(" AND deleted = ").push_bind(false);
+            })
+            .order_by("title", Order::Asc)
+            .finish();
+        assert_eq!(qb.sql(), "SELECT DISTINCT title FROM article WHERE tenant_id = ? AND deleted = ? ORDER BY title ASC");
+
+        let titles: Vec<String> = fetch_column(qb).await.unwrap();
+        assert_eq!(titles, vec!["draft", "published"]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_all() {
+        use crate::common::types::BatchMode;
+
+        init_pool().await;
+        let filter_build_fn = |qb: &mut QB| {
+            qb.push("tenant_id = ").push_bind(1479);
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+
+        let articles: Vec<Article> = (1..=5)
+            .map(|i| Article::new(1479, &format!("concurrent {}", i), None))
+            .collect();
+        let builders = articles.iter()
+            .map(|article| Insert::one(article, &ARTICLE_KEY).unwrap())
+            .collect();
+        let results = execute_all(builders, BatchMode::Concurrent(3)).await.unwrap();
+        assert_eq!(results.len(), 5);
+        assert!(results.iter().all(|r| r.rows_affected() == 1));
+
+        let count_all = || Select::<Article>::table()
+            .columns(|qb| {
+                qb.push("COUNT(*)");
+            })
+            .filter(filter_build_fn)
+            .finish();
+        assert_eq!(fetch_scalar(count_all()).await.unwrap(), 5);
+
+        let failing = vec![
+            Insert::one(&articles[0], &ARTICLE_KEY).unwrap(),
+            QB::new("INSERT INTO no_such_table (id) VALUES (1)"),
+        ];
+        assert!(execute_all(failing, BatchMode::Transaction).await.is_err());
+        assert_eq!(fetch_scalar(count_all()).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_having_bind() {
+        use crate::common::error::QueryError;
+
+        let err = Select::<Article>::table()
+            .group_by("tenant_id").unwrap()
+            .having_bind("COUNT(*) > ? AND SUM(views) > ?", DataKind::from(1))
+            .err()
+            .unwrap();
+        assert!(QueryError::PlaceholderMismatch(2, 1).matches(&err));
+
+        let pool = create_memory_pool().await;
+        for (tenant_id, copies) in [(1, 3), (2, 1), (3, 2)] {
+            for _ in 0..copies {
+                Insert::one(&Article::new(tenant_id, "grouped", None), &ARTICLE_KEY).unwrap()
+                    .build().execute(&pool).await.unwrap();
+            }
+        }
+
+        let mut qb = Select::<Article>::table()
+            .columns(|b| {
+                b.push("tenant_id");
+            })
+            .group_by("tenant_id").unwrap()
+            .having_bind("COUNT(*) >= ?", DataKind::from(2)).unwrap()
+            .having_bind("MAX(id) > ?", DataKind::from(0)).unwrap()
+            .order_by("tenant_id", Order::Asc)
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT tenant_id FROM article GROUP BY tenant_id HAVING COUNT(*) >= ? AND MAX(id) > ? ORDER BY tenant_id ASC"
+        );
+        let tenants: Vec<i64> = qb.build_query_scalar().fetch_all(&pool).await.unwrap();
+        assert_eq!(tenants, vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_update_one_checked() {
+        use crate::common::types::UpdateOutcome;
+
+        init_pool().await;
+        let filter_build_fn = |qb: &mut QB| {
+            qb.push("tenant_id = ").push_bind(1482);
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        execute(Insert::one(&Article::new(1482, "original", None), &ARTICLE_KEY).unwrap()).await.unwrap();
+        let mut article: Article = fetch_one(Select::<Article>::table()
+            .filter(filter_build_fn)
+            .finish()).await.unwrap();
+
+        article.title = "changed".to_string();
+        assert_eq!(update_one_checked(&article, &ARTICLE_KEY).await.unwrap(), UpdateOutcome::Updated);
+
+        article.id = i32::MAX;
+        assert_eq!(update_one_checked(&article, &ARTICLE_KEY).await.unwrap(), UpdateOutcome::NotFound);
+
+        let titles: Vec<String> = fetch_column(Select::<Article>::table()
+            .distinct("title")
+            .filter(filter_build_fn)
+            .finish()).await.unwrap();
+        assert_eq!(titles, vec!["changed"]);
+    }
+
+    #[tokio::test]
+    async fn test_random_one() {
+        let pool = create_memory_pool().await;
+        for (tenant_id, title) in [(1, "featured a"), (1, "featured b"), (2, "other")] {
+            Insert::one(&Article::new(tenant_id, title, None), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let random_featured = || Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1);
+            })
+            .random_one();
+        assert_eq!(
+            random_featured().sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? ORDER BY RANDOM() LIMIT 1"
+        );
+        for _ in 0..5 {
+            let article: Article = random_featured().build_query_as().fetch_one(&pool).await.unwrap();
+            assert_eq!(article.tenant_id, 1);
+            assert!(article.title.starts_with("featured"));
+        }
+
+        let none: Option<Article> = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(3);
+            })
+            .random_one()
+            .build_query_as()
+            .fetch_optional(&pool).await.unwrap();
+        assert!(none.is_none());
+    }
+
+    #[test]
+    fn test_subquery_placeholder_check() {
+        use crate::common::error::QueryError;
+
+        let mismatched = Subquery::<ArticleTag>::table()
+            .columns(|b| {
+                b.push("article_id");
+            })
+            .filter(|b| {
+                b.push("tag = ? AND share_seq > ").push_bind(DataKind::from(1));
+            });
+        let err = mismatched.check_placeholders().unwrap_err();
+        assert!(QueryError::PlaceholderMismatch(2, 1).matches(&err));
+
+        let mut qb = QB::new("SELECT id FROM article WHERE id IN");
+        assert!(mismatched.try_append_to(&mut qb).is_err());
+        assert_eq!(qb.sql(), "SELECT id FROM article WHERE id IN");
+
+        let matched = Subquery::<ArticleTag>::table()
+            .columns(|b| {
+                b.push("article_id");
+            })
+            .filter(|b| {
+                b.push("tag <> '?' AND share_seq > ").push_bind(DataKind::from(1));
+            });
+        assert!(matched.check_placeholders().is_ok());
+        matched.try_append_to(&mut qb).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id FROM article WHERE id IN (SELECT article_id FROM article_tag WHERE tag <> '?' AND share_seq > ?) "
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_tree() {
+        use field_access::FieldAccess;
+
+        #[derive(Debug, Default, FieldAccess, sqlx::FromRow)]
+        struct Category {
+            id: i64,
+            parent_id: Option<i64>,
+            name: String,
+        }
+
+        init_pool().await;
+        connection::execute_ddl("
+            DROP TABLE IF EXISTS tree_category;
+            CREATE TABLE tree_category (id INTEGER PRIMARY KEY, parent_id INTEGER, name TEXT NOT NULL);
+            INSERT INTO tree_category (id, parent_id, name) VALUES
+                (1, NULL, 'books'), (2, NULL, 'music'), (3, 1, 'fiction'),
+                (4, 1, 'poetry'), (5, 2, 'jazz'), (6, 3, 'crime');
+        ").await.unwrap();
+
+        let tree: Vec<TreeNode<Category>> = fetch_tree("parent_id", "id", QB::new("SELECT * FROM tree_category ORDER BY id"))
+            .await.unwrap();
+        let names = |nodes: &[TreeNode<Category>]| nodes.iter().map(|n| n.node.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&tree), ["books", "music"]);
+        assert_eq!(names(&tree[0].children), ["fiction", "poetry"]);
+        assert_eq!(names(&tree[0].children[0].children), ["crime"]);
+        assert!(tree[0].children[1].children.is_empty());
+        assert_eq!(names(&tree[1].children), ["jazz"]);
+
+        let subtree: Vec<TreeNode<Category>> = fetch_tree("parent_id", "id", QB::new("SELECT * FROM tree_category WHERE id <> 1 ORDER BY id"))
+            .await.unwrap();
+        assert_eq!(names(&subtree), ["music", "fiction", "poetry"]);
+        assert_eq!(names(&subtree[1].children), ["crime"]);
+
+        let category = |id: i64, parent_id: i64, name: &str| Category { id, parent_id: Some(parent_id), name: name.to_string() };
+        let rows = vec![category(1, 0, "root"), category(7, 8, "loop-a"), category(8, 7, "loop-b"), category(9, 8, "leaf")];
+        let forest = TreeNode::build::<DataKind>(rows, "parent_id", "id");
+        assert_eq!(names(&forest), ["root", "loop-a"]);
+        assert_eq!(names(&forest[1].children), ["loop-b"]);
+        assert_eq!(names(&forest[1].children[0].children), ["leaf"]);
+    }
+
+    #[test]
+    fn test_batch_extract_alignment() {
+        use crate::common::fields::batch_extract;
+
+        let mut first = Article::new(1487, "first", None);
+        first.created_at = None;
+        let second = Article::new(1487, "second", Some("body".to_string()));
+
+        let (names, rows) = batch_extract::<_, DataKind>(&[&first, &second], &["id"], true);
+        assert!(!names.contains(&"id"));
+        assert_eq!(names.iter().filter(|name| **name == "content").count(), 1);
+        assert!(rows.iter().all(|row| row.len() == names.len()));
+
+        let column = |name: &str| names.iter().position(|n| *n == name).unwrap();
+        assert_eq!(rows[0][column("title")], DataKind::from("first"));
+        assert_eq!(rows[1][column("title")], DataKind::from("second"));
+        assert_eq!(rows[0][column("content")], DataKind::Null);
+        assert_eq!(rows[1][column("content")], DataKind::from("body"));
+        assert_eq!(rows[0][column("created_at")], DataKind::Null);
+        assert_eq!(rows[1][column("created_at")], DataKind::DateTime(second.created_at.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_json_array_append() {
+        let pool = create_memory_pool().await;
+        sqlx::query("CREATE TABLE json_doc (id INTEGER PRIMARY KEY, tags TEXT NOT NULL)")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO json_doc (id, tags) VALUES (1, '[\"a\"]'), (2, '[]')")
+            .execute(&pool).await.unwrap();
+
+        let mut qb = Update::<Article>::with_table("json_doc")
+            .json_array_append("tags", DataKind::from("b"))
+            .filter(|b| {
+                b.push("id = ").push_bind(DataKind::from(1));
+            })
+            .finish();
+        assert_eq!(qb.sql(), "UPDATE json_doc SET tags = json_insert(tags, '$[#]', ?) WHERE id = ?");
+        qb.build().execute(&pool).await.unwrap();
+
+        let tags: Vec<String> = sqlx::query_scalar("SELECT tags FROM json_doc ORDER BY id")
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(tags, [r#"["a","b"]"#, "[]"]);
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_keep_latest() {
+        let pool = create_memory_pool().await;
+        let base = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        for (title, day, content) in [("a", 1, "a-old"), ("a", 3, "a-new"), ("a", 2, "a-mid"), ("b", 5, "b-only"), ("c", 1, "c-old"), ("c", 4, "c-new")] {
+            let mut article = Article::new(1489, title, Some(content.to_string()));
+            article.created_at = Some(base + chrono::Duration::days(day));
+            Insert::one(&article, &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let mut qb = Delete::<Article>::dedupe_keep_latest("id", &["tenant_id", "title"], "created_at").unwrap();
+        assert_eq!(
+            qb.sql(),
+            "DELETE FROM article WHERE id IN (SELECT id FROM (SELECT id, ROW_NUMBER() OVER (PARTITION BY tenant_id, title ORDER BY created_at DESC, id DESC) AS row_num FROM article) AS ranked WHERE row_num > 1)"
+        );
+        let result = qb.build().execute(&pool).await.unwrap();
+        assert_eq!(result.rows_affected(), 3);
+
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT content FROM article WHERE tenant_id = 1489 ORDER BY title")
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(remaining, ["a-new", "b-only", "c-new"]);
+
+        assert!(Delete::<Article>::dedupe_keep_latest("id", &[], "created_at").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_builders_are_send() {
+        use std::rc::Rc;
+        use field_access::FieldAccess;
+
+        fn assert_send<T: Send + 'static>(_: &T) {}
+
+        #[derive(Debug, Default, FieldAccess)]
+        struct LocalOnly {
+            id: i64,
+            shared: Rc<String>,
+        }
+
+        assert_send(&Select::<LocalOnly>::table());
+        assert_send(&Update::<LocalOnly>::table());
+        assert_send(&Delete::<LocalOnly>::table());
+        assert_send(&Insert::<LocalOnly>::table());
+        assert_send(&Subquery::<LocalOnly>::table());
+
+        init_pool().await;
+        execute(Delete::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1490));
+            })
+            .finish()).await.unwrap();
+        execute(Insert::one(&Article::new(1490, "spawned", None), &ARTICLE_KEY).unwrap()).await.unwrap();
+
+        let query = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1490));
+            })
+            .finish();
+        let rows: Vec<Article> = tokio::spawn(fetch_all(query)).await.unwrap().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].title, "spawned");
+
+        let count = tokio::spawn(async move {
+            let builder = Select::<Article>::table()
+                .columns(|b| {
+                    b.push("COUNT(*)");
+                })
+                .filter(|b| {
+                    b.push("tenant_id = ").push_bind(DataKind::from(1490));
+                });
+            tokio::task::yield_now().await;
+            fetch_scalar(builder.finish()).await
+        }).await.unwrap().unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_order_by_field() {
+        let pool = create_memory_pool().await;
+        for title in ["low", "urgent", "other", "high"] {
+            Insert::one(&Article::new(1492, title, None), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let mut qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1492));
+            })
+            .order_by_field("title", vec!["urgent", "high", "low"])
+            .order_by("id", Order::Asc)
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? ORDER BY CASE title WHEN ? THEN 1 WHEN ? THEN 2 WHEN ? THEN 3 ELSE 0 END, id ASC"
+        );
+        let rows: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        let titles: Vec<_> = rows.iter().map(|row| row.title.as_str()).collect();
+        assert_eq!(titles, ["other", "urgent", "high", "low"]);
+
+        let mut qb = Select::<Article>::table()
+            .order_by_field("title", vec!["urgent", "high", "low"])
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1492));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? ORDER BY CASE title WHEN ? THEN 1 WHEN ? THEN 2 WHEN ? THEN 3 ELSE 0 END"
+        );
+        let rows: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(rows.last().map(|row| row.title.as_str()), Some("low"));
+
+        let qb = Select::<Article>::table()
+            .order_by_field::<&str>("title", vec![])
+            .finish();
+        assert_eq!(qb.sql(), "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_sharded() {
+        use crate::common::error::QueryError;
+
+        for (shard, titles) in [("shard_b", ["b1", "b2"]), ("shard_a", ["a1", "a2"])] {
+            let pool = create_memory_pool().await;
+            for title in titles {
+                Insert::one(&Article::new(1493, title, None), &ARTICLE_KEY).unwrap()
+                    .build().execute(&pool).await.unwrap();
+            }
+            connection::register_named_pool(shard, pool);
+        }
+
+        let rows: Vec<Article> = fetch_all_sharded(|| {
+            Select::<Article>::table()
+                .filter(|b| {
+                    b.push("tenant_id = ").push_bind(DataKind::from(1493));
+                })
+                .order_by("title", Order::Asc)
+                .finish()
+        }).await.unwrap();
+        let titles: Vec<_> = rows.iter().map(|row| row.title.as_str()).collect();
+        assert_eq!(titles, ["a1", "a2", "b1", "b2"]);
+
+        assert!(connection::get_named_pool("shard_a").is_ok());
+        assert!(connection::unregister_named_pool("shard_a").is_some());
+        assert!(connection::unregister_named_pool("shard_b").is_some());
+        let err = fetch_all_sharded::<Article, _>(|| Select::<Article>::table().finish()).await.unwrap_err();
+        assert!(QueryError::DBPoolNotInitialized.matches(&err));
+    }
+
+    #[tokio::test]
+    async fn test_columns_coalesce() {
+        use crate::common::error::QueryError;
+
+        let pool = create_memory_pool().await;
+        for (title, content) in [("first", None), ("second", Some("body"))] {
+            Insert::one(&Article::new(1494, title, content.map(str::to_string)), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+        let filter_build_fn = |b: &mut QB| {
+            b.push("tenant_id = ").push_bind(DataKind::from(1494));
+        };
+
+        let mut qb = Select::<Article>::table()
+            .columns_coalesce(&[("content", "title", "content")]).unwrap()
+            .filter(filter_build_fn)
+            .order_by("id", Order::Asc)
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, views, deleted, created_at, COALESCE(content, title) AS content FROM article WHERE tenant_id = ? ORDER BY id ASC"
+        );
+        let rows: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(rows[0].content.as_deref(), Some("first"));
+        assert_eq!(rows[1].content.as_deref(), Some("body"));
+
+        let mut qb = Select::<Article>::table()
+            .columns_coalesce(&[("content", "'it''s empty'", "content")]).unwrap()
+            .filter(filter_build_fn)
+            .order_by("id", Order::Asc)
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, views, deleted, created_at, COALESCE(content, ?) AS content FROM article WHERE tenant_id = ? ORDER BY id ASC"
+        );
+        let rows: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(rows[0].content.as_deref(), Some("it's empty"));
+        assert_eq!(rows[1].content.as_deref(), Some("body"));
+
+        for fallback in ["'", "'abc", "'a'b'", "it's"] {
+            let err = Select::<Article>::table()
+                .columns_coalesce(&[("content", fallback, "content")])
+                .err().unwrap();
+            assert!(QueryError::ValueInvalid(fallback.to_string()).matches(&err));
+        }
+        let err = Select::<Article>::table()
+            .strict()
+            .columns_coalesce(&[("content", "title || content", "content")]).unwrap()
+            .try_finish()
+            .err().unwrap();
+        assert!(QueryError::InvalidIdentifier("title || content".to_string()).matches(&err));
+    }
+
+    #[tokio::test]
+    async fn test_export_ndjson() {
+        init_pool().await;
+        let filter_build_fn = |b: &mut QB| {
+            b.push("tenant_id = ").push_bind(DataKind::from(1498));
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        for title in ["one", "two", "three"] {
+            execute(Insert::one(&Article::new(1498, title, None), &ARTICLE_KEY).unwrap()).await.unwrap();
+        }
+
+        let mut out = Vec::new();
+        let written = export_ndjson::<Article, _>(Select::<Article>::table()
+            .filter(filter_build_fn)
+            .order_by("id", Order::Asc)
+            .finish(), &mut out).await.unwrap();
+        assert_eq!(written, 3);
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.ends_with('\n'));
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let titles: Vec<String> = lines.iter()
+            .map(|line| {
+                let value: serde_json::Value = serde_json::from_str(line).unwrap();
+                assert_eq!(value["tenant_id"], 1498);
+                value["title"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(titles, ["one", "two", "three"]);
+    }
+
+    #[tokio::test]
+    async fn test_select_star() {
+        let pool = create_memory_pool().await;
+        for title in ["first", "second"] {
+            Insert::one(&Article::new(1499, title, Some(format!("{} body", title))), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+        let filter_build_fn = |b: &mut QB| {
+            b.push("tenant_id = ").push_bind(DataKind::from(1499));
+        };
+
+        let mut named = Select::<Article>::table()
+            .filter(filter_build_fn)
+            .order_by("id", Order::Asc)
+            .finish();
+        let mut star = Select::<Article>::table()
+            .star()
+            .filter(filter_build_fn)
+            .order_by("id", Order::Asc)
+            .finish();
+        assert_eq!(
+            named.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? ORDER BY id ASC"
+        );
+        assert_eq!(star.sql(), "SELECT * FROM article WHERE tenant_id = ? ORDER BY id ASC");
+
+        let named_rows: Vec<Article> = named.build_query_as().fetch_all(&pool).await.unwrap();
+        let star_rows: Vec<Article> = star.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(named_rows.len(), 2);
+        assert_eq!(named_rows, star_rows);
+    }
+
+    #[test]
+    fn test_filter_tree() {
+        let tree = FilterTree::And(vec![
+            FilterTree::Or(vec![
+                FilterTree::leaf("views > ?", vec![DataKind::from(10)]),
+                FilterTree::leaf("title LIKE ?", vec![DataKind::from("%rust%")]),
+            ]),
+            FilterTree::leaf("tenant_id = ?", vec![DataKind::from(1)]),
+        ]);
+        let (sql, values) = tree.clone().compile();
+        assert_eq!(sql, "(((views > ?) OR (title LIKE ?)) AND (tenant_id = ?))");
+        assert_eq!(values, vec![DataKind::from(10), DataKind::from("%rust%"), DataKind::from(1)]);
+
+        let qb = Select::<Article>::table()
+            .filter(|b| push_filter_tree(b, tree).unwrap())
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE (((views > ?) OR (title LIKE ?)) AND (tenant_id = ?))"
+        );
+
+        let (sql, values) = FilterTree::<DataKind>::Or(vec![]).compile();
+        assert_eq!(sql, "1 = 0");
+        assert!(values.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ilike() {
+        let qb = Select::<Article>::table()
+            .filter(|b| push_ilike(b, "title", DataKind::from("%RUST%")))
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE LOWER(title) LIKE LOWER(?)"
+        );
+
+        let pool = create_memory_pool().await;
+        for title in ["Learning Rust", "rustacean", "Go basics"] {
+            let entity = Article::new(1, title, None);
+            Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+        }
+        let mut qb = Select::<Article>::table()
+            .filter(|b| push_ilike(b, "title", DataKind::from("%RUST%")))
+            .order_by("id", Order::Asc)
+            .finish();
+        let rows: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        let titles: Vec<_> = rows.into_iter().map(|a| a.title).collect();
+        assert_eq!(titles, vec!["Learning Rust", "rustacean"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_using() {
+        let pool = create_memory_pool().await;
+        let tag_key = PrimaryKey::Composite(&["article_id", "share_seq"]);
+        for (id, title) in [(1, "spam"), (2, "kept")] {
+            let entity = Article::new(1, title, None);
+            Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+            let mut tag = ArticleTag::new("rust");
+            tag.article_id = id;
+            Insert::one(&tag, &tag_key).unwrap().build().execute(&pool).await.unwrap();
+        }
+
+        let mut qb = Delete::<ArticleTag>::using("article", |b| {
+                b.push("article.id = article_tag.article_id AND article.title = ").push_bind(DataKind::from("spam"));
+            })
+            .filter(|b| {
+                b.push("article_tag.tag = ").push_bind(DataKind::from("rust"));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "DELETE FROM article_tag WHERE EXISTS (SELECT 1 FROM article WHERE article.id = article_tag.article_id AND article.title = ?) AND article_tag.tag = ?"
+        );
+        assert_eq!(qb.build().execute(&pool).await.unwrap().rows_affected(), 1);
+
+        let remaining: Vec<i32> = sqlx::query_scalar("SELECT article_id FROM article_tag")
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(remaining, vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_get_changed_since() {
+        init_pool().await;
+        let qb = Delete::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1502);
+            })
+            .finish();
+        execute(qb).await.unwrap();
+
+        let base = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        for (title, hours) in [("old", 1), ("newer", 3), ("newest", 5), ("middle", 2)] {
+            let mut entity = Article::new(1502, title, None);
+            entity.created_at = Some(base + chrono::Duration::hours(hours));
+            execute(Insert::one(&entity, &ARTICLE_KEY).unwrap()).await.unwrap();
+        }
+
+        let since = DataKind::from(base + chrono::Duration::hours(2));
+        let rows: Vec<Article> = get_changed_since("created_at", since, |b| {
+            b.push(" AND tenant_id = ").push_bind(1502);
+        }).await.unwrap();
+        let titles: Vec<_> = rows.into_iter().map(|a| a.title).collect();
+        assert_eq!(titles, vec!["newer", "newest"]);
+    }
+
+    #[tokio::test]
+    async fn test_not_like() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                push_not_like(b, "title", DataKind::from("%draft%"));
+                b.push(" AND ");
+                push_not_ilike(b, "content", DataKind::from("%SPAM%"));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE title NOT LIKE ? AND LOWER(content) NOT LIKE LOWER(?)"
+        );
+
+        let pool = create_memory_pool().await;
+        for title in ["Release notes", "draft post", "Roadmap"] {
+            let entity = Article::new(1, title, None);
+            Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+        }
+        let mut qb = Select::<Article>::table()
+            .filter(|b| push_not_like(b, "title", DataKind::from("%draft%")))
+            .order_by("id", Order::Asc)
+            .finish();
+        let rows: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        let titles: Vec<_> = rows.into_iter().map(|a| a.title).collect();
+        assert_eq!(titles, vec!["Release notes", "Roadmap"]);
+    }
+
+    #[test]
+    fn test_lock_mode_is_noop() {
+        let qb = Select::<Article>::table()
+            .lock(LockMode::Update)
+            .order_by("id", Order::Asc)
+            .paginate(1, 10).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article ORDER BY id ASC LIMIT ? OFFSET ?"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_interceptor() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use crate::common::helper::{clear_interceptors, register_interceptor};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        register_interceptor(|sql: String, backend: &str| {
+            if !sql.contains("AS interceptor_probe") {
+                return sql;
+            }
+            assert_eq!(backend, "SQLite");
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            sql.replace("AS interceptor_probe", "+ 1 AS interceptor_probe") + " /* intercepted */"
+        });
+
+        init_pool().await;
+        let mut qb = QB::new("SELECT ");
+        qb.push_bind(41).push(" AS interceptor_probe");
+        let value = fetch_scalar(qb).await.unwrap();
+        clear_interceptors();
+
+        assert_eq!(value, 42);
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_multi() {
+        use crate::common::error::QueryError;
+
+        let pool = create_memory_pool().await;
+        for views in [3, 1, 3, 2, 1, 3, 2] {
+            let mut entity = Article::new(1, "keyset", None);
+            entity.views = views;
+            Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+        }
+
+        for keys in [
+            [("views", Order::Asc), ("id", Order::Asc)],
+            [("views", Order::Desc), ("id", Order::Asc)],
+        ] {
+            let mut all = Select::<Article>::table()
+                .order_by(keys[0].0, keys[0].1.clone())
+                .order_by(keys[1].0, keys[1].1.clone())
+                .finish();
+            let expected: Vec<i32> = all.build_query_as::<Article>().fetch_all(&pool).await.unwrap()
+                .into_iter().map(|a| a.id).collect();
+
+            let mut visited = Vec::new();
+            let mut cursor: Option<Vec<DataKind>> = None;
+            loop {
+                let mut qb = Select::<Article>::table()
+                    .cursor_multi(&keys, cursor.take(), 2)
+                    .unwrap();
+                let page = qb.build_query_as::<Article>().fetch_all(&pool).await.unwrap();
+                if let Some(last) = page.last() {
+                    cursor = Some(vec![DataKind::from(last.views), DataKind::from(last.id)]);
+                }
+                let len = page.len();
+                visited.extend(page.into_iter().map(|a| a.id));
+                if len < 2 {
+                    break;
+                }
+            }
+            assert_eq!(visited, expected);
+        }
+
+        let err = Select::<Article>::table()
+            .cursor_multi(&[("views", Order::Asc), ("id", Order::Asc)], Some(vec![DataKind::from(1)]), 2)
+            .err().unwrap();
+        assert!(QueryError::PlaceholderMismatch(2, 1).matches(&err));
+    }
+
+    #[test]
+    fn test_distinct_on_unsupported() {
+        use crate::common::error::QueryError;
+
+        let err = Select::<Article>::table().distinct_on(&["tenant_id"]).err().unwrap();
+        assert!(QueryError::UnsupportedSyntax("DISTINCT ON".to_string()).matches(&err));
+    }
+
+    #[test]
+    fn test_query_error_matches_variant() {
+        use crate::common::error::QueryError;
+
+        let err: sqlx::Error = QueryError::LockNotAvailable.into();
+        assert!(QueryError::LockNotAvailable.matches(&err));
+        assert!(!QueryError::Other("Row lock not available".to_string()).matches(&err));
+
+        let err: sqlx::Error = QueryError::Other("Row lock not available".to_string()).into();
+        assert!(!QueryError::LockNotAvailable.matches(&err));
+        assert!(!QueryError::PlaceholderMismatch(2, 1).matches(&QueryError::PlaceholderMismatch(1, 2).into()));
+    }
+
+    #[test]
+    fn test_eq_cast() {
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                push_eq_cast(b, "views", DataKind::from("10"), "INTEGER");
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE views = CAST(? AS INTEGER)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_many_case() {
+        let pool = create_memory_pool().await;
+        let entities: Vec<Article> = (0..50)
+            .map(|i| Article::new(1506, &format!("article {}", i), None))
+            .collect();
+        Insert::many(&entities, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+
+        let mut qb = Select::<Article>::table().order_by("id", Order::Asc).finish();
+        let mut articles: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(articles.len(), 50);
+        for article in articles.iter_mut() {
+            article.title = format!("renamed {}", article.id);
+            article.views = article.id * 10;
+        }
+
+        let mut qb = Update::many(&articles, &ARTICLE_KEY).unwrap();
+        assert!(qb.sql().starts_with("UPDATE article SET tenant_id = CASE id WHEN ? THEN ? WHEN ? THEN ?"));
+        assert!(qb.sql().ends_with("ELSE created_at END WHERE id IN (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"));
+        let result = qb.build().execute(&pool).await.unwrap();
+        assert_eq!(result.rows_affected(), 50);
+
+        let mut qb = Select::<Article>::table().order_by("id", Order::Asc).finish();
+        let updated: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        for article in updated {
+            assert_eq!(article.title, format!("renamed {}", article.id));
+            assert_eq!(article.views, article.id * 10);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_one_returning() {
+        init_pool().await;
+        execute(Delete::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1507));
+            })
+            .finish()).await.unwrap();
+
+        let entity = Article::new(1507, "returning", None);
+        let inserted = insert_one_returning(&entity, &ARTICLE_KEY).await.unwrap();
+        assert!(inserted.id > 0);
+        assert_eq!(inserted.title, "returning");
+
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("id = ").push_bind(DataKind::from(inserted.id));
+            })
+            .finish();
+        let stored: Article = fetch_one(qb).await.unwrap();
+        assert_eq!(stored.tenant_id, 1507);
+    }
+
+    #[test]
+    fn test_cursor_order() {
+        use crate::common::error::QueryError;
+
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1);
+            })
+            .cursor("id", Order::Desc, Some(DataKind::Integer(20)), 10).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? AND id < ? ORDER BY id DESC LIMIT ?"
+        );
+
+        let err = Select::<Article>::table()
+            .order_by("title", Order::Asc)
+            .cursor("id", Order::Asc, None, 10)
+            .err().unwrap();
+        assert!(QueryError::CursorOrderConflict("title ASC".to_string()).matches(&err));
+
+        let err = Select::<Article>::table()
+            .order_by("id", Order::Asc)
+            .cursor_multi(&[("created_at", Order::Desc), ("id", Order::Desc)], None, 10)
+            .err().unwrap();
+        assert!(QueryError::CursorOrderConflict("id ASC".to_string()).matches(&err));
+
+        let qb = Select::<Article>::table()
+            .order_by("id", Order::Asc)
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1);
+            })
+            .cursor("id", Order::Asc, Some(DataKind::Integer(20)), 10).unwrap();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE tenant_id = ? AND id > ? ORDER BY id ASC LIMIT ?"
+        );
+
+        let qb = Select::<Article>::table()
+            .order_by("created_at", Order::Desc)
+            .cursor_multi(&[("created_at", Order::Desc), ("id", Order::Desc)], None, 10).unwrap();
+        assert!(qb.sql().ends_with(" ORDER BY created_at DESC, id DESC LIMIT ?"));
+
+        let err = Select::<Article>::table()
+            .order_by("id", Order::Desc)
+            .cursor("id", Order::Asc, None, 10)
+            .err().unwrap();
+        assert!(QueryError::CursorOrderConflict("id DESC".to_string()).matches(&err));
+    }
+
+    #[tokio::test]
+    async fn test_filter_tree_combine() {
+        let tree = FilterTree::leaf("substr(title, 1, ?) = ?", vec![DataKind::from(4), DataKind::from("Rust")])
+            .and(FilterTree::leaf("views >= ?", vec![DataKind::from(5)]))
+            .and(FilterTree::leaf("tenant_id = ?", vec![DataKind::from(1508)])
+                .or(FilterTree::leaf("tenant_id = ?", vec![DataKind::from(0)])));
+        let (sql, values) = tree.clone().compile();
+        assert_eq!(sql, "((substr(title, 1, ?) = ?) AND (views >= ?) AND ((tenant_id = ?) OR (tenant_id = ?)))");
+        assert_eq!(values, vec![DataKind::from(4), DataKind::from("Rust"), DataKind::from(5), DataKind::from(1508), DataKind::from(0)]);
+
+        let pool = create_memory_pool().await;
+        for (title, views) in [("Rust in action", 9), ("Rust basics", 1), ("Go in action", 9)] {
+            let mut entity = Article::new(1508, title, None);
+            entity.views = views;
+            Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+        }
+        let mut qb = Select::<Article>::table()
+            .filter(|b| push_filter_tree(b, tree).unwrap())
+            .finish();
+        let rows: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        let titles: Vec<_> = rows.into_iter().map(|a| a.title).collect();
+        assert_eq!(titles, vec!["Rust in action"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_with_cte() {
+        let pool = create_memory_pool().await;
+        for (title, views) in [("stale", 1), ("fresh", 9), ("borderline", 5)] {
+            let mut entity = Article::new(1508, title, None);
+            entity.views = views;
+            Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+        }
+
+        let doomed = Subquery::<Article>::table()
+            .columns(|b| {
+                b.push("id");
+            })
+            .filter(|b| {
+                b.push("views < ").push_bind(DataKind::from(5));
+            });
+        let mut qb = Delete::<Article>::with_cte("doomed", doomed)
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1508))
+                    .push(" AND id IN (SELECT id FROM doomed)");
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "WITH doomed AS (SELECT id FROM article WHERE views < ?) DELETE FROM article WHERE tenant_id = ? AND id IN (SELECT id FROM doomed)"
+        );
+        let result = qb.build().execute(&pool).await.unwrap();
+        assert_eq!(result.rows_affected(), 1);
+
+        let mut qb = Select::<Article>::table().order_by("id", Order::Asc).finish();
+        let rows: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        let titles: Vec<_> = rows.into_iter().map(|a| a.title).collect();
+        assert_eq!(titles, vec!["fresh", "borderline"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_or_insert_by() {
+        init_pool().await;
+        execute(Delete::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1509);
+            })
+            .finish()).await.unwrap();
+
+        let mut entity = Article::new(1509, "natural key 1509", None);
+        entity.views = 1;
+        assert!(update_or_insert_by("title", &entity, &ARTICLE_KEY).await.unwrap());
+
+        entity.views = 2;
+        entity.content = Some("updated".to_string());
+        assert!(!update_or_insert_by("title", &entity, &ARTICLE_KEY).await.unwrap());
+
+        let qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1509);
+            })
+            .finish();
+        let rows = fetch_all::<Article>(qb).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].views, 2);
+        assert_eq!(rows[0].content.as_deref(), Some("updated"));
+    }
+
+    #[tokio::test]
+    async fn test_insert_many_or_ignore() {
+        let pool = create_memory_pool().await;
+        let mut entity = Article::new(1509, "first", None);
+        entity.id = 7;
+        let key = PrimaryKey::Single("id", false);
+        let result = Insert::many_or_ignore([&entity], &key, &["id"]).unwrap().build().execute(&pool).await.unwrap();
+        assert_eq!(result.rows_affected(), 1);
+
+        entity.title = "duplicate".to_string();
+        let mut qb = Insert::many_or_ignore([&entity], &key, &[]).unwrap();
+        assert!(qb.sql().ends_with(" ON CONFLICT DO NOTHING"));
+        let result = qb.build().execute(&pool).await.unwrap();
+        assert_eq!(result.rows_affected(), 0);
+
+        let mut qb = Select::<Article>::table().finish();
+        let rows: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].title, "first");
+    }
+
+    #[tokio::test]
+    async fn test_returning_columns() {
+        use sqlx::Row;
+        use crate::common::error::QueryError;
+
+        let pool = create_memory_pool().await;
+        let entity = Article::new(1510, "wide row", Some("a long body".to_string()));
+        Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+
+        let mut qb = Update::<Article>::table()
+            .custom(|b| {
+                b.push("views = views + 1");
+            })
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1510);
+            })
+            .try_returning(&["id", "views"]).unwrap()
+            .finish();
+        assert!(qb.sql().ends_with(" RETURNING id, views"));
+        let rows = qb.build().fetch_all(&pool).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].len(), 2);
+        assert_eq!(rows[0].get::<i32, _>("views"), 1);
+
+        let err = Delete::<Article>::table().try_returning(&["id", "titel"]).err().unwrap();
+        assert!(QueryError::ValueInvalid("titel".to_string()).matches(&err));
+        assert!(Delete::<Article>::table().try_returning(&[]).is_err());
+
+        let qb = Delete::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(1510);
+            })
+            .returning(Vec::<&str>::new())
+            .returning_none()
+            .finish();
+        assert_eq!(qb.sql(), "DELETE FROM article WHERE tenant_id = ?");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stream() {
+        use futures_util::TryStreamExt;
+
+        init_pool().await;
+        let filter_build_fn = |b: &mut QB| {
+            b.push("tenant_id = ").push_bind(DataKind::from(1511));
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        let entities: Vec<Article> = (0..300).map(|i| Article::new(1511, &format!("row{}", i), None)).collect();
+        execute(Insert::many(&entities, &ARTICLE_KEY).unwrap()).await.unwrap();
+
+        let count = fetch_stream::<Article>(Select::<Article>::table().filter(filter_build_fn).finish())
+            .try_fold(0, |count, article| async move {
+                assert_eq!(article.tenant_id, 1511);
+                Ok(count + 1)
+            })
+            .await
+            .unwrap();
+        assert_eq!(count, 300);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stream_panic() {
+        use futures_util::StreamExt;
+
+        struct Exploding;
+
+        impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for Exploding {
+            fn from_row(_: &'r sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
+                panic!("decoding failed");
+            }
+        }
+
+        init_pool().await;
+        let items: Vec<_> = fetch_stream::<Exploding>(QB::new("SELECT 1")).collect().await;
+        assert_eq!(items.len(), 1);
+        assert!(matches!(items[0], Err(sqlx::Error::WorkerCrashed)));
+    }
+
+    #[tokio::test]
+    async fn test_group_concat() {
+        let pool = create_memory_pool().await;
+        let result = Insert::one(&Article::new(1512, "tagged", None), &ARTICLE_KEY).unwrap()
+            .build().execute(&pool).await.unwrap();
+        let article_id = result.last_insert_rowid() as i32;
+        for (seq, tag) in ["rust", "sql", "go"].iter().enumerate() {
+            let mut article_tag = ArticleTag::new(tag);
+            article_tag.article_id = article_id;
+            article_tag.share_seq = seq as i32 + 1;
+            Insert::one(&article_tag, &PrimaryKey::Composite(&["article_id", "share_seq"])).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let mut qb = Select::<ArticleTag>::table()
+            .columns(|qb| {
+                qb.push("article_id, ");
+                push_group_concat::<_, DataKind>(qb, "tag", " | ", "tags");
+            })
+            .group_by("article_id").unwrap()
+            .finish();
+        assert_eq!(qb.sql(), "SELECT article_id, GROUP_CONCAT(tag, ?) AS tags FROM article_tag GROUP BY article_id");
+        let (id, tags): (i32, String) = qb.build_query_as().fetch_one(&pool).await.unwrap();
+        assert_eq!(id, article_id);
+        let mut tags: Vec<&str> = tags.split(" | ").collect();
+        tags.sort();
+        assert_eq!(tags, ["go", "rust", "sql"]);
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_integer_state() {
+        use crate::common::types::SoftDelete;
+
+        let pool = create_memory_pool().await;
+        let mut ids = Vec::new();
+        for title in ["kept", "removed"] {
+            let result = Insert::one(&Article::new(1512, title, None), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+            ids.push(result.last_insert_rowid());
+        }
+
+        let state = SoftDelete::new("deleted", "= 0", DataKind::Integer(1));
+        let mut qb = Delete::<Article>::soft(&state)
+            .filter(|b| {
+                b.push("id = ").push_bind(DataKind::Integer(ids[1]));
+            })
+            .finish();
+        assert_eq!(qb.sql(), "UPDATE article SET deleted = ? WHERE id = ?");
+        assert_eq!(qb.build().execute(&pool).await.unwrap().rows_affected(), 1);
+
+        let mut qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push(state.live_condition());
+            })
+            .finish();
+        assert_eq!(qb.sql(), "SELECT id, tenant_id, title, content, views, deleted, created_at FROM article WHERE deleted = 0");
+        let live: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].title, "kept");
+
+        let flag = SoftDelete::<DataKind>::flag("deleted");
+        assert_eq!(flag.live_condition(), "deleted = FALSE");
+        assert_eq!(flag.deleted_value, DataKind::Bool(true));
+    }
+
+    #[tokio::test]
+    async fn test_batch_loader() {
+        use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+        use futures_util::future::try_join_all;
+        use crate::common::{filter::push_in_large, loader::BatchLoader};
+
+        init_pool().await;
+        let filter_build_fn = |b: &mut QB| {
+            b.push("tenant_id = ").push_bind(DataKind::from(1513));
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        let entities: Vec<Article> = (0..10).map(|i| Article::new(1513, &format!("row{}", i), None)).collect();
+        execute(Insert::many(&entities, &ARTICLE_KEY).unwrap()).await.unwrap();
+        let ids: Vec<i32> = fetch_all::<Article>(Select::<Article>::table().filter(filter_build_fn).finish())
+            .await.unwrap()
+            .iter().map(|article| article.id).collect();
+        assert_eq!(ids.len(), 10);
+
+        let queries = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&queries);
+        let loader = BatchLoader::new(
+            move |keys: Vec<i32>| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                fetch_all::<Article>(Select::<Article>::table()
+                    .filter(|b| {
+                        push_in_large::<_, _, DataKind>(b, "id", keys);
+                    })
+                    .finish())
+            },
+            |article: &Article| article.id,
+        );
+
+        let loaded = try_join_all(ids.iter().map(|id| loader.load(*id))).await.unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+        for (id, article) in ids.iter().zip(&loaded) {
+            assert_eq!(article.as_ref().map(|article| article.id), Some(*id));
+        }
+
+        assert_eq!(loader.load(-1).await.unwrap(), None);
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_loader_error() {
+        use futures_util::future::join_all;
+        use sqlx::Error;
+        use crate::common::{error::QueryError, loader::BatchLoader};
+
+        let loader = BatchLoader::new(
+            |_: Vec<i32>| async { Err::<Vec<Article>, _>(Error::RowNotFound) },
+            |article: &Article| article.id,
+        );
+        let results = join_all((1..=3).map(|id| loader.load(id))).await;
+        assert!(results.iter().all(|result| matches!(result, Err(Error::RowNotFound))));
+
+        let loader = BatchLoader::new(
+            |_: Vec<i32>| async { Err::<Vec<Article>, Error>(QueryError::NoPrimaryKeyDefined.into()) },
+            |article: &Article| article.id,
+        );
+        let results = join_all((1..=3).map(|id| loader.load(id))).await;
+        for result in results {
+            assert!(QueryError::NoPrimaryKeyDefined.matches(&result.unwrap_err()));
+        }
+
+        let pool = create_memory_pool().await;
+        let loader = BatchLoader::new(
+            move |_: Vec<i32>| {
+                let pool = pool.clone();
+                async move {
+                    sqlx::query_as::<_, Article>("SELECT * FROM missing_table").fetch_all(&pool).await
+                }
+            },
+            |article: &Article| article.id,
+        );
+        let results = join_all((1..=3).map(|id| loader.load(id))).await;
+        for result in results {
+            let Err(Error::Database(err)) = result else { panic!("expected a database error") };
+            assert!(err.message().contains("missing_table"));
+            assert!(err.code().is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_having_without_group_by() {
+        use crate::common::error::QueryError;
+
+        let pool = create_memory_pool().await;
+        for title in ["a", "b", "c"] {
+            Insert::one(&Article::new(1513, title, None), &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let count_having = |min: i64| Select::<Article>::table()
+            .columns(|b| {
+                b.push("COUNT(*)");
+            })
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1513));
+            })
+            .having_bind("COUNT(*) > ?", DataKind::from(min)).unwrap()
+            .finish();
+        let mut qb = count_having(2);
+        assert_eq!(qb.sql(), "SELECT COUNT(*) FROM article WHERE tenant_id = ? HAVING COUNT(*) > ?");
+        let count: Option<i64> = qb.build_query_scalar().fetch_optional(&pool).await.unwrap();
+        assert_eq!(count, Some(3));
+        let count: Option<i64> = count_having(5).build_query_scalar().fetch_optional(&pool).await.unwrap();
+        assert_eq!(count, None);
+
+        let qb = Select::<Article>::table()
+            .having(|b| {
+                b.push("COUNT(*) > 1");
+            })
+            .finish();
+        assert!(qb.sql().ends_with(" FROM article HAVING COUNT(*) > 1"));
+
+        let mut qb = QB::new("SELECT EXISTS");
+        Subquery::<Article>::table()
+            .columns(|b| {
+                b.push("COUNT(*)");
+            })
+            .having(|b| {
+                b.push("COUNT(*) >= ").push_bind(DataKind::from(3));
+            })
+            .append_to(&mut qb);
+        assert_eq!(qb.sql().trim_end(), "SELECT EXISTS (SELECT COUNT(*) FROM article HAVING COUNT(*) >= ?)");
+        let exists: bool = qb.build_query_scalar().fetch_one(&pool).await.unwrap();
+        assert!(exists);
+
+        let err = Select::<Article>::table()
+            .having(|b| {
+                b.push("COUNT(*) > 1");
+            })
+            .group_by("tenant_id")
+            .err().unwrap();
+        assert!(QueryError::GroupByAfterHaving.matches(&err));
+        let err = Subquery::<Article>::table()
+            .having(|b| {
+                b.push("COUNT(*) > 1");
+            })
+            .group_by("tenant_id")
+            .err().unwrap();
+        assert!(QueryError::GroupByAfterHaving.matches(&err));
+    }
+
+    #[tokio::test]
+    async fn test_window() {
+        use crate::common::{error::QueryError, types::WindowFunc};
+
+        let qb = Select::<Article>::table()
+            .window(WindowFunc::row_number("rn").partition_by("tenant_id").order_by("views", Order::Desc)).unwrap()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1514));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "SELECT id, tenant_id, title, content, views, deleted, created_at, ROW_NUMBER() OVER (PARTITION BY tenant_id ORDER BY views DESC) AS rn FROM article WHERE tenant_id = ?"
+        );
+
+        let pool = create_memory_pool().await;
+        for (tenant_id, title, views) in [(1514, "low", 1), (1514, "high", 9), (1515, "only", 5)] {
+            let mut entity = Article::new(tenant_id, title, None);
+            entity.views = views;
+            Insert::one(&entity, &ARTICLE_KEY).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let mut qb = Select::<Article>::table()
+            .columns(|b| {
+                b.push("title, ")
+                    .push(WindowFunc::rank("pos").partition_by("tenant_id").order_by("views", Order::Desc).to_sql())
+                    .push(", ")
+                    .push(WindowFunc::lag("title", 1, "prev").partition_by("tenant_id").order_by("views", Order::Desc).to_sql());
+            })
+            .order_by("id", Order::Asc)
+            .finish();
+        let rows: Vec<(String, i64, Option<String>)> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        assert_eq!(rows, vec![
+            ("low".to_string(), 2, Some("high".to_string())),
+            ("high".to_string(), 1, None),
+            ("only".to_string(), 1, None),
+        ]);
+
+        let err = Select::<Article>::table()
+            .window(WindowFunc::row_number("rn")).unwrap()
+            .window(WindowFunc::rank("pos"))
+            .err().unwrap();
+        assert!(QueryError::ClauseAfterFrom("window".to_string()).matches(&err));
+        let err = Select::<Article>::table()
+            .columns(|b| {
+                b.push("id");
+            })
+            .window(WindowFunc::row_number("rn"))
+            .err().unwrap();
+        assert!(QueryError::ClauseAfterFrom("window".to_string()).matches(&err));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_scalar_opt() {
+        init_pool().await;
+        let filter_build_fn = |b: &mut QB| {
+            b.push("tenant_id = ").push_bind(DataKind::from(1515));
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        let max_id = || Select::<Article>::table()
+            .columns(|b| {
+                b.push("MAX(id)");
+            })
+            .filter(filter_build_fn)
+            .finish();
+        let first_title = || Select::<Article>::table()
+            .columns(|b| {
+                b.push("title");
+            })
+            .filter(filter_build_fn)
+            .finish();
+
+        assert_eq!(fetch_scalar_opt::<i64>(max_id()).await.unwrap(), None);
+        assert_eq!(fetch_scalar_opt::<String>(first_title()).await.unwrap(), None);
+        assert_eq!(fetch_scalar_optional(max_id()).await.unwrap(), None);
+
+        let result = execute(Insert::one(&Article::new(1515, "metric", None), &ARTICLE_KEY).unwrap()).await.unwrap();
+        assert_eq!(fetch_scalar_opt::<i64>(max_id()).await.unwrap(), Some(result.last_insert_rowid()));
+        assert_eq!(fetch_scalar_opt::<String>(first_title()).await.unwrap(), Some("metric".to_string()));
+        assert_eq!(fetch_scalar_optional(max_id()).await.unwrap(), Some(result.last_insert_rowid()));
+    }
+
+    #[tokio::test]
+    async fn test_update_with_join() {
+        let pool = create_memory_pool().await;
+        let result = Insert::one(&Article::new(1515, "parent", None), &ARTICLE_KEY).unwrap()
+            .build().execute(&pool).await.unwrap();
+        let article_id = result.last_insert_rowid() as i32;
+        for (seq, tag) in ["rust", "sql"].iter().enumerate() {
+            let mut article_tag = ArticleTag::new(tag);
+            article_tag.article_id = article_id;
+            article_tag.share_seq = seq as i32 + 1;
+            Insert::one(&article_tag, &PrimaryKey::Composite(&["article_id", "share_seq"])).unwrap()
+                .build().execute(&pool).await.unwrap();
+        }
+
+        let mut qb = Update::<ArticleTag>::with_join("article", "article.id = article_tag.article_id")
+            .custom(|b| {
+                b.push("tenant_id = article.tenant_id");
+            })
+            .filter(|b| {
+                b.push("article_tag.tag = ").push_bind(DataKind::from("rust"));
+            })
+            .finish();
+        assert_eq!(
+            qb.sql(),
+            "UPDATE article_tag SET tenant_id = article.tenant_id FROM article WHERE article.id = article_tag.article_id AND (article_tag.tag = ?)"
+        );
+        assert_eq!(qb.build().execute(&pool).await.unwrap().rows_affected(), 1);
+
+        let tenants: Vec<(String, i64)> = sqlx::query_as("SELECT tag, tenant_id FROM article_tag ORDER BY share_seq")
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(tenants, vec![("rust".to_string(), 1515), ("sql".to_string(), 0)]);
+
+        let qb = Update::<ArticleTag>::with_join("article", "article.id = article_tag.article_id")
+            .custom(|b| {
+                b.push("tenant_id = article.tenant_id");
+            })
+            .finish();
+        assert_eq!(qb.sql(), "UPDATE article_tag SET tenant_id = article.tenant_id FROM article WHERE article.id = article_tag.article_id");
+    }
+
+    #[tokio::test]
+    async fn test_find_or_create() {
+        init_pool().await;
+        let filter_build_fn = |b: &mut QB| {
+            b.push("tenant_id = ").push_bind(DataKind::from(1516));
+        };
+        execute(Delete::<Article>::table().filter(filter_build_fn).finish()).await.unwrap();
+        let key = PrimaryKey::Single("id", false);
+        let by_id = |id: i32| move |b: &mut QB| {
+            b.push("id = ").push_bind(DataKind::from(id));
+        };
+
+        let mut entity = Article::new(1516, "created", None);
+        entity.id = 15160;
+        let created = find_or_create(by_id(15160), &entity, &key).await.unwrap();
+        assert_eq!((created.id, created.title.as_str()), (15160, "created"));
+
+        entity.title = "ignored".to_string();
+        let existing = find_or_create(by_id(15160), &entity, &key).await.unwrap();
+        assert_eq!(existing, created);
+
+        let mut first = Article::new(1516, "first", None);
+        first.id = 15161;
+        let mut second = Article::new(1516, "second", None);
+        second.id = 15161;
+        let (a, b) = tokio::join!(
+            find_or_create(by_id(15161), &first, &key),
+            find_or_create(by_id(15161), &second, &key),
+        );
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert_eq!(a, b);
+        let rows = fetch_all::<Article>(Select::<Article>::table().filter(by_id(15161)).finish()).await.unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn test_having_bind_without_from() {
+        let qb = Select::<Article>::table()
+            .having_bind("COUNT(*) > ?", DataKind::from(0)).unwrap()
+            .finish();
+        assert!(qb.sql().starts_with("SELECT "));
+        assert!(qb.sql().ends_with(" FROM article HAVING COUNT(*) > ?"));
+    }
+
+    #[tokio::test]
+    async fn test_filter_tree_after_condition() {
+        let pool = create_memory_pool().await;
+        for (tenant_id, title, views) in [(1500, "mine", 1), (1501, "other", 99), (1501, "other-rust", 1)] {
+            let mut entity = Article::new(tenant_id, title, None);
+            entity.views = views;
+            Insert::one(&entity, &ARTICLE_KEY).unwrap().build().execute(&pool).await.unwrap();
+        }
+
+        let tree = FilterTree::leaf("views > ?", vec![DataKind::from(10)])
+            .or(FilterTree::leaf("title LIKE ? OR title = ?", vec![DataKind::from("%rust%"), DataKind::from("mine")]));
+        let mut qb = Select::<Article>::table()
+            .filter(|b| {
+                b.push("tenant_id = ").push_bind(DataKind::from(1500)).push(" AND ");
+                push_filter_tree(b, tree).unwrap();
+            })
+            .finish();
+        assert!(qb.sql().ends_with("WHERE tenant_id = ? AND ((views > ?) OR (title LIKE ? OR title = ?))"));
+        let rows: Vec<Article> = qb.build_query_as().fetch_all(&pool).await.unwrap();
+        let titles: Vec<_> = rows.into_iter().map(|a| a.title).collect();
+        assert_eq!(titles, vec!["mine"]);
+    }
+}
@@ -16,7 +16,8 @@ use sqlx::{Pool, Sqlite};
 use sqlx::{pool::PoolOptions, Error, SqlitePool};
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use tokio::sync::OnceCell;
 use std::time::Duration;
 
@@ -25,6 +26,9 @@ use crate::common::error::QueryError;
 // Global static variable to store the database connection pool
 static DB_POOL: OnceCell<Arc<SqlitePool>> = OnceCell::const_new();
 
+// Additional pools registered by name, e.g. one per shard
+static NAMED_POOLS: RwLock<Option<HashMap<String, Arc<SqlitePool>>>> = RwLock::new(None);
+
 /// Initialize the connection pool with a custom pool
 /// 
 /// # Arguments
@@ -99,4 +103,110 @@ pub fn get_db_pool() -> Result<Arc<SqlitePool>, Error> {
     DB_POOL.get()
         .cloned() // Clone the Arc to return a new reference
         .ok_or_else(||QueryError::DBPoolNotInitialized.into())
-}
\ No newline at end of file
+}
+
+/// Register a pool under a name, replacing any pool already registered with that name
+/// 
+/// Named pools are independent of the global pool and are used by queries that fan out
+/// over several databases, such as `fetch_all_sharded`.
+/// 
+/// # Arguments
+/// * `name` - Pool name, e.g. a shard id
+/// * `pool` - A pre-configured SQLite connection pool
+/// 
+/// 以指定名称注册连接池，同名的已注册连接池会被替换
+/// 
+/// 命名连接池独立于全局连接池，供在多个数据库上分发执行的查询使用，例如 `fetch_all_sharded`。
+/// 
+/// # 参数
+/// * `name` - 连接池名称，例如分片 id
+/// * `pool` - 预配置的 SQLite 连接池
+pub fn register_named_pool(name: impl Into<String>, pool: SqlitePool) {
+    let mut pools = NAMED_POOLS.write().unwrap_or_else(|e| e.into_inner());
+    pools.get_or_insert_with(HashMap::new).insert(name.into(), Arc::new(pool));
+}
+
+/// Remove a named pool
+/// 
+/// # Arguments
+/// * `name` - Pool name
+/// 
+/// # Returns
+/// The removed pool, if one was registered under the name
+/// 
+/// 移除命名连接池
+/// 
+/// # 参数
+/// * `name` - 连接池名称
+/// 
+/// # 返回值
+/// 被移除的连接池，如果该名称未注册则返回 None
+pub fn unregister_named_pool(name: &str) -> Option<Arc<SqlitePool>> {
+    let mut pools = NAMED_POOLS.write().unwrap_or_else(|e| e.into_inner());
+    pools.as_mut().and_then(|pools| pools.remove(name))
+}
+
+/// Gets a named pool
+/// 
+/// # Arguments
+/// * `name` - Pool name
+/// 
+/// # Returns
+/// A cloned Arc reference to the pool or an error if no pool is registered under the name
+/// 
+/// 获取命名连接池
+/// 
+/// # 参数
+/// * `name` - 连接池名称
+/// 
+/// # 返回值
+/// 连接池的克隆 Arc 引用，如果该名称未注册则返回错误
+pub fn get_named_pool(name: &str) -> Result<Arc<SqlitePool>, Error> {
+    let pools = NAMED_POOLS.read().unwrap_or_else(|e| e.into_inner());
+    pools.as_ref()
+        .and_then(|pools| pools.get(name).cloned())
+        .ok_or_else(|| QueryError::DBPoolNotInitialized.into())
+}
+
+/// Gets all named pools, sorted by name
+/// 
+/// 获取所有命名连接池，按名称排序
+pub fn named_pools() -> Vec<(String, Arc<SqlitePool>)> {
+    let pools = NAMED_POOLS.read().unwrap_or_else(|e| e.into_inner());
+    let mut pools: Vec<_> = pools.iter()
+        .flat_map(|pools| pools.iter().map(|(name, pool)| (name.clone(), pool.clone())))
+        .collect();
+    pools.sort_by(|a, b| a.0.cmp(&b.0));
+    pools
+}
+
+/// Execute DDL statements (e.g. schema setup for tests) on the global pool
+/// 
+/// Unlike a single query, `sql` may contain several statements. They are split naively
+/// on `;` and run in order on one connection, so semicolons inside string literals,
+/// comments or trigger/function bodies are not supported. Empty statements are skipped.
+/// 
+/// # Arguments
+/// * `sql` - One or more SQLite statements separated by `;`
+/// 
+/// # Returns
+/// Ok on success, or the Error of the first failing statement
+/// 
+/// 在全局连接池上执行 DDL 语句（例如测试中的表结构初始化）
+/// 
+/// 与单条查询不同，`sql` 可以包含多条语句。语句按 `;` 简单拆分并在同一连接上依次执行，
+/// 因此不支持字符串字面量、注释或触发器/函数体中的分号。空语句会被跳过。
+/// 
+/// # 参数
+/// * `sql` - 以 `;` 分隔的一条或多条 SQLite 语句
+/// 
+/// # 返回值
+/// 成功时返回 Ok，否则返回第一条失败语句的 Error
+pub async fn execute_ddl(sql: &str) -> Result<(), Error> {
+    let pool = get_db_pool()?;
+    let mut conn = pool.acquire().await?;
+    for statement in sql.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+        sqlx::query(statement).execute(&mut *conn).await?;
+    }
+    Ok(())
+}
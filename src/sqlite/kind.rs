@@ -13,6 +13,7 @@
 
 use std::any::Any;
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::error::Error;
 use std::sync::Arc;
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
@@ -22,7 +23,9 @@ use sqlx::types::Uuid;
 use sqlx::{Database, Encode, Sqlite, Type};
 use sqlx::sqlite::SqliteArgumentValue;
 
-use crate::common::conversion::{unwrap_option, ValueConvert};
+use crate::common::conversion::{unwrap_option, DbEnumInt, ValueConvert};
+#[cfg(feature = "json-fallback")]
+use crate::common::conversion::json_fallback;
 
 /// Enum representing different types of database field values.
 #[derive(Default, Debug, Clone, PartialEq)]
@@ -114,6 +117,10 @@ impl ValueConvert for DataKind {
                 $(if let Some(v) = unwrap_option::<$type>(value) {
                     return $variant(v);
                 })*
+                #[cfg(feature = "json-fallback")]
+                if let Some(json) = json_fallback(value) {
+                    return DataKind::Json(Arc::new(json));
+                }
                 return DataKind::Null;
             };
         }
@@ -149,6 +156,29 @@ impl ValueConvert for DataKind {
     }
 }
 
+/// Values are only comparable within the same variant, e.g. two cursor values of the same column;
+/// different variants (and unordered types such as JSON) yield `None`.
+///
+/// 仅同一变体内的值可比较，例如同一列的两个游标值；不同变体（以及 JSON 等无序类型）返回 `None`。
+impl PartialOrd for DataKind {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (DataKind::Text(a), DataKind::Text(b)) => a.partial_cmp(b),
+            (DataKind::Integer(a), DataKind::Integer(b)) => a.partial_cmp(b),
+            (DataKind::Real(a), DataKind::Real(b)) => a.partial_cmp(b),
+            (DataKind::DateTime(a), DataKind::DateTime(b)) => a.partial_cmp(b),
+            (DataKind::DateTimeUtc(a), DataKind::DateTimeUtc(b)) => a.partial_cmp(b),
+            (DataKind::Date(a), DataKind::Date(b)) => a.partial_cmp(b),
+            (DataKind::Time(a), DataKind::Time(b)) => a.partial_cmp(b),
+            (DataKind::Blob(a), DataKind::Blob(b)) => a.partial_cmp(b),
+            (DataKind::Bool(a), DataKind::Bool(b)) => a.partial_cmp(b),
+            (DataKind::Uuid(a), DataKind::Uuid(b)) => a.partial_cmp(b),
+            _ if self == other => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
 // Implement automatic conversion from common types to DataKind
 macro_rules! impl_from {
     ($type:ty, $variant:expr) => {
@@ -187,6 +217,13 @@ impl_from!(NaiveTime, DataKind::Time);
 impl_from!(Value, |value: Value| DataKind::Json(Arc::new(value)));
 impl_from!(Uuid, DataKind::Uuid);
 
+// Integer-backed enums are bound as their discriminant
+impl<E: DbEnumInt> From<E> for DataKind {
+    fn from(value: E) -> Self {
+        DataKind::Integer(value.to_i32() as i64)
+    }
+}
+
 impl<'a> From<DataKind> for Cow<'a, DataKind> {
     fn from(value: DataKind) -> Self {
         Cow::Owned(value)
@@ -3,3 +3,17 @@ pub async fn get_database_url() -> String {
     std::env::var("DATABASE_URL").expect("DATABASE_URL must be set")
 }
 
+
+/// Create an isolated in-memory SQLite pool with the article schema applied.
+#[cfg(feature = "sqlite")]
+pub async fn create_memory_pool() -> sqlx::SqlitePool {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let schema = include_str!("../../migrations/001_article.sql");
+    let sqlite_schema = schema.split("/* mysql: */").next().unwrap();
+    sqlx::raw_sql(sqlite_schema).execute(&pool).await.unwrap();
+    pool
+}